@@ -0,0 +1,60 @@
+extern crate toml;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const SECTIONS: [(&str, &str); 5] = [
+    ("mandatory", "MANDATORY"),
+    ("leg", "LEG"),
+    ("unique", "UNIQUE"),
+    ("repeated", "REPEATED"),
+    ("security", "SECURITY"),
+];
+
+fn main() {
+    let spec_path = "spec/bcbp.toml";
+    println!("cargo:rerun-if-changed={}", spec_path);
+
+    let src = fs::read_to_string(spec_path).expect("can't read BCBP spec");
+    let spec: toml::Table = src.parse().expect("invalid BCBP spec");
+
+    let mut out = String::new();
+
+    for &(section, name) in SECTIONS.iter() {
+        let items = spec.get(section)
+            .and_then(|v| v.as_array())
+            .unwrap_or_else(|| panic!("missing `{}` section in BCBP spec", section));
+
+        out.push_str(&format!("pub const {}: &[Item] = &[\n", name));
+
+        for item in items {
+            let int = |key: &str, default: Option<i64>| {
+                item.get(key)
+                    .and_then(|v| v.as_integer())
+                    .or(default)
+                    .unwrap_or_else(|| panic!("`{}` missing in `{}` item", key, section))
+            };
+            let name = item.get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or_else(|| panic!("`name` missing in `{}` item", section));
+            let charset = match item.get("charset").and_then(|v| v.as_str()).unwrap_or("f") {
+                "a"  => "Alpha",
+                "n"  => "Numeric",
+                "an" => "Alphanumeric",
+                "f"  => "Any",
+                other => panic!("unknown charset `{}` in `{}` item", other, section),
+            };
+
+            out.push_str(&format!(
+                "    Item {{ id: {}, name: {:?}, len: {}, since: {}, charset: Charset::{} }},\n",
+                int("id", None), name, int("len", None), int("since", Some(1)), charset
+            ));
+        }
+
+        out.push_str("];\n\n");
+    }
+
+    let dst = Path::new(&env::var("OUT_DIR").unwrap()).join("spec.rs");
+    fs::write(dst, out).expect("can't write generated BCBP spec");
+}
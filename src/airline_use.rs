@@ -0,0 +1,99 @@
+//! Decoders of documented airline individual use data
+//!
+//! Carriers lay out the airline use data of a leg (item 4) as they like.
+//! Where a layout is documented it can be decoded by fixed width fields
+//! with `FixedLayout`, and `register_airline_use!` adds one to an
+//! `ExtensionRegistry` in a single line.
+//!
+//! Layouts shipped with the crate sit behind a feature per airline, like
+//! `airline-sk`, so an application only carries the carriers it needs.
+//! `well_known()` registers every layout that was compiled in. To
+//! contribute one, add an `airline-xx` feature and a line to
+//! `well_known()`, with a pass from the carrier's documentation in the
+//! tests.
+//!
+//! ```
+//! #[macro_use]
+//! extern crate iata;
+//!
+//! use iata::bcbp::ExtensionRegistry;
+//!
+//! # fn main() {
+//! let mut registry = ExtensionRegistry::new();
+//! register_airline_use!(registry, "XX", "*", { "tier" => 1, "lounge" => 1 });
+//!
+//! assert!(registry.is_registered("XX"));
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use bcbp::{ConditionalExtension, ExtensionRegistry};
+
+/// Registers a `FixedLayout` for an airline: registry, airline, prefix and `"field" => width` pairs
+#[macro_export]
+macro_rules! register_airline_use {
+    ($registry:expr, $airline:expr, $prefix:expr, { $($name:expr => $len:expr),* $(,)* }) => {
+        $registry.register($airline, $crate::airline_use::FixedLayout::new($prefix, &[$(($name, $len)),*]))
+    };
+}
+
+/// Airline use data made of a prefix and fixed width fields
+///
+/// Data without the prefix isn't recognized. Fields are trimmed, the ones
+/// the data is too short for are left out, and data after the last field
+/// is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedLayout {
+    prefix: &'static str,
+    fields: Vec<(&'static str, usize)>,
+}
+
+impl FixedLayout {
+    pub fn new(prefix: &'static str, fields: &[(&'static str, usize)]) -> FixedLayout {
+        FixedLayout {
+            prefix,
+            fields: fields.to_vec(),
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.prefix
+    }
+
+    /// Field names with their widths, in the order of the data
+    pub fn fields(&self) -> &[(&'static str, usize)] {
+        &self.fields
+    }
+}
+
+impl ConditionalExtension for FixedLayout {
+    fn decode(&self, data: &str) -> Option<BTreeMap<String, String>> {
+        let mut rest = data.strip_prefix(self.prefix)?;
+        let mut ret = BTreeMap::new();
+
+        for &(name, len) in &self.fields {
+            let value = match rest.get(.. len) {
+                Some(value) => value,
+                None        => break,
+            };
+
+            ret.insert(name.to_string(), value.trim().to_string());
+            rest = &rest[len ..];
+        }
+
+        Some(ret)
+    }
+}
+
+/// Registry with the layouts of every airline feature enabled
+pub fn well_known() -> ExtensionRegistry {
+    #[allow(unused_mut)]
+    let mut registry = ExtensionRegistry::new();
+
+    // The sample pass of the implementation guide, `*306...`
+    #[cfg(feature = "airline-sk")]
+    register_airline_use!(registry, "SK", "*", { "marker" => 3 });
+
+    registry
+}
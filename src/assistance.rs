@@ -0,0 +1,180 @@
+//! Special assistance needs from SSR codes and boarding passes
+//!
+//! Reservations carry assistance requests as special service request codes,
+//! which passenger name lists repeat as `.R/` remarks. Some carriers also
+//! copy the codes into the airline use data of the pass. Both end up in one
+//! `AssistanceNeeds`, so ground staff see a single view.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::assistance::{Assistance, AssistanceNeeds};
+//!
+//! let needs = AssistanceNeeds::from_pnl_line("1SMITH/JOHNMR .R/WCHR HK1 .R/DEAF HK1");
+//! assert!(needs.contains(Assistance::WheelchairRamp));
+//! assert!(needs.needs_wheelchair());
+//! assert_eq!(needs.to_string(), "WCHR DEAF");
+//! ```
+
+use std::fmt;
+use std::collections::BTreeSet;
+
+use bcbp::{BCBP, Segment};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Assistance {
+    /// `WCHR`, can climb steps and walk in the cabin, not long distances
+    WheelchairRamp,
+    /// `WCHS`, can't climb steps, can walk in the cabin
+    WheelchairSteps,
+    /// `WCHC`, immobile, needs to be carried to the seat
+    WheelchairCabin,
+    /// `WCOB`, needs an onboard wheelchair
+    WheelchairOnBoard,
+    /// `WCMP`, travels with a manual wheelchair
+    ManualWheelchair,
+    /// `WCBD`, `WCBW` or `WCLB`, travels with a battery powered wheelchair
+    PoweredWheelchair,
+    /// `BLND`
+    Blind,
+    /// `DEAF`
+    Deaf,
+    /// `DPNA`, intellectual or developmental disability
+    Cognitive,
+    /// `MAAS`, meet and assist
+    MeetAndAssist,
+    /// `MEDA`, medical case
+    Medical,
+    /// `STCR`, stretcher
+    Stretcher,
+    /// `OXYG`, medical oxygen
+    Oxygen,
+    /// `SVAN` or `ESAN`, service or emotional support animal
+    Animal,
+}
+
+impl Assistance {
+    /// SSR code, the first one for needs with several codes
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Assistance::WheelchairRamp    => "WCHR",
+            Assistance::WheelchairSteps   => "WCHS",
+            Assistance::WheelchairCabin   => "WCHC",
+            Assistance::WheelchairOnBoard => "WCOB",
+            Assistance::ManualWheelchair  => "WCMP",
+            Assistance::PoweredWheelchair => "WCBD",
+            Assistance::Blind             => "BLND",
+            Assistance::Deaf              => "DEAF",
+            Assistance::Cognitive         => "DPNA",
+            Assistance::MeetAndAssist     => "MAAS",
+            Assistance::Medical           => "MEDA",
+            Assistance::Stretcher         => "STCR",
+            Assistance::Oxygen            => "OXYG",
+            Assistance::Animal            => "SVAN",
+        }
+    }
+
+    /// Need of an SSR code, `None` for codes unrelated to assistance
+    pub fn parse(code: &str) -> Option<Assistance> {
+        let ret = match code.trim().to_uppercase().as_ref() {
+            "WCHR" => Assistance::WheelchairRamp,
+            "WCHS" => Assistance::WheelchairSteps,
+            "WCHC" => Assistance::WheelchairCabin,
+            "WCOB" => Assistance::WheelchairOnBoard,
+            "WCMP" => Assistance::ManualWheelchair,
+            "WCBD" | "WCBW" | "WCLB" => Assistance::PoweredWheelchair,
+            "BLND" => Assistance::Blind,
+            "DEAF" => Assistance::Deaf,
+            "DPNA" => Assistance::Cognitive,
+            "MAAS" => Assistance::MeetAndAssist,
+            "MEDA" => Assistance::Medical,
+            "STCR" => Assistance::Stretcher,
+            "OXYG" => Assistance::Oxygen,
+            "SVAN" | "ESAN" => Assistance::Animal,
+            _ => return None,
+        };
+
+        Some(ret)
+    }
+
+    pub fn is_wheelchair(&self) -> bool {
+        matches!(*self,
+            Assistance::WheelchairRamp | Assistance::WheelchairSteps | Assistance::WheelchairCabin
+            | Assistance::WheelchairOnBoard | Assistance::ManualWheelchair | Assistance::PoweredWheelchair)
+    }
+}
+
+/// Set of assistance needs of a passenger, displayed as SSR codes separated by spaces
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssistanceNeeds {
+    needs: BTreeSet<Assistance>,
+}
+
+impl AssistanceNeeds {
+    pub fn new() -> AssistanceNeeds {
+        AssistanceNeeds::default()
+    }
+
+    /// Needs of SSR codes, unrelated codes are skipped
+    pub fn from_ssr_codes<I, S>(codes: I) -> AssistanceNeeds
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        AssistanceNeeds {
+            needs: codes.into_iter().filter_map(|c| Assistance::parse(c.as_ref())).collect(),
+        }
+    }
+
+    /// Needs of the `.R/` remarks of a passenger name list entry
+    pub fn from_pnl_line(line: &str) -> AssistanceNeeds {
+        AssistanceNeeds::from_ssr_codes(line.split(".R/").skip(1).filter_map(|remark| remark.get(.. 4)))
+    }
+
+    /// Needs found as SSR codes in the airline use data of the leg
+    pub fn from_segment(segment: &Segment) -> AssistanceNeeds {
+        let data = segment.airline_data().unwrap_or("");
+        AssistanceNeeds::from_ssr_codes(data.split(|c: char| !c.is_ascii_alphanumeric()))
+    }
+
+    /// Needs found in the airline use data of any leg of the pass
+    pub fn from_bcbp(bcbp: &BCBP) -> AssistanceNeeds {
+        let mut ret = AssistanceNeeds::new();
+
+        for s in bcbp.segments() {
+            ret.merge(&AssistanceNeeds::from_segment(s));
+        }
+
+        ret
+    }
+
+    pub fn insert(&mut self, need: Assistance) {
+        self.needs.insert(need);
+    }
+
+    /// Adds the needs of another source, like the PNL entry of a scanned passenger
+    pub fn merge(&mut self, other: &AssistanceNeeds) {
+        self.needs.extend(other.needs.iter().cloned());
+    }
+
+    pub fn contains(&self, need: Assistance) -> bool {
+        self.needs.contains(&need)
+    }
+
+    pub fn needs_wheelchair(&self) -> bool {
+        self.needs.iter().any(Assistance::is_wheelchair)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Assistance> + '_ {
+        self.needs.iter().cloned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.needs.is_empty()
+    }
+}
+
+impl fmt::Display for AssistanceNeeds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let codes: Vec<&str> = self.needs.iter().map(Assistance::code).collect();
+        f.write_str(&codes.join(" "))
+    }
+}
@@ -0,0 +1,247 @@
+//! Tamper evident log of boarding scans for offline gates
+//!
+//! Every entry holds the SHA-256 of the entry before it, so changing,
+//! removing or reordering entries breaks the chain from that point on.
+//! The log is written as one line per entry, with tab separated index,
+//! scan time, device, location, previous hash, hash and pass data. An
+//! entry hash covers the same fields, joined with the ASCII unit separator
+//! (0x1F). The first entry follows a hash of 64 zeros.
+//!
+//! The chain shows tampering, it doesn't prevent rewriting the whole log.
+//! Ship `head()` off the device now and then to pin it.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::audit::BoardingLog;
+//! use iata::bcbp::BCBP;
+//! use iata::scan::ScanEvent;
+//!
+//! let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+//!
+//! let mut log = BoardingLog::new();
+//! log.append(&ScanEvent::new(bcbp, 1483272000, "GATE-B12-1")).unwrap();
+//!
+//! let mut file = Vec::new();
+//! log.write_to(&mut file).unwrap();
+//!
+//! let read = BoardingLog::read_from(&file[..]).unwrap();
+//! assert_eq!(read.head(), log.head());
+//! ```
+
+use std::fmt;
+use std::error;
+use std::io::{self, BufRead, Write};
+
+use sha2::{Digest, Sha256};
+
+use scan::ScanEvent;
+
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AuditError {
+    /// The underlying reader or writer failed
+    Io(io::Error),
+    /// Scan has a tab or a line break in a field, which the log can't hold
+    Field(&'static str),
+    /// Line of the log is not an entry
+    Malformed { line: u64 },
+    /// Entry doesn't follow the one before it
+    Chain { index: u64 },
+    /// Entry doesn't match its hash
+    Hash { index: u64 },
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AuditError::Io(ref e)           => write!(f, "{}", e),
+            AuditError::Field(field)        => write!(f, "{} contains a tab or a line break", field),
+            AuditError::Malformed { line }  => write!(f, "line {} is not a log entry", line),
+            AuditError::Chain { index }     => write!(f, "entry {} doesn't follow the previous entry", index),
+            AuditError::Hash { index }      => write!(f, "entry {} doesn't match its hash", index),
+        }
+    }
+}
+
+impl error::Error for AuditError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            AuditError::Io(ref e) => Some(e),
+            _                     => None,
+        }
+    }
+}
+
+impl From<io::Error> for AuditError {
+    fn from(e: io::Error) -> AuditError {
+        AuditError::Io(e)
+    }
+}
+
+/// Accepted scan as recorded in the log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Position in the log, counted from 0
+    pub index: u64,
+    /// Unix time in seconds
+    pub scanned_at: i64,
+    pub device_id: String,
+    pub location: Option<String>,
+    /// Pass data as built from the scanned pass
+    pub data: String,
+    /// Lowercase hex hash of the previous entry
+    pub prev_hash: String,
+    /// Lowercase hex hash of this entry
+    pub hash: String,
+}
+
+impl LogEntry {
+    fn digest(&self) -> String {
+        let message = format!(
+            "{}\x1f{}\x1f{}\x1f{}\x1f{}\x1f{}",
+            self.prev_hash,
+            self.index,
+            self.scanned_at,
+            self.device_id,
+            self.location.as_ref().map(|s| s.as_ref()).unwrap_or(""),
+            self.data,
+        );
+
+        Sha256::digest(message.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn parse(line: &str) -> Option<LogEntry> {
+        let mut fields = line.splitn(7, '\t');
+
+        Some(LogEntry {
+            index: fields.next()?.parse().ok()?,
+            scanned_at: fields.next()?.parse().ok()?,
+            device_id: fields.next()?.into(),
+            location: Some(fields.next()?).filter(|s| !s.is_empty()).map(String::from),
+            prev_hash: fields.next()?.into(),
+            hash: fields.next()?.into(),
+            data: fields.next()?.into(),
+        })
+    }
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.index,
+            self.scanned_at,
+            self.device_id,
+            self.location.as_ref().map(|s| s.as_ref()).unwrap_or(""),
+            self.prev_hash,
+            self.hash,
+            self.data)
+    }
+}
+
+/// Append only chain of accepted scans
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoardingLog {
+    entries: Vec<LogEntry>,
+}
+
+impl BoardingLog {
+    pub fn new() -> BoardingLog {
+        BoardingLog::default()
+    }
+
+    /// Records an accepted scan, redact it first if the log must not hold passenger data
+    pub fn append(&mut self, scan: &ScanEvent) -> Result<&LogEntry, AuditError> {
+        let data = scan.bcbp.expect_build();
+        let location = scan.location.as_ref().map(|s| s.as_ref()).unwrap_or("");
+
+        for &(name, value) in [("device id", scan.device_id.as_ref()), ("location", location), ("pass data", data.as_ref())].iter() {
+            if value.contains(['\t', '\r', '\n']) {
+                return Err(AuditError::Field(name))
+            }
+        }
+
+        let mut entry = LogEntry {
+            index: self.entries.len() as u64,
+            scanned_at: scan.scanned_at,
+            device_id: scan.device_id.clone(),
+            location: scan.location.clone(),
+            data,
+            prev_hash: self.head().into(),
+            hash: String::new(),
+        };
+
+        entry.hash = entry.digest();
+        self.entries.push(entry);
+
+        Ok(&self.entries[self.entries.len() - 1])
+    }
+
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+
+    /// Hash of the last entry, 64 zeros for an empty log
+    pub fn head(&self) -> &str {
+        self.entries.last().map(|e| e.hash.as_ref()).unwrap_or(GENESIS)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Checks the chain, the error points at the first entry that doesn't fit
+    pub fn verify(&self) -> Result<(), AuditError> {
+        let mut prev = GENESIS;
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            if entry.index != i as u64 || entry.prev_hash != prev {
+                return Err(AuditError::Chain { index: i as u64 })
+            }
+
+            if entry.digest() != entry.hash {
+                return Err(AuditError::Hash { index: i as u64 })
+            }
+
+            prev = &entry.hash;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the log one entry per line
+    pub fn write_to<W: Write>(&self, mut w: W) -> Result<(), AuditError> {
+        for entry in &self.entries {
+            writeln!(w, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a log written by `write_to()` and verifies the chain
+    pub fn read_from<R: BufRead>(r: R) -> Result<BoardingLog, AuditError> {
+        let mut ret = BoardingLog::new();
+
+        for (i, line) in r.lines().enumerate() {
+            let line = line?;
+
+            if line.is_empty() {
+                continue
+            }
+
+            let entry = LogEntry::parse(&line).ok_or(AuditError::Malformed { line: i as u64 + 1 })?;
+            ret.entries.push(entry);
+        }
+
+        ret.verify()?;
+        Ok(ret)
+    }
+}
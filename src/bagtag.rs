@@ -0,0 +1,193 @@
+//! Baggage tag licence plates, IATA Resolution 740
+//!
+//! A licence plate is 10 digits: leading digit, 3 digit airline numeric code
+//! and 6 digit serial number.
+
+use std::fmt;
+
+/// Meaning of the leading digit of a licence plate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum TagCategory {
+    /// Tag issued for interline (and online) checked baggage
+    Interline,
+    /// Tag printed in fallback mode while the DCS is unavailable
+    Fallback,
+    /// Rush tag for mishandled baggage forwarding
+    Rush,
+    /// Leading digits reserved for airline use
+    Other(u8),
+}
+
+impl TagCategory {
+    pub fn digit(&self) -> u8 {
+        match *self {
+            TagCategory::Interline => 0,
+            TagCategory::Fallback  => 1,
+            TagCategory::Rush      => 2,
+            TagCategory::Other(d)  => d,
+        }
+    }
+
+    pub fn from_digit(d: u8) -> TagCategory {
+        match d {
+            0 => TagCategory::Interline,
+            1 => TagCategory::Fallback,
+            2 => TagCategory::Rush,
+            d => TagCategory::Other(d),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LicensePlate {
+    leading: u8,
+    airline: u16,
+    serial: u32,
+}
+
+impl LicensePlate {
+    /// `None` unless the digit, airline code and serial fit their widths
+    pub fn new(category: TagCategory, airline: u16, serial: u32) -> Option<LicensePlate> {
+        if category.digit() > 9 || airline > 999 || serial > 999_999 {
+            return None
+        }
+
+        Some(LicensePlate {
+            leading: category.digit(),
+            airline,
+            serial,
+        })
+    }
+
+    /// Parses the 10 digit form
+    pub fn parse(src: &str) -> Option<LicensePlate> {
+        if src.len() != 10 || !src.chars().all(|c| c.is_ascii_digit()) {
+            return None
+        }
+
+        LicensePlate::new(
+            TagCategory::from_digit(src[0..1].parse().ok()?),
+            src[1..4].parse().ok()?,
+            src[4..10].parse().ok()?,
+        )
+    }
+
+    pub fn category(&self) -> TagCategory {
+        TagCategory::from_digit(self.leading)
+    }
+
+    pub fn airline(&self) -> u16 {
+        self.airline
+    }
+
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+}
+
+impl fmt::Display for LicensePlate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{:03}{:06}", self.leading, self.airline, self.serial)
+    }
+}
+
+/// Block of serial numbers assigned to an airline, allocated in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagRange {
+    category: TagCategory,
+    airline: u16,
+    next: u32,
+    last: u32,
+}
+
+impl TagRange {
+    /// Range of serials from `first` to `last` inclusive
+    pub fn new(category: TagCategory, airline: u16, first: u32, last: u32) -> Option<TagRange> {
+        LicensePlate::new(category, airline, last)?;
+
+        if first > last {
+            return None
+        }
+
+        Some(TagRange {
+            category,
+            airline,
+            next: first,
+            last,
+        })
+    }
+
+    /// Allocates the next tag, `None` once the range is exhausted
+    pub fn allocate(&mut self) -> Option<LicensePlate> {
+        if self.next > self.last {
+            return None
+        }
+
+        let ret = LicensePlate::new(self.category, self.airline, self.next);
+        self.next += 1;
+        ret
+    }
+
+    pub fn remaining(&self) -> u32 {
+        (self.last + 1).saturating_sub(self.next)
+    }
+}
+
+/// Licence plate with the number of consecutive tags issued after it, items 23, 31 and 32
+///
+/// DCS systems allocate the tags of a passenger's bags consecutively, so the
+/// pass only carries the first plate and a 3 digit count of the following ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TagSequence {
+    first: LicensePlate,
+    following: u16,
+}
+
+impl TagSequence {
+    pub fn new(first: LicensePlate, following: u16) -> TagSequence {
+        TagSequence {
+            first,
+            following,
+        }
+    }
+
+    /// Parses the 13 digit item, `None` when it is blank or malformed
+    pub fn parse(src: &str) -> Option<TagSequence> {
+        if src.len() != 13 || !src.chars().all(|c| c.is_ascii_digit()) {
+            return None
+        }
+
+        Some(TagSequence {
+            first: LicensePlate::parse(&src[.. 10])?,
+            following: src[10 ..].parse().ok()?,
+        })
+    }
+
+    pub fn first(&self) -> LicensePlate {
+        self.first
+    }
+
+    /// Number of tags in the sequence, the first one included
+    pub fn count(&self) -> usize {
+        self.following as usize + 1
+    }
+
+    /// All plates of the sequence, stopping early if serials run out
+    pub fn expand(&self) -> Vec<LicensePlate> {
+        let first = self.first;
+
+        (0 .. self.count() as u32)
+            .map_while(|i| LicensePlate::new(first.category(), first.airline(), first.serial() + i))
+            .collect()
+    }
+}
+
+impl fmt::Display for TagSequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{:03}", self.first, self.following)
+    }
+}
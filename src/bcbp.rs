@@ -1,27 +1,86 @@
 use std::str;
-use std::u32;
-use std::usize;
-use self::str::FromStr;
+use std::fmt;
+use std::collections::{BTreeMap, HashMap};
 
-use nom::{IResult, ErrorKind, alpha, alphanumeric, digit, space, anychar, rest_s};
-use chrono::Duration;
+#[cfg(feature = "chrono")]
 pub use chrono::prelude::*;
 
-#[derive(Debug, PartialEq)]
-pub enum Error {
-    DataLength,
-    FormatCode,
-    SegmentsCount,
-    Format,
-    Name,
-    Date,
-    CoditionalData,
-    CoditionalDataSize,
-    SecurityDataSize,
-    SecurityData,
+use clock::{Clock, SystemClock};
+use redact::{self, Redacted, RedactionRules};
+use pax_status::PaxStatus;
+use names::{self, NAME_LEN};
+use bagtag::TagSequence;
+use flight::{CityPair, RouteType};
+use seatmap::{Seat, SeatError, SeatMap};
+use date::{self, DateError};
+use datetime;
+
+pub mod parser;
+pub mod index;
+mod error;
+mod parse;
+mod build;
+mod field;
+mod security;
+mod encoded;
+mod context;
+mod reservation;
+mod tier;
+mod doc_check;
+mod template;
+mod sequence;
+mod strip;
+mod validate;
+mod items;
+mod unique;
+mod repeated;
+#[cfg(feature = "serde")]
+pub mod schema;
+#[cfg(feature = "crypto")]
+mod identity;
+#[cfg(feature = "crypto")]
+mod token;
+#[cfg(feature = "instrument")]
+mod instrument;
+
+pub use self::error::{Error, BuildError, CapacityWarning, Warning};
+pub use self::parse::{ParseMode, ParseOptions};
+pub use self::build::write_to;
+pub use self::field::{TicketIndicator, PassengerDescription, Source, IssueDate, Version};
+pub use self::security::SecuritySection;
+pub use self::reservation::{ReservationSummary, ReservationField, ReservationMatch};
+pub use self::context::ErrorContext;
+pub use self::tier::{Alliance, FfTier};
+pub use self::doc_check::DocCheckReason;
+pub use self::template::{PassTemplate, TemplateError, Issue};
+pub use self::sequence::{SequenceAllocator, MemoryAllocator, SequenceError, MAX_SEQUENCE};
+pub use self::strip::Stripped;
+pub use self::validate::{validate_only, Summary};
+pub use self::unique::ConditionalUnique;
+pub use self::repeated::SegmentConditional;
+#[cfg(feature = "crypto")]
+pub use self::token::token_window;
+#[cfg(feature = "instrument")]
+pub use self::instrument::{CountingAllocator, ParseStats, SectionSizes};
+
+/// Legs a single barcode can encode
+const MAX_SEGMENTS: usize = 9;
+
+/// Application supplied data about a flight segment
+///
+/// Never read from or written to the barcode, it is only carried along with
+/// the segment so exports can include it.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SegmentMetadata {
+    pub terminal: Option<String>,
+    pub gate: Option<String>,
+    pub boarding_time: Option<date::Time>,
+    pub status: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Segment {
     pnr: String,
     src_airport: String,
@@ -33,6 +92,42 @@ pub struct Segment {
     seat: String,
     sequence: u32,
     pax_status: String,
+    airline_data: Option<String>,
+    conditional: SegmentConditional,
+    metadata: Option<SegmentMetadata>,
+    items: BTreeMap<u16, String>,
+}
+
+impl fmt::Debug for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Segment")
+            .field("pnr", &Redacted(&self.pnr))
+            .field("src_airport", &self.src_airport)
+            .field("dst_airport", &self.dst_airport)
+            .field("airline", &self.airline)
+            .field("flight_code", &self.flight_code)
+            .field("flight_day", &self.flight_day)
+            .field("compartment", &self.compartment)
+            .field("seat", &self.seat)
+            .field("sequence", &self.sequence)
+            .field("pax_status", &self.pax_status)
+            .field("airline_data", &Redacted(&self.airline_data))
+            .field("conditional", &self.conditional)
+            .field("metadata", &self.metadata)
+            .field("items", &redacted_items(&self.items))
+            .finish()
+    }
+}
+
+/// Raw items set with `add_item()` may hold any passenger data, only their numbers are shown
+fn redacted_items(items: &BTreeMap<u16, String>) -> BTreeMap<u16, Redacted<&String>> {
+    items.iter().map(|(&id, value)| (id, Redacted(value))).collect()
+}
+
+impl Default for Segment {
+    fn default() -> Segment {
+        Segment::new()
+    }
 }
 
 impl Segment {
@@ -48,6 +143,10 @@ impl Segment {
             seat: String::new(),
             sequence: 0,
             pax_status: String::new(),
+            airline_data: None,
+            conditional: SegmentConditional::default(),
+            metadata: None,
+            items: BTreeMap::new(),
         }
     }
 
@@ -67,6 +166,18 @@ impl Segment {
         self.dst_airport.as_ref()
     }
 
+    /// From and to airports of the leg
+    pub fn city_pair(&self) -> CityPair {
+        CityPair::new(&self.src_airport, &self.dst_airport)
+    }
+
+    /// Whether the leg crosses a border, `None` if an airport is unknown to the lookup
+    pub fn is_international<F, C>(&self, country_of: F) -> Option<bool>
+        where F: Fn(&str) -> Option<C>, C: PartialEq
+    {
+        self.city_pair().is_domestic(country_of).map(|domestic| !domestic)
+    }
+
     pub fn flight_code(&self) -> &str {
         self.flight_code.as_ref()
     }
@@ -75,24 +186,75 @@ impl Segment {
         self.flight_day
     }
 
-    pub fn flight_date(&self, year: i32) -> NaiveDate {
+    #[deprecated(note = "use `try_flight_date()`")]
+    pub fn flight_date(&self, year: i32) -> Result<date::Date, DateError> {
+        self.try_flight_date(year)
+    }
+
+    /// Date of the flight in the given year
+    ///
+    /// Fails when the flight day is not set or does not exist in the year,
+    /// day 366 is only valid in leap years.
+    pub fn try_flight_date(&self, year: i32) -> Result<date::Date, DateError> {
+        if self.flight_day == 0 {
+            return Err(DateError::Missing)
+        }
+
+        date::from_ordinal(year, self.flight_day)
+            .ok_or(DateError::InvalidDay { year, day: self.flight_day })
+    }
 
-        let day = if self.flight_day > 0 && self.flight_day < 366 { self.flight_day } else { 1 };
+    /// Date of the flight in the given year
+    ///
+    /// # Panics
+    ///
+    /// When the flight day is not set or does not exist in the year, see
+    /// `try_flight_date()`.
+    #[track_caller]
+    pub fn expect_flight_date(&self, year: i32) -> date::Date {
+        match self.try_flight_date(year) {
+            Ok(date) => date,
+            Err(e)   => panic!("{}", e),
+        }
+    }
 
-        NaiveDate::from_yo(year, day)
+    pub fn flight_date_opt(&self, year: i32) -> Option<date::Date> {
+        self.try_flight_date(year).ok()
     }
 
-    pub fn flight_date_current_year(&self) -> NaiveDate {
-        let now = Utc::today();
+    #[deprecated(note = "use `try_flight_date_current_year()`")]
+    pub fn flight_date_current_year(&self) -> Result<date::Date, DateError> {
+        self.try_flight_date_current_year()
+    }
 
-        self.flight_date(now.year())
+    #[deprecated(note = "use `try_flight_date_current_year_with()`")]
+    pub fn flight_date_current_year_with<C: Clock>(&self, clock: &C) -> Result<date::Date, DateError> {
+        self.try_flight_date_current_year_with(clock)
+    }
+
+    /// # Panics
+    ///
+    /// Same as `expect_flight_date()`, see `try_flight_date_current_year()`.
+    #[track_caller]
+    pub fn expect_flight_date_current_year(&self) -> date::Date {
+        self.expect_flight_date(date::year(&SystemClock.today()))
+    }
+
+    /// Date of the flight in the current year, see `try_flight_date()`
+    pub fn try_flight_date_current_year(&self) -> Result<date::Date, DateError> {
+        self.try_flight_date_current_year_with(&SystemClock)
+    }
+
+    /// Same as `try_flight_date_current_year()`, but takes "today" from the given clock
+    pub fn try_flight_date_current_year_with<C: Clock>(&self, clock: &C) -> Result<date::Date, DateError> {
+        self.try_flight_date(date::year(&clock.today()))
     }
 
     pub fn flight_day_aligned(&self) -> String {
         if self.flight_day == 0 {
             return String::new()
         }
-        format!("{:0>3}", self.flight_day).into()
+        format!("{:0>3}", self.flight_day)
     }
 
     pub fn compartment(&self) -> char {
@@ -104,10 +266,19 @@ impl Segment {
     }
 
     pub fn seat_aligned(&self) -> String {
-        if self.seat.len() == 0 {
+        if self.seat.is_empty() {
             return String::new()
         }
-        format!("{:0>4}", self.seat).into()
+        format!("{:0>4}", self.seat)
+    }
+
+    /// Checks the seat against the seat map of the aircraft, `None` for a blank seat
+    pub fn check_seat(&self, map: &SeatMap) -> Result<Option<Seat>, SeatError> {
+        if self.seat.trim().is_empty() {
+            return Ok(None)
+        }
+
+        map.check(&self.seat).map(Some)
     }
 
     pub fn sequence(&self) -> u32 {
@@ -118,30 +289,134 @@ impl Segment {
         if self.sequence == 0 {
             return String::new()
         }
-        format!("{:0>4}", self.sequence).into()
+        format!("{:0>4}", self.sequence)
     }
 
     pub fn pax_status(&self) -> &str {
         self.pax_status.as_ref()
     }
+
+    /// Passenger status, `None` when the item is blank
+    pub fn passenger_status(&self) -> Option<PaxStatus> {
+        self.pax_status.chars().next().map(PaxStatus::from)
+    }
+
+    /// Raw data for individual airline use which follows the conditional items
+    pub fn airline_data(&self) -> Option<&str> {
+        self.airline_data.as_ref().map(|s| s.as_ref())
+    }
+
+    /// Repeated conditional items of the leg
+    pub fn conditional(&self) -> &SegmentConditional {
+        &self.conditional
+    }
+
+    /// Marketing carrier designator, item 19, when the leg was sold under another airline's code
+    pub fn marketing_airline(&self) -> Option<&str> {
+        self.conditional.marketing_airline()
+    }
+
+    pub fn frequent_flyer_airline(&self) -> Option<&str> {
+        self.conditional.frequent_flyer_airline()
+    }
+
+    pub fn frequent_flyer_number(&self) -> Option<&str> {
+        self.conditional.frequent_flyer_number()
+    }
+
+    pub fn metadata(&self) -> Option<&SegmentMetadata> {
+        self.metadata.as_ref()
+    }
+
+    pub fn set_metadata(&mut self, metadata: Option<SegmentMetadata>) {
+        self.metadata = metadata;
+    }
+}
+
+/// Decoder for the airline individual use data of a single carrier
+pub trait ConditionalExtension {
+    /// Splits raw airline use data into named fields, `None` if the data isn't recognized
+    fn decode(&self, data: &str) -> Option<BTreeMap<String, String>>;
+}
+
+impl<F> ConditionalExtension for F where F: Fn(&str) -> Option<BTreeMap<String, String>> {
+    fn decode(&self, data: &str) -> Option<BTreeMap<String, String>> {
+        self(data)
+    }
+}
+
+/// Set of `ConditionalExtension`s keyed by airline designator
+#[derive(Default)]
+pub struct ExtensionRegistry {
+    decoders: HashMap<String, Box<dyn ConditionalExtension>>,
+}
+
+impl ExtensionRegistry {
+    pub fn new() -> ExtensionRegistry {
+        ExtensionRegistry::default()
+    }
+
+    /// Registers a decoder for the airline, replacing a previous one
+    ///
+    /// Designators are matched regardless of case and padding.
+    pub fn register<E>(&mut self, airline: &str, decoder: E)
+        where E: ConditionalExtension + 'static
+    {
+        self.decoders.insert(names::normalize(airline), Box::new(decoder));
+    }
+
+    pub fn is_registered(&self, airline: &str) -> bool {
+        self.decoders.contains_key(&names::normalize(airline))
+    }
+
+    /// Decodes airline use data of the segment with the decoder of its operating carrier
+    pub fn decode(&self, segment: &Segment) -> Option<BTreeMap<String, String>> {
+        let data = segment.airline_data()?;
+
+        self.decoders.get(&names::normalize(segment.airline()))?.decode(data)
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BCBP {
-    ticket_flag: char,
+    ticket_indicator: TicketIndicator,
     name_first: String,
     name_last: String,
-    pub segments: Vec<Segment>,
-    conditional_version: Option<char>,
+    segments: Vec<Segment>,
     conditional_data: Option<String>,
-    pax_type: Option<char>,
-    doc_type: Option<char>,
-    checkin_src: Option<char>,
-    boardingpass_src: Option<char>,
-    boardingpass_day: Option<u32>,
-    boardingpass_airline: Option<String>,
-    security_data_type: Option<char>,
-    security_data: Option<String>,
+    unique: ConditionalUnique,
+    security: Option<SecuritySection>,
+    trailing: Option<String>,
+    symbology: Option<String>,
+    items: BTreeMap<u16, String>,
+    warnings: Vec<Warning>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    original: Option<build::Original>,
+}
+
+impl fmt::Debug for BCBP {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BCBP")
+            .field("ticket_indicator", &self.ticket_indicator)
+            .field("name_first", &Redacted(&self.name_first))
+            .field("name_last", &Redacted(&self.name_last))
+            .field("segments", &self.segments)
+            .field("conditional_data", &Redacted(&self.conditional_data))
+            .field("unique", &self.unique)
+            .field("security", &self.security)
+            .field("trailing", &Redacted(&self.trailing))
+            .field("symbology", &self.symbology)
+            .field("items", &redacted_items(&self.items))
+            .field("warnings", &self.warnings)
+            .finish()
+    }
+}
+
+impl Default for BCBP {
+    fn default() -> BCBP {
+        BCBP::new()
+    }
 }
 
 impl BCBP {
@@ -150,25 +425,48 @@ impl BCBP {
         BCBP {
             name_first: String::new(),
             name_last:  String::new(),
-            ticket_flag: ' ',
+            ticket_indicator: TicketIndicator::Other(' '),
             segments: Vec::new(),
-            conditional_version: None,
             conditional_data: None,
-            pax_type: None,
-            doc_type: None,
-            checkin_src: None,
-            boardingpass_src: None,
-            boardingpass_day: None,
-            boardingpass_airline: None,
-            security_data_type: None,
-            security_data: None,
+            unique: ConditionalUnique::default(),
+            security: None,
+            trailing: None,
+            symbology: None,
+            items: BTreeMap::new(),
+            warnings: Vec::new(),
+            original: None,
         }
     }
 
+    /// Passenger name as it is encoded in the 20 character name field
+    ///
+    /// Characters outside of ASCII can't be represented in the barcode and are
+    /// replaced with `?`, see `name_truncated()` to detect a cut name.
     pub fn name(&self) -> String {
-        let mut tmp = format!("{}/{}", self.name_last, self.name_first);
-        tmp.truncate(20);
-        tmp
+        self.name_field().0
+    }
+
+    /// Whether `LAST/FIRST` does not fit into the name field
+    pub fn name_truncated(&self) -> bool {
+        self.name_field().1
+    }
+
+    fn name_field(&self) -> (String, bool) {
+        let full = if self.name_first.is_empty() {
+            self.name_last.clone()
+        } else {
+            format!("{}/{}", self.name_last, self.name_first)
+        };
+
+        let mut name: String = full.chars()
+            .map(|c| if c.is_ascii() { c } else { '?' })
+            .collect();
+
+        let truncated = name.len() > NAME_LEN;
+        // Only ASCII is left, so byte length matches char count
+        name.truncate(NAME_LEN);
+
+        (name, truncated)
     }
 
     pub fn name_last(&self) -> &str {
@@ -179,347 +477,294 @@ impl BCBP {
         self.name_first.as_ref()
     }
 
+    pub fn electronic_ticket_indicator(&self) -> TicketIndicator {
+        self.ticket_indicator
+    }
+
+    #[deprecated(note = "use `electronic_ticket_indicator()`")]
     pub fn ticket_flag(&self) -> char {
-        self.ticket_flag
+        self.ticket_indicator.as_char()
     }
 
-    pub fn segments_count(&self) -> u8 {
-        let mut cnt = self.segments.len();
-        if cnt > 9 {
-            cnt = 9;
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Domestic, international or mixed itinerary, `None` if an airport is unknown to the lookup
+    pub fn route_type<F, C>(&self, country_of: F) -> Option<RouteType>
+        where F: Fn(&str) -> Option<C>, C: PartialEq
+    {
+        let pairs: Vec<CityPair> = self.segments.iter().map(Segment::city_pair).collect();
+        RouteType::of(&pairs, country_of)
+    }
+
+    /// Legs for in place changes, the number of legs stays the same
+    pub fn segments_mut(&mut self) -> &mut [Segment] {
+        &mut self.segments
+    }
+
+    /// Appends a leg, fails with `Error::SegmentsCount` when the pass already has 9
+    pub fn add_segment(&mut self, segment: Segment) -> Result<(), Error> {
+        if self.segments.len() >= MAX_SEGMENTS {
+            return Err(Error::SegmentsCount)
         }
-        cnt as u8
+
+        self.segments.push(segment);
+        Ok(())
+    }
+
+    pub fn remove_segment(&mut self, index: usize) -> Option<Segment> {
+        if index >= self.segments.len() {
+            return None
+        }
+
+        Some(self.segments.remove(index))
     }
 
+    pub fn segments_count(&self) -> u8 {
+        self.segments.len().min(MAX_SEGMENTS) as u8
+    }
+
+    /// Version of the conditional items, `None` for mandatory only passes
+    pub fn conditional_version(&self) -> Option<char> {
+        self.unique.version()
+    }
+
+    /// Version of the conditional items, which selects the layout they are read with
+    pub fn version(&self) -> Option<Version> {
+        self.unique.version().map(Version::from)
+    }
+
+    #[deprecated(note = "use `conditional_version()`")]
     pub fn conditional_verion(&self) -> char {
-        self.ticket_flag
+        self.unique.version().unwrap_or(' ')
+    }
+
+    /// Unique conditional items, empty for mandatory only passes
+    pub fn conditional_unique(&self) -> &ConditionalUnique {
+        &self.unique
+    }
+
+    pub fn passenger_description(&self) -> Option<PassengerDescription> {
+        self.unique.passenger_description()
     }
 
+    #[deprecated(note = "use `passenger_description()`")]
     pub fn pax_type(&self) -> Option<char> {
-        self.pax_type
+        self.unique.pax_type.map(|d| d.as_char())
+    }
+
+    pub fn issue_date(&self) -> Option<IssueDate> {
+        self.unique.issue_date()
+    }
+
+    /// Baggage tag sequences of items 23, 31 and 32, blank items are left out
+    pub fn bag_tags(&self) -> Vec<&TagSequence> {
+        self.unique.bag_tags()
+    }
+
+    /// Source of check-in, item 12
+    pub fn checkin_source(&self) -> Option<Source> {
+        self.unique.checkin_source()
+    }
+
+    /// Source of boarding pass issuance, item 14
+    pub fn boarding_pass_source(&self) -> Option<Source> {
+        self.unique.boarding_pass_source()
+    }
+
+    pub fn document_type(&self) -> Option<char> {
+        self.unique.document_type()
     }
 
+    #[deprecated(note = "use `document_type()`")]
     pub fn doc_type(&self) -> Option<char> {
-        self.pax_type
-    }
-
-    pub fn build(&self) -> Result<String, String> {
-
-        let mut ret = format!("M{}{:<20}{}", self.segments_count(), self.name(), self.ticket_flag);
-
-        for s in &self.segments {
-            ret = format!("{}{:<7}{:<3}{:<3}{:<3}{:<5}{:3}{:1}{:>4}{:<5}{:1}00",
-                ret,
-                s.pnr,
-                s.src_airport,
-                s.dst_airport,
-                s.airline,
-                s.flight_code,
-                s.flight_day_aligned(),
-                s.compartment,
-                s.seat_aligned(),
-                s.sequence_aligned(),
-                s.pax_status);
+        self.unique.doc_type
+    }
+
+    /// Airline designator of the boarding pass issuer, item 21
+    pub fn issuer_airline(&self) -> Option<&str> {
+        self.unique.issuer_airline()
+    }
+
+    /// Whether the pass was issued by another airline than the operating carrier of the first leg
+    ///
+    /// Ground handlers route such passengers to a document check, since the
+    /// issuer may not have checked the documents for the operating carrier.
+    /// `None` without an issuer or legs.
+    pub fn is_interline_issued(&self) -> Option<bool> {
+        let issuer = self.issuer_airline()?;
+        let operating = self.segments.first()?.airline();
+
+        Some(!issuer.eq_ignore_ascii_case(operating))
+    }
+
+    /// Reason to verify travel documents before boarding, `None` if check-in already did
+    ///
+    /// Unknown airports and blank statuses on international legs require a check.
+    pub fn requires_doc_check<F, C>(&self, country_of: F) -> Option<DocCheckReason>
+        where F: Fn(&str) -> Option<C>, C: PartialEq
+    {
+        doc_check::requires_doc_check(self, country_of)
+    }
+
+    pub fn security(&self) -> Option<&SecuritySection> {
+        self.security.as_ref()
+    }
+
+    /// Type of the security data, `None` without a security section or for a legacy one
+    pub fn security_data_type(&self) -> Option<char> {
+        match self.security {
+            Some(SecuritySection::Standard { kind, .. }) => Some(kind),
+            _ => None,
+        }
+    }
+
+    /// Security data without its header, a legacy section is given as it follows `^`
+    pub fn security_data(&self) -> Option<&str> {
+        match self.security {
+            Some(SecuritySection::Standard { ref data, .. }) => Some(data),
+            Some(SecuritySection::Legacy(ref raw)) => Some(raw),
+            None => None,
         }
-        Ok(ret)
     }
 
-    pub fn from(src: &str) -> Result<BCBP, Error> {
-        let src = src.to_uppercase();
+    /// Data after the last leg or the security section, only kept by lenient parsing
+    ///
+    /// `build()` appends it back, so the pass is reproduced as scanned.
+    pub fn trailing(&self) -> Option<&str> {
+        self.trailing.as_ref().map(|s| s.as_ref())
+    }
+
+    /// AIM symbology identifier the scanner prefixed the data with, like `]Q3` for QR codes
+    pub fn symbology(&self) -> Option<&str> {
+        self.symbology.as_ref().map(|s| s.as_ref())
+    }
+
+    /// Deviations from the specification tolerated by lenient parsing
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
 
-        if src.len() < 60 {
-            return Err(Error::DataLength)
+    /// Heuristic scan quality from 0 to 100, based on the warnings of lenient parsing
+    ///
+    /// Passes parsed in strict mode have no warnings and score 100 unless the
+    /// security section is legacy. Kiosks may ask for a rescan below 80 or so.
+    pub fn quality_score(&self) -> u8 {
+        let mut penalty: u32 = self.warnings.iter()
+            .map(|w| match *w {
+                Warning::Charset { .. }    => 15,
+                Warning::TrailingData      => 20,
+                Warning::Padding { .. }    => 5,
+                Warning::Undersized { .. } => 20,
+            })
+            .sum();
+
+        // Security data whose length doesn't match its size field
+        if let Some(SecuritySection::Legacy(_)) = self.security {
+            penalty += 10;
         }
 
-        let mut bcbp = BCBP::new();
+        100u32.saturating_sub(penalty) as u8
+    }
+
+    /// One line description like `SMITH/JOHN SU1234 JFK→SVO 12AUG 12A SEQ 7`
+    pub fn summary(&self) -> String {
+        self.summary_with(&SystemClock)
+    }
+
+    /// Same as `summary()`, flight dates are resolved within the year of the given clock
+    pub fn summary_with<C: Clock>(&self, clock: &C) -> String {
+        let mut ret = self.name();
+
+        for (i, s) in self.segments.iter().enumerate() {
+            if i > 0 {
+                ret.push(',');
+            }
 
-        match bcbp_main(src.as_ref()) {
-            IResult::Done(rest, parts)    => {
+            ret.push_str(&format!(" {}{} {}→{}", s.airline, s.flight_code, s.src_airport, s.dst_airport));
 
-                let legs_count = parts.0 as i8 - '0' as i8;
+            if let Ok(date) = s.try_flight_date_current_year_with(clock) {
+                ret.push_str(&format!(" {}", datetime::format_ddmmm(&date)));
+            }
 
-                if legs_count < 1 || legs_count > 9 {
-                    return Err(Error::SegmentsCount)
-                }
-
-                bcbp.ticket_flag = parts.2;
-
-                match bcbp_name(parts.1) {
-                    IResult::Done(name_rest, name)    => {
-                        if name_rest != "" {
-                            return Err(Error::Name)
-                        }
-                        bcbp.name_last  = name.0;
-                        bcbp.name_first = name.1.unwrap_or(String::from("")).trim().into();
-                    },
-                    _ => return Err(Error::Name)
-                }
-
-                let mut next_segment = rest;
-
-                for i in 0 .. legs_count {
-                    match bcbp_segment(next_segment) {
-                        IResult::Done(leg_rest, o)    => {
-                            let sz = usize::from_str_radix(o.1, 16).unwrap();
-
-                            if sz > leg_rest.len() {
-                                return Err(Error::CoditionalDataSize)
-                            }
-
-                            let (first, last) = leg_rest.split_at(sz);
-
-                            // #[cfg(test)] println!("{:?} | {:?}", first, last);
-                            bcbp.segments.push(o.0);
-
-                            next_segment = last;
-
-                            let mut chunk = first;
-
-                            if sz != 0 {
-                                if i == 0 {
-                                    match bcbp_ext_uniq(chunk) {
-                                        IResult::Done(_, o)    => {
-                                            //println!("U== {:?}", o);
-
-                                            let sz = usize::from_str_radix(o.1, 16).unwrap();
-
-                                            if sz > chunk.len() {
-                                                return Err(Error::CoditionalDataSize)
-                                            }
-
-                                            let (first, last) = chunk.split_at(sz + 4);
-
-                                            bcbp.conditional_version = Some(o.0);
-                                            bcbp.conditional_data    = Some(first.into());
-                                            bcbp.pax_type = o.2;
-                                            bcbp.checkin_src = o.3;
-                                            bcbp.boardingpass_src = o.4;
-                                            bcbp.doc_type = o.6;
-                                            // 0 ver: anychar >>
-                                            // 1 size: take!(2) >>
-                                            // 2 pax_type: opt!(complete!(anychar)) >>
-                                            // 3 checkin_src: opt!(complete!(anychar)) >>
-                                            // 4 boardingpass_src: opt!(complete!(anychar)) >>
-                                            // 5 boardingpass_day: opt!(complete!(take!(4))) >>
-                                            // 6 doc_type: opt!(complete!(anychar)) >>
-                                            // 7 boardingpass_airline: opt!(complete!(take!(3))) >>
-                                            // 8 tags: opt!(complete!(take!(13))) >>
-
-                                            chunk = last;
-
-                                            //println!("U>> {:?}", chunk);
-                                        },
-                                        _ => return Err(Error::CoditionalData)
-                                    }
-                                }
-
-                                match bcbp_ext_seg(chunk) {
-                                    IResult::Done(_, o)    => {
-                                        let sz = usize::from_str_radix(o.0, 16).unwrap();
-
-                                        if sz > chunk.len() {
-                                            return Err(Error::CoditionalDataSize)
-                                        }
-
-                                        let (_, last) = chunk.split_at(sz + 2);
-
-                                        chunk = last;
-
-                                        #[cfg(test)] println!("S>> {:?}", chunk);
-
-                                    },
-                                    _ => return Err(Error::CoditionalData)
-                                }
-
-                            }
-                        },
-                        IResult::Error(e)      => println!("{:?}", e),
-                        IResult::Incomplete(_) => {
-                            return Err(Error::DataLength)
-                        }
-                    }
-                }
-            },
-            IResult::Error(e) => {
-                match e {
-                    ErrorKind::Custom(1) => return Err(Error::FormatCode),
-                    _ => return Err(Error::Format),
-                }
-            },
-            IResult::Incomplete(_) => {
-                return Err(Error::DataLength)
+            if !s.seat.is_empty() {
+                ret.push_str(&format!(" {}", s.seat));
+            }
+
+            if s.sequence > 0 {
+                ret.push_str(&format!(" SEQ {}", s.sequence));
             }
         }
 
-        Ok(bcbp)
+        ret
     }
-}
 
-fn u32_from_str_force(src: &str, radix: u32) -> u32 {
-    match u32::from_str_radix(src.trim().trim_left_matches('0'), radix) {
-        Ok(v) => v,
-        _     => 0,
+    pub fn set_security(&mut self, security: Option<SecuritySection>) {
+        self.security = security;
+    }
+
+    /// Sets security data which `build()` appends with its type and length header
+    pub fn set_security_data(&mut self, kind: char, data: &str) {
+        self.security = Some(SecuritySection::Standard {
+            kind,
+            data: data.into(),
+        });
+    }
+
+    /// Masks passenger data with `X`, numeric items with `0`, the pass still builds and parses
+    ///
+    /// A security section that isn't masked is dropped once the data it
+    /// signs is.
+    pub fn redact(&mut self, rules: &RedactionRules) {
+        if rules.name {
+            self.name_last = redact::mask(&self.name_last);
+            self.name_first = redact::mask(&self.name_first);
+        }
+
+        for s in &mut self.segments {
+            if rules.pnr {
+                s.pnr = redact::mask(&s.pnr);
+            }
+            if rules.frequent_flyer_number {
+                s.conditional.frequent_flyer_number = s.conditional.frequent_flyer_number.as_ref().map(|n| redact::mask(n));
+                mask_item(&mut s.items, 236, redact::mask);
+            }
+            if rules.document_number {
+                s.conditional.document_number = s.conditional.document_number.as_ref().map(|n| redact::mask_numeric(n));
+                mask_item(&mut s.items, 143, redact::mask_numeric);
+            }
+            if rules.airline_data {
+                s.airline_data = s.airline_data.as_ref().map(|d| redact::mask(d));
+            }
+        }
+
+        // Raw copies may hold any of the items
+        if *rules != RedactionRules::none() {
+            self.conditional_data = self.conditional_data.as_ref().map(|d| redact::mask(d));
+            self.trailing = self.trailing.as_ref().map(|d| redact::mask(d));
+            self.original = None;
+        }
+
+        let masks_signed = RedactionRules { security_data: false, ..*rules } != RedactionRules::none();
+
+        if masks_signed && !rules.security_data {
+            self.security = None;
+        }
+
+        if rules.security_data {
+            self.security = match self.security.take() {
+                Some(SecuritySection::Standard { kind, data }) => Some(SecuritySection::Standard { kind, data: redact::mask(&data) }),
+                Some(SecuritySection::Legacy(raw)) => Some(SecuritySection::Legacy(redact::mask(&raw))),
+                None => None,
+            };
+        }
     }
 }
 
-named!(bcbp_main<&str, (char, &str, char)>,
-    do_parse!(
-        add_return_error!(
-            ErrorKind::Custom(1),
-            char!('M')
-        ) >>
-        segments: add_return_error!(
-            ErrorKind::Custom(2),
-            anychar
-        ) >>
-        name: add_return_error!(
-            ErrorKind::Custom(3),
-            take!(20)
-        ) >>
-        ticket_flag: add_return_error!(
-            ErrorKind::Custom(4),
-            anychar
-        ) >>
-        (
-            segments,
-            name,
-            ticket_flag
-        )
-    )
-);
-
-named!(bcbp_name<&str, (String, Option<String>)>,
-    do_parse!(
-        last:  map_res!(alpha, str::FromStr::from_str) >>
-        first: opt!(complete!(
-            preceded!(
-            char!('/'),
-            // map_res!(alt!(alphanumeric | space), str::FromStr::from_str)
-            map_res!(rest_s, str::FromStr::from_str)
-        ))) >>
-        (
-            last,
-            first
-        )
-    )
-);
-
-named!(bcbp_segment<&str, (Segment, &str)>,
-    do_parse!(
-        pnr: add_return_error!(
-            ErrorKind::Custom(1001),
-            take!(7)
-        ) >>
-        src: add_return_error!(
-            ErrorKind::Custom(1002),
-            take!(3)
-        ) >>
-        dst: add_return_error!(
-            ErrorKind::Custom(1003),
-            take!(3)
-        ) >>
-        airline: add_return_error!(
-            ErrorKind::Custom(1004),
-            take!(3)
-        ) >>
-        flight_code: add_return_error!(
-            ErrorKind::Custom(1005),
-            take!(5)
-        ) >>
-        flight_day: add_return_error!(
-            ErrorKind::Custom(1006),
-            take!(3)
-        ) >>
-        compartment: add_return_error!(
-            ErrorKind::Custom(1007),
-            anychar
-        ) >>
-        seat: add_return_error!(
-            ErrorKind::Custom(1008),
-            take!(4)
-        ) >>
-        sequence: add_return_error!(
-            ErrorKind::Custom(1009),
-            take!(5)
-        ) >>
-        pax_status: add_return_error!(
-            ErrorKind::Custom(1010),
-            take!(1)
-        ) >>
-        size_ext: add_return_error!(
-            ErrorKind::Custom(1011),
-            take!(2)
-        ) >>
-        (
-            Segment{
-                pnr: pnr.trim().into(),
-                src_airport: src.trim().into(),
-                dst_airport: dst.trim().into(),
-                airline: airline.trim().into(),
-                flight_code: flight_code.trim().into(),
-                flight_day: u32_from_str_force(flight_day, 10),
-                compartment: compartment,
-                seat: seat.trim().trim_left_matches('0').to_string(),
-                sequence: u32_from_str_force(sequence, 10),
-                pax_status: pax_status.trim().into(),
-            },
-            size_ext
-        )
-    )
-);
-
-named!(bcbp_ext_uniq<&str, (char, &str, Option<char>, Option<char>, Option<char>, Option<&str>, Option<char>, Option<&str>, Option<&str>)>,
-    do_parse!(
-        add_return_error!(
-            ErrorKind::Custom(2001),
-            alt!(char!('>') | char!('<'))
-        ) >>
-        ver: anychar >>
-        size: take!(2) >>
-        pax_type: opt!(complete!(anychar)) >>
-        checkin_src: opt!(complete!(anychar)) >>
-        boardingpass_src: opt!(complete!(anychar)) >>
-        boardingpass_day: opt!(complete!(take!(4))) >>
-        doc_type: opt!(complete!(anychar)) >>
-        boardingpass_airline: opt!(complete!(take!(3))) >>
-        tags: opt!(complete!(take!(13))) >>
-        (
-            ver,
-            size,
-            pax_type,
-            checkin_src,
-            boardingpass_src,
-            boardingpass_day,
-            doc_type,
-            boardingpass_airline,
-            tags
-        )
-    )
-);
-
-named!(bcbp_ext_seg<&str, (&str, Option<&str>, Option<&str>, Option<char>, Option<char>, Option<&str>, Option<&str>, Option<&str>, Option<char>, Option<&str>)>,
-    do_parse!(
-        size: take!(2) >>
-        prefix: opt!(complete!(take!(3))) >>
-        number: opt!(complete!(take!(10))) >>
-        indicator: opt!(complete!(anychar)) >>
-        verify: opt!(complete!(anychar)) >>
-        airline: opt!(complete!(take!(3))) >>
-        ff_airline: opt!(complete!(take!(3))) >>
-        ff_number: opt!(complete!(take!(16))) >>
-        id_ad: opt!(complete!(anychar)) >>
-        bag_allowance: opt!(complete!(take!(3))) >>
-        (
-            size,
-            prefix,
-            number,
-            indicator,
-            verify,
-            airline,
-            ff_airline,
-            ff_number,
-            id_ad,
-            bag_allowance
-        )
-    )
-);
+/// Masks a raw item set with `add_item()`
+fn mask_item(items: &mut BTreeMap<u16, String>, id: u16, mask: fn(&str) -> String) {
+    if let Some(value) = items.get_mut(&id) {
+        *value = mask(value);
+    }
+}
@@ -0,0 +1,321 @@
+//! Encoding of passes
+
+use std::fmt;
+use std::iter;
+
+use names::NAME_LEN;
+use spec;
+
+use super::items;
+use super::{BCBP, BuildError, CapacityWarning, SecuritySection, Segment, MAX_SEGMENTS, MAX_SEQUENCE};
+
+impl BCBP {
+    #[deprecated(note = "use `try_build()`, which returns a `BuildError`")]
+    pub fn build(&self) -> Result<String, String> {
+        self.try_build().map_err(|e| e.to_string())
+    }
+
+    /// Encodes the pass
+    ///
+    /// A parsed pass is written as it was scanned until its items change.
+    /// Changed items fail with `BuildError::SignedData` while the security
+    /// section kept from parsing signs the old data.
+    pub fn try_build(&self) -> Result<String, BuildError> {
+        let mut ret = String::new();
+        write_to(self, &mut ret)?;
+        Ok(ret)
+    }
+
+    /// Encodes the pass
+    ///
+    /// # Panics
+    ///
+    /// When the pass can't be encoded, see `try_build()`.
+    #[track_caller]
+    pub fn expect_build(&self) -> String {
+        match self.try_build() {
+            Ok(data) => data,
+            Err(e)   => panic!("{}", e),
+        }
+    }
+
+    /// Copy for a reprint with the seat of the first leg changed
+    ///
+    /// The security section signs the old data, so the copy goes without it.
+    pub fn with_new_seat(&self, seat: &str) -> Result<BCBP, BuildError> {
+        let seat = seat.trim().trim_start_matches('0').to_uppercase();
+        let digits = seat.len().saturating_sub(1);
+
+        let valid = !seat.is_empty()
+            && seat.is_ascii()
+            && digits <= 3
+            && seat[.. digits].chars().all(|c| c.is_ascii_digit())
+            && seat[digits ..].chars().all(|c| c.is_ascii_uppercase());
+
+        if !valid {
+            return Err(BuildError::Seat(seat))
+        }
+
+        self.reissue(|bcbp| bcbp.segments[0].seat = seat)
+    }
+
+    /// Copy for a reprint with the check-in sequence number of the first leg changed, see `with_new_seat()`
+    pub fn with_new_sequence(&self, sequence: u32) -> Result<BCBP, BuildError> {
+        if sequence > MAX_SEQUENCE {
+            return Err(BuildError::Sequence(sequence))
+        }
+
+        self.reissue(|bcbp| bcbp.segments[0].sequence = sequence)
+    }
+
+    fn reissue<F: FnOnce(&mut BCBP)>(&self, change: F) -> Result<BCBP, BuildError> {
+        if self.segments.is_empty() {
+            return Err(BuildError::SegmentsCount(0))
+        }
+
+        let mut ret = self.clone();
+        change(&mut ret);
+        ret.security = None;
+
+        // Size fields are written from the items, raw data kept by a lenient scan would follow them unsized
+        ret.trailing = None;
+        ret.warnings.clear();
+
+        Ok(ret)
+    }
+
+    /// Length of the barcode carrying conditional items up to `version`, 0 for mandatory items only
+    ///
+    /// Conditional items count at their full width, airline data and the
+    /// security section at their actual length.
+    pub fn encoded_len_for_version(&self, version: u8) -> usize {
+        let conditional_len = |section: &[spec::Item]| -> usize {
+            section.iter().filter(|item| item.since <= version).map(|item| item.len).sum()
+        };
+
+        let mut ret = spec::fixed_len(spec::MANDATORY) + self.segments.len() * spec::fixed_len(spec::LEG);
+
+        if version > 0 && !self.segments.is_empty() {
+            ret += conditional_len(spec::UNIQUE);
+
+            for s in &self.segments {
+                ret += conditional_len(spec::REPEATED) + s.airline_data().map_or(0, str::len);
+            }
+        }
+
+        ret += self.security.as_ref().map_or(0, SecuritySection::encoded_len);
+        ret + self.trailing.as_ref().map_or(0, String::len)
+    }
+
+    /// Warns when the barcode with conditional items up to `version` exceeds `capacity` characters
+    pub fn check_capacity(&self, version: u8, capacity: usize) -> Option<CapacityWarning> {
+        let len = self.encoded_len_for_version(version);
+
+        if len > capacity {
+            Some(CapacityWarning { version, len, capacity })
+        } else {
+            None
+        }
+    }
+}
+
+
+/// Writes the barcode of the pass, like `BCBP::try_build()` but without allocating
+///
+/// Nothing is written when the pass can't be encoded, so a buffer can be
+/// cleared and reused between passes when printing them in bulk.
+///
+/// ```
+/// # extern crate iata;
+/// use iata::bcbp::{self, BCBP};
+///
+/// let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+/// let passes = vec![BCBP::from(src).unwrap(), BCBP::from(src).unwrap()];
+///
+/// let mut buf = String::with_capacity(160);
+/// for pass in &passes {
+///     buf.clear();
+///     bcbp::write_to(pass, &mut buf).unwrap();
+///     assert_eq!(buf, src);
+/// }
+/// ```
+pub fn write_to<W: fmt::Write>(bcbp: &BCBP, w: &mut W) -> Result<(), BuildError> {
+    if !bcbp.name_last.is_ascii() || !bcbp.name_first.is_ascii() {
+        return Err(BuildError::Name)
+    }
+
+    if bcbp.segments.is_empty() || bcbp.segments.len() > MAX_SEGMENTS {
+        return Err(BuildError::SegmentsCount(bcbp.segments.len()))
+    }
+
+    for s in &bcbp.segments {
+        check_segment(s)?;
+    }
+
+    if let Some(ref security) = bcbp.security {
+        security.validate()?;
+    }
+
+    let original = bcbp.original.as_ref().filter(|o| o.matches(bcbp));
+
+    // A security section kept from parsing signs the scanned data only
+    if let Some(ref o) = bcbp.original {
+        if original.is_none() && bcbp.security.is_some() && bcbp.security == o.security {
+            return Err(BuildError::SignedData)
+        }
+    }
+
+    match original {
+        Some(o) => w.write_str(&o.data)?,
+        None    => write_signed(bcbp, w)?,
+    }
+
+    if let Some(ref security) = bcbp.security {
+        security.write_to(w)?;
+    }
+
+    if let Some(ref trailing) = bcbp.trailing {
+        w.write_str(trailing)?;
+    }
+
+    Ok(())
+}
+
+/// Barcode data a pass was parsed from, up to its security section
+///
+/// Parsing drops padding and case the items encode to differently, so the
+/// scanned data is written back as long as the items still encode to what
+/// they did when parsed.
+#[derive(Clone)]
+pub(super) struct Original {
+    data: String,
+    encoded: String,
+    security: Option<SecuritySection>,
+}
+
+impl Original {
+    pub(super) fn new(bcbp: &BCBP, data: &str) -> Original {
+        let mut encoded = String::with_capacity(data.len());
+        // Writing to a `String` doesn't fail
+        let _ = write_signed(bcbp, &mut encoded);
+
+        Original {
+            data: data.into(),
+            encoded,
+            security: bcbp.security.clone(),
+        }
+    }
+
+    /// Whether the items of the pass encode to the same data as when parsed
+    fn matches(&self, bcbp: &BCBP) -> bool {
+        let mut cmp = Compare { expected: &self.encoded, pos: 0, equal: true };
+        write_signed(bcbp, &mut cmp).is_ok() && cmp.equal && cmp.pos == self.encoded.len()
+    }
+}
+
+/// Writer comparing the data against an expected string instead of keeping it
+struct Compare<'a> {
+    expected: &'a str,
+    pos: usize,
+    equal: bool,
+}
+
+impl<'a> fmt::Write for Compare<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.equal = self.equal && self.expected.get(self.pos ..).is_some_and(|rest| rest.starts_with(s));
+        self.pos += s.len();
+        Ok(())
+    }
+}
+
+/// Checks the mandatory items of a leg fit their width and charset
+fn check_segment(s: &Segment) -> Result<(), BuildError> {
+    let items = [
+        (7, &s.pnr),
+        (26, &s.src_airport),
+        (38, &s.dst_airport),
+        (42, &s.airline),
+        (43, &s.flight_code),
+        (104, &s.seat),
+        (113, &s.pax_status),
+    ];
+
+    for &(id, value) in items.iter() {
+        let item = spec::item(id).expect("leg item is in spec");
+
+        if value.chars().count() > item.len {
+            return Err(BuildError::FieldTooLong(id, value.clone()))
+        }
+
+        if !value.is_ascii() || !item.charset.validate(value) {
+            return Err(BuildError::InvalidCharset(id, value.clone()))
+        }
+    }
+
+    // A leg is from an airport to another by a flight of a carrier, everything else may be blank
+    for &(id, value) in items[1 .. 5].iter() {
+        if value.trim().is_empty() {
+            return Err(BuildError::MissingMandatory(id))
+        }
+    }
+
+    if s.flight_day > 999 {
+        return Err(BuildError::FieldTooLong(46, s.flight_day.to_string()))
+    }
+
+    if !spec::Charset::Alpha.accepts(s.compartment) {
+        return Err(BuildError::InvalidCharset(71, s.compartment.to_string()))
+    }
+
+    if s.sequence > MAX_SEQUENCE {
+        return Err(BuildError::Sequence(s.sequence))
+    }
+
+    Ok(())
+}
+
+/// Writes the mandatory and conditional items, the data the security section signs
+fn write_signed<W: fmt::Write>(bcbp: &BCBP, w: &mut W) -> fmt::Result {
+    write!(w, "M{}", bcbp.segments_count())?;
+
+    let separator = if bcbp.name_first.is_empty() { "" } else { "/" };
+    let name = bcbp.name_last.chars()
+        .chain(separator.chars())
+        .chain(bcbp.name_first.chars())
+        .chain(iter::repeat(' '))
+        .take(NAME_LEN);
+
+    for c in name {
+        w.write_char(c)?;
+    }
+
+    w.write_char(bcbp.ticket_indicator.as_char())?;
+
+    for (i, s) in bcbp.segments.iter().enumerate() {
+        write!(w, "{:<7}{:<3}{:<3}{:<3}{:<5}", s.pnr, s.src_airport, s.dst_airport, s.airline, s.flight_code)?;
+
+        match s.flight_day {
+            0   => w.write_str("   ")?,
+            day => write!(w, "{:03}", day)?,
+        }
+
+        w.write_char(s.compartment)?;
+
+        if s.seat.is_empty() {
+            w.write_str("    ")?;
+        } else {
+            write!(w, "{:0>4}", s.seat)?;
+        }
+
+        match s.sequence {
+            0   => w.write_str("     ")?,
+            seq if seq < 10000 => write!(w, "{:04} ", seq)?,
+            seq => write!(w, "{}", seq)?,
+        }
+
+        write!(w, "{:1}", s.pax_status)?;
+        items::write_conditional(bcbp, i, w)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,339 @@
+//! Parse errors located in the scanned data, for support tools
+//!
+//! An error tells the item parsing failed in, where in the data and the
+//! data of the item. That data and the snippet around the failure have the
+//! items of `RedactionRules::all()` masked, so they can be shown and logged
+//! without exposing passenger data.
+
+use std::fmt;
+use std::error;
+
+use nom;
+use nom::error::{ErrorKind, ParseError, FromExternalError};
+
+use spec;
+use redact::RedactionRules;
+use super::{BCBP, Error, Warning, ParseOptions, Version, parser};
+
+/// Characters shown on each side of the failure
+const SNIPPET_RADIUS: usize = 12;
+
+/// Parser error along with the address of the input it happened at
+///
+/// Items are checked on their own slices, which end before the data does,
+/// so the position is taken from where the slice starts.
+struct Located {
+    error: Error,
+    at: usize,
+}
+
+impl<'a> ParseError<&'a str> for Located {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Located {
+        Located {
+            error: Error::from_error_kind(input, kind),
+            at: input.as_ptr() as usize,
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Located) -> Located {
+        other
+    }
+}
+
+impl<'a> FromExternalError<&'a str, Error> for Located {
+    fn from_external_error(input: &'a str, _: ErrorKind, e: Error) -> Located {
+        Located {
+            error: e,
+            at: input.as_ptr() as usize,
+        }
+    }
+}
+
+/// Error with the item it happened in, its offset in the data and an optional masked snippet
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorContext {
+    pub error: Error,
+    /// Offset in characters, a symbology identifier counts
+    pub offset: usize,
+    /// Offset in bytes, only differs from `offset` after non ASCII data
+    pub byte_offset: usize,
+    /// IATA number of the item parsing failed in, when it can be told
+    pub item: Option<u16>,
+    /// Data of the failing item, or of the data at the offset when the item isn't known, masked like the snippet
+    pub raw: String,
+    /// Only filled when `ParseOptions::snippets` is set
+    pub snippet: Option<String>,
+}
+
+impl ErrorContext {
+    /// Name of the failing item in the specification
+    pub fn item_name(&self) -> Option<&'static str> {
+        self.item.and_then(spec::item).map(|i| i.name)
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        // Charset and size errors name their item already
+        match (self.item, &self.error) {
+            (_, &Error::InvalidCharset(_)) | (_, &Error::InvalidSizeField(..)) | (None, _) => {},
+            (Some(item), _) => write!(f, " in item {} ({})", item, self.item_name().unwrap_or("unknown item"))?,
+        }
+
+        write!(f, " at offset {}", self.offset)?;
+
+        if let Some(ref snippet) = self.snippet {
+            write!(f, ": `{}`", snippet)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for ErrorContext {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl PartialEq<Error> for ErrorContext {
+    fn eq(&self, other: &Error) -> bool {
+        self.error == *other
+    }
+}
+
+impl From<ErrorContext> for Error {
+    fn from(e: ErrorContext) -> Error {
+        e.error
+    }
+}
+
+/// Two digit hex size field at a character offset
+fn size_at(src: &[char], at: usize) -> Option<usize> {
+    let digits: String = src.get(at .. at + 2)?.iter().collect();
+    usize::from_str_radix(&digits, 16).ok()
+}
+
+/// Ranges of the items of a conditional block the rules cover
+fn block_ranges(ret: &mut Vec<(usize, usize)>, rules: &RedactionRules, section: &[spec::Item], version: Option<Version>, from: usize, to: usize) {
+    let mut pos = from;
+
+    for item in section.iter().filter(|item| !item.is_variable() && version.is_none_or(|v| v.defines(item))) {
+        if pos >= to {
+            break
+        }
+
+        if rules.covers(item.id) {
+            ret.push((pos, (pos + item.len).min(to)));
+        }
+
+        pos += item.len;
+    }
+}
+
+/// Ranges of the items `RedactionRules::all()` masks that can be located
+fn private_ranges(src: &[char], prefix: usize) -> Vec<(usize, usize)> {
+    let rules = RedactionRules::all();
+    let leg = spec::fixed_len(spec::LEG);
+    let legs = src.get(prefix + 1).and_then(|c| c.to_digit(10)).unwrap_or(1);
+
+    let mut ret = Vec::new();
+    let mut start = prefix + spec::fixed_len(spec::MANDATORY);
+    let mut version = None;
+
+    if rules.covers(11) {
+        ret.push((prefix + 2, prefix + 22));
+    }
+
+    for i in 0 .. legs {
+        if rules.covers(7) {
+            ret.push((start, start + 7));
+        }
+
+        let size = match size_at(src, start + leg - 2) {
+            Some(size) => size,
+            None       => break,
+        };
+
+        let end = start + leg + size;
+        let mut pos = start + leg;
+
+        // Marker, version and size of the unique items precede them
+        if i == 0 && pos < end && (src.get(pos) == Some(&'>') || src.get(pos) == Some(&'<')) {
+            version = src.get(pos + 1).map(|&c| Version::from(c));
+
+            if let Some(unique) = size_at(src, pos + 2) {
+                block_ranges(&mut ret, &rules, &spec::UNIQUE[3 ..], version, pos + 4, (pos + 4 + unique).min(end));
+            }
+
+            pos = (pos + 4 + size_at(src, pos + 2).unwrap_or(0)).min(end);
+        }
+
+        if let Some(repeated) = size_at(src, pos).filter(|_| pos + 2 <= end) {
+            let block_end = (pos + 2 + repeated).min(end);
+
+            block_ranges(&mut ret, &rules, &spec::REPEATED[1 ..], version, pos + 2, block_end);
+
+            if rules.covers(4) {
+                ret.push((block_end, end));
+            }
+        }
+
+        start = end;
+    }
+
+    if rules.covers(30) && src.get(start) == Some(&'^') {
+        ret.push((start + 4, size_at(src, start + 2).map_or(src.len(), |size| start + 4 + size)));
+    }
+
+    ret
+}
+
+/// Characters of a range with the private ones masked
+fn masked(chars: &[char], private: &[(usize, usize)], from: usize, to: usize) -> String {
+    (from .. to.min(chars.len()))
+        .map(|i| {
+            let masked = private.iter().any(|&(start, end)| i >= start && i < end);
+            if masked && chars[i] != ' ' { '*' } else { chars[i] }
+        })
+        .collect()
+}
+
+fn snippet(src: &str, prefix: usize, offset: usize) -> String {
+    let chars: Vec<char> = src.chars().collect();
+    let private = private_ranges(&chars, prefix);
+
+    masked(&chars, &private, offset.saturating_sub(SNIPPET_RADIUS), offset + SNIPPET_RADIUS)
+}
+
+/// Item an error is about regardless of where the parser stopped
+fn error_item(error: &Error) -> Option<u16> {
+    match *error {
+        Error::FormatCode                => Some(1),
+        Error::SegmentsCount             => Some(5),
+        Error::Name                      => Some(11),
+        Error::ElectronicTicketIndicator => Some(253),
+        Error::InvalidCharset(item)      => Some(item),
+        Error::InvalidSizeField(item, _) => Some(item),
+        Error::SecurityData | Error::SecurityHeader => Some(25),
+        Error::SecurityDataSize          => Some(29),
+        _ => None,
+    }
+}
+
+/// Mandatory item at a character offset with its range, `None` within conditional items
+fn item_at(src: &[char], prefix: usize, offset: usize) -> Option<(u16, usize, usize)> {
+    let find = |section: &[spec::Item], start: usize| {
+        let mut pos = start;
+
+        for item in section {
+            if offset < pos + item.len {
+                return Some((item.id, pos, pos + item.len))
+            }
+            pos += item.len;
+        }
+
+        None
+    };
+
+    if let Some(found) = find(spec::MANDATORY, prefix) {
+        return Some(found)
+    }
+
+    let leg = spec::fixed_len(spec::LEG);
+    let legs = src.get(prefix + 1).and_then(|c| c.to_digit(10)).unwrap_or(1);
+    let mut start = prefix + spec::fixed_len(spec::MANDATORY);
+
+    for _ in 0 .. legs {
+        if offset < start + leg {
+            return find(spec::LEG, start)
+        }
+
+        let size: String = src.iter().skip(start + leg - 2).take(2).collect();
+        match usize::from_str_radix(&size, 16) {
+            Ok(size) if offset >= start + leg + size => start += leg + size,
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Failing item and its raw data, see `ErrorContext`
+fn failing_item(src: &str, prefix: usize, offset: usize, error: &Error) -> (Option<u16>, String) {
+    let chars: Vec<char> = src.chars().collect();
+    let private = private_ranges(&chars, prefix);
+    let at = item_at(&chars, prefix, offset);
+
+    let item = error_item(error).or(at.map(|(id, _, _)| id));
+
+    let (from, to) = match at {
+        Some((id, start, end)) if Some(id) == item => (start, end),
+        _ => {
+            let len = item.and_then(spec::item).map_or(0, |i| i.len);
+            (offset, offset + if len > 0 { len } else { SNIPPET_RADIUS })
+        },
+    };
+
+    (item, masked(&chars, &private, from, to))
+}
+
+impl BCBP {
+    #[deprecated(note = "use `from_with()`, which returns an `ErrorContext`")]
+    pub fn from_with_context(src: &str, options: ParseOptions) -> Result<BCBP, ErrorContext> {
+        BCBP::from_with(src, options)
+    }
+}
+
+/// Parses a pass, see `BCBP::from_with()`
+pub(super) fn parse(src: &str, options: ParseOptions) -> Result<BCBP, ErrorContext> {
+    let (symbology, data) = match src.get(.. 3) {
+        Some(id) if id.starts_with(']') => (Some(id), &src[3 ..]),
+        _ => (None, src),
+    };
+
+    let prefix = symbology.map(|s| s.len()).unwrap_or(0);
+
+    let fail = |error: Error, offset: usize| {
+        let (item, raw) = failing_item(src, prefix, offset, &error);
+
+        Err(ErrorContext {
+            snippet: if options.snippets { Some(snippet(src, prefix, offset)) } else { None },
+            byte_offset: src.char_indices().nth(offset).map_or(src.len(), |(i, _)| i),
+            item,
+            raw,
+            error,
+            offset,
+        })
+    };
+
+    if data.len() < spec::fixed_len(spec::MANDATORY) + spec::fixed_len(spec::LEG) {
+        return fail(Error::DataLength, src.chars().count())
+    }
+
+    let offset = |rest: usize| src[.. src.len() - rest].chars().count();
+
+    // Every input the parser fails at is a slice of `data`
+    let located = |at: usize| offset(data.len() - at.saturating_sub(data.as_ptr() as usize).min(data.len()));
+
+    let (rest, mut bcbp) = match parser::bcbp_with::<Located>(options.clone())(data) {
+        Ok(ok) => ok,
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => return fail(e.error, located(e.at)),
+        Err(nom::Err::Incomplete(_)) => return fail(Error::DataLength, src.chars().count()),
+    };
+
+    if !rest.is_empty() {
+        if !options.is_lenient() {
+            return fail(Error::TrailingData, offset(rest.len()))
+        }
+
+        bcbp.trailing = Some(rest.into());
+        bcbp.warnings.push(Warning::TrailingData);
+    }
+
+    bcbp.symbology = symbology.map(|s| s.into());
+
+    Ok(bcbp)
+}
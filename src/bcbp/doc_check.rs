@@ -0,0 +1,77 @@
+//! Travel document verification decision
+//!
+//! Departure control checks passports and visas at check-in. A pass still
+//! needs a document check at the gate when an international leg wasn't
+//! through check-in, when it's an itinerary receipt rather than a boarding
+//! pass, or when the passenger is on standby.
+
+use std::fmt;
+
+use pax_status::PaxStatus;
+use super::BCBP;
+
+/// Why travel documents have to be checked before boarding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DocCheckReason {
+    /// Document type `I`, the pass is not valid for boarding
+    ItineraryReceipt,
+    /// Airport of the leg is unknown to the country lookup
+    UnknownRoute { leg: usize },
+    /// International leg with a blank passenger status
+    UnknownStatus { leg: usize },
+    /// International leg the passenger hasn't checked in for
+    NotCheckedIn { leg: usize },
+    /// Standby passenger on an international leg
+    Standby { leg: usize },
+}
+
+impl DocCheckReason {
+    /// Index of the leg that requires the check, `None` for the whole pass
+    pub fn leg(&self) -> Option<usize> {
+        match *self {
+            DocCheckReason::ItineraryReceipt => None,
+            DocCheckReason::UnknownRoute { leg }
+            | DocCheckReason::UnknownStatus { leg }
+            | DocCheckReason::NotCheckedIn { leg }
+            | DocCheckReason::Standby { leg } => Some(leg),
+        }
+    }
+}
+
+impl fmt::Display for DocCheckReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DocCheckReason::ItineraryReceipt       => write!(f, "itinerary receipt"),
+            DocCheckReason::UnknownRoute { leg }   => write!(f, "unknown airport on leg {}", leg + 1),
+            DocCheckReason::UnknownStatus { leg }  => write!(f, "unknown passenger status on international leg {}", leg + 1),
+            DocCheckReason::NotCheckedIn { leg }   => write!(f, "not checked in for international leg {}", leg + 1),
+            DocCheckReason::Standby { leg }        => write!(f, "standby on international leg {}", leg + 1),
+        }
+    }
+}
+
+pub(super) fn requires_doc_check<F, C>(bcbp: &BCBP, country_of: F) -> Option<DocCheckReason>
+    where F: Fn(&str) -> Option<C>, C: PartialEq
+{
+    if bcbp.document_type().map(|c| c.to_ascii_uppercase()) == Some('I') {
+        return Some(DocCheckReason::ItineraryReceipt);
+    }
+
+    for (leg, segment) in bcbp.segments().iter().enumerate() {
+        match segment.is_international(&country_of) {
+            None => return Some(DocCheckReason::UnknownRoute { leg }),
+            Some(false) => continue,
+            Some(true) => {}
+        }
+
+        match segment.passenger_status() {
+            None => return Some(DocCheckReason::UnknownStatus { leg }),
+            Some(status) if status.needs_document_check() => return Some(DocCheckReason::NotCheckedIn { leg }),
+            Some(PaxStatus::Standby) => return Some(DocCheckReason::Standby { leg }),
+            Some(_) => {}
+        }
+    }
+
+    None
+}
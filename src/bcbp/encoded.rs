@@ -0,0 +1,158 @@
+//! Unwrapping of passes embedded in URLs, JSON strings and base64 payloads
+
+use super::{BCBP, ErrorContext};
+
+impl BCBP {
+    /// Parses a pass wrapped the way mobile and wallet apps deliver it
+    ///
+    /// Accepts JSON string escapes, percent encoded URLs or query values and
+    /// base64 (standard or URL safe) payloads, falling back to parsing the
+    /// input as is. Offsets of an error are in the unwrapped data.
+    ///
+    /// ```
+    /// # extern crate iata;
+    /// use iata::bcbp::BCBP;
+    ///
+    /// let url = "https://wallet.example/add?v=2&pass=M1JOHN%2FSMITH%20JORDAN%20%20%20EABCDEF%20JFKSVOSU%201234A001Y001Z0007%20000";
+    /// assert_eq!(BCBP::from_encoded(url).unwrap().name(), "JOHN/SMITH JORDAN");
+    ///
+    /// let base64 = "TTFKT0hOL1NNSVRIIEpPUkRBTiAgIEVBQkNERUYgSkZLU1ZPU1UgMTIzNEEwMDFZMDAxWjAwMDcgMDAw";
+    /// assert_eq!(BCBP::from_encoded(base64).unwrap().segments()[0].pnr(), "ABCDEF");
+    /// ```
+    pub fn from_encoded(src: &str) -> Result<BCBP, ErrorContext> {
+        let src = unescape(src);
+        // Trailing spaces may belong to the last item, so only line breaks are trimmed
+        let mut src = src.trim_start().trim_end_matches(['\r', '\n']);
+
+        let quoted = src.trim_end();
+        if quoted.len() > 1 && quoted.starts_with('"') && quoted.ends_with('"') {
+            src = quoted[1 .. quoted.len() - 1].trim_end_matches(['\r', '\n']);
+        }
+
+        let candidates: Vec<&str> = match src.find('?') {
+            Some(pos) => src[pos + 1 ..].split('&')
+                .map(|pair| pair.split_once('=').map(|(_, value)| value).unwrap_or(pair))
+                .collect(),
+            None => vec![src],
+        };
+
+        for candidate in &candidates {
+            let decoded = match percent_decode(candidate) {
+                Some(decoded) => decoded,
+                None => continue,
+            };
+
+            if looks_like_pass(&decoded) {
+                return BCBP::from(&decoded)
+            }
+
+            if let Some(decoded) = base64_decode(decoded.trim()) {
+                if looks_like_pass(&decoded) {
+                    return BCBP::from(&decoded)
+                }
+            }
+        }
+
+        BCBP::from(src)
+    }
+}
+
+fn looks_like_pass(src: &str) -> bool {
+    let mut chars = src.chars();
+
+    chars.next().map(|c| c.eq_ignore_ascii_case(&'M')).unwrap_or(false)
+        && chars.next().map(|c| c.is_ascii_digit()).unwrap_or(false)
+}
+
+/// Resolves JSON string escapes
+fn unescape(src: &str) -> String {
+    let mut ret = String::with_capacity(src.len());
+    let mut chars = src.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue
+        }
+
+        match chars.next() {
+            Some('n') => ret.push('\n'),
+            Some('r') => ret.push('\r'),
+            Some('t') => ret.push('\t'),
+            Some('u') => {
+                let code: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&code, 16).ok().and_then(::std::char::from_u32) {
+                    Some(c) => ret.push(c),
+                    None    => ret.push_str(&format!("\\u{}", code)),
+                }
+            },
+            Some(c) => ret.push(c),
+            None    => ret.push('\\'),
+        }
+    }
+
+    ret
+}
+
+/// Decodes `%XX` sequences and `+` as space, `None` for invalid UTF-8
+///
+/// Input with spaces is not encoded and is returned as is.
+fn percent_decode(src: &str) -> Option<String> {
+    if !src.contains(['%', '+']) || src.contains(' ') {
+        return Some(src.into())
+    }
+
+    let bytes = src.as_bytes();
+    let mut ret = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = src.get(i + 1 .. i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                ret.push(byte);
+                i += 3;
+                continue
+            },
+            (b'+', _) => ret.push(b' '),
+            (byte, _) => ret.push(byte),
+        }
+
+        i += 1;
+    }
+
+    String::from_utf8(ret).ok()
+}
+
+/// Decodes standard or URL safe base64 with optional padding
+fn base64_decode(src: &str) -> Option<String> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A' ..= b'Z' => Some((c - b'A') as u32),
+            b'a' ..= b'z' => Some((c - b'a') as u32 + 26),
+            b'0' ..= b'9' => Some((c - b'0') as u32 + 52),
+            b'+' | b'-'   => Some(62),
+            b'/' | b'_'   => Some(63),
+            _ => None,
+        }
+    }
+
+    let src = src.trim_end_matches('=');
+    let mut ret = Vec::with_capacity(src.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+
+    for c in src.bytes() {
+        acc = (acc << 6) | value(c)?;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            ret.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    String::from_utf8(ret).ok()
+}
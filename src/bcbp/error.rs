@@ -0,0 +1,173 @@
+//! Parse and build errors, lenient parsing warnings
+
+use std::fmt;
+use std::error;
+
+use spec;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    DataLength,
+    FormatCode,
+    SegmentsCount,
+    Format,
+    Name,
+    Date,
+    CoditionalData,
+    CoditionalDataSize,
+    SecurityDataSize,
+    SecurityData,
+    /// Security section ends before its type and length
+    SecurityHeader,
+    ElectronicTicketIndicator,
+    /// Item, by IATA number, contains characters outside of its charset
+    InvalidCharset(u16),
+    /// Data follows the last leg or the security section
+    TrailingData,
+    /// Size item, by IATA number, isn't 2 hex digits, along with its bytes
+    ///
+    /// The bytes are kept inline so the error doesn't allocate, data ending
+    /// within the item is padded with spaces.
+    InvalidSizeField(u16, [u8; 2]),
+}
+
+impl Error {
+    /// `InvalidSizeField` of the first 2 bytes of the item data
+    pub(super) fn invalid_size_field(item: u16, data: &[u8]) -> Error {
+        let mut digits = [b' '; 2];
+
+        for (dst, &src) in digits.iter_mut().zip(data) {
+            *dst = src;
+        }
+
+        Error::InvalidSizeField(item, digits)
+    }
+}
+
+fn item_name(id: u16) -> &'static str {
+    spec::item(id).map(|i| i.name).unwrap_or("unknown item")
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::DataLength         => f.write_str("data is too short, mandatory items take at least 60 characters"),
+            Error::FormatCode         => f.write_str("format code must be 'M'"),
+            Error::SegmentsCount      => f.write_str("number of legs encoded must be a digit from 1 to 9"),
+            Error::Format             => f.write_str("mandatory items don't match the BCBP layout"),
+            Error::Name               => f.write_str("passenger name must be LAST/FIRST in 20 characters"),
+            Error::Date               => f.write_str("date must be a day of the year from 001 to 366"),
+            Error::CoditionalData     => f.write_str("conditional items don't match the BCBP layout"),
+            Error::CoditionalDataSize => f.write_str("conditional item size field exceeds the remaining data"),
+            Error::SecurityDataSize   => f.write_str("security data length exceeds the remaining data"),
+            Error::SecurityData       => f.write_str("security data must be '^', type and a 2 digit hex length"),
+            Error::SecurityHeader     => f.write_str("security section is cut off before its type and length"),
+            Error::ElectronicTicketIndicator => f.write_str("electronic ticket indicator must be 'E', another letter or blank"),
+            Error::InvalidCharset(item) => write!(f, "item {} ({}) contains characters outside of its charset", item, item_name(item)),
+            Error::TrailingData       => f.write_str("data follows the last leg or the security section"),
+            Error::InvalidSizeField(item, ref value) => write!(f, "size item {} ({}) must be 2 hex digits, found {:?}", item, item_name(item), String::from_utf8_lossy(value)),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Reasons a pass can't be encoded
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// Passenger name contains non ASCII characters
+    Name,
+    /// Number of legs, a pass must have 1 to 9
+    SegmentsCount(usize),
+    /// Length of the security data, which can't exceed 255 characters
+    SecurityDataSize(usize),
+    /// Seat number, which is up to 3 digits and a letter
+    Seat(String),
+    /// Check-in sequence number, which has 5 digits at most
+    Sequence(u32),
+    /// Item number that isn't a settable conditional item of the section, see `BCBP::add_item()`
+    Item(u16),
+    /// Raw item value that is too long or has characters the item doesn't allow
+    ItemValue(u16, String),
+    /// Mandatory item, by IATA number, whose value is wider than the item
+    FieldTooLong(u16, String),
+    /// Mandatory item, by IATA number, with characters outside of its charset
+    InvalidCharset(u16, String),
+    /// Mandatory item, by IATA number, which can't be blank
+    MissingMandatory(u16),
+    /// Items changed since parsing, the security section kept from it doesn't sign them
+    SignedData,
+    /// The writer failed
+    Write,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::Name                     => f.write_str("passenger name must contain only ASCII characters"),
+            BuildError::SegmentsCount(n)         => write!(f, "pass must have 1 to 9 legs, got {}", n),
+            BuildError::SecurityDataSize(n)      => write!(f, "security data can't be longer than 255 characters, got {}", n),
+            BuildError::Seat(ref seat)           => write!(f, "seat number must be up to 3 digits and a letter, got {:?}", seat),
+            BuildError::Sequence(n)              => write!(f, "check-in sequence number can't exceed 99999, got {}", n),
+            BuildError::Item(id)                 => write!(f, "item {} can't be set in this section", id),
+            BuildError::ItemValue(n, ref s)      => write!(f, "{:?} is not a valid value of item {}", s, n),
+            BuildError::FieldTooLong(n, ref s)   => write!(f, "{:?} is longer than item {} ({})", s, n, item_name(n)),
+            BuildError::InvalidCharset(n, ref s) => write!(f, "{:?} contains characters outside of the charset of item {} ({})", s, n, item_name(n)),
+            BuildError::MissingMandatory(n)      => write!(f, "item {} ({}) can't be blank", n, item_name(n)),
+            BuildError::SignedData               => f.write_str("security section signs the data the pass was parsed from, not its changed items"),
+            BuildError::Write                    => f.write_str("writer failed"),
+        }
+    }
+}
+
+impl error::Error for BuildError {}
+
+impl From<fmt::Error> for BuildError {
+    fn from(_: fmt::Error) -> BuildError {
+        BuildError::Write
+    }
+}
+
+/// Barcode data that doesn't fit the symbol it's printed in, see `BCBP::check_capacity()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityWarning {
+    /// Conditional version the length was estimated for
+    pub version: u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl fmt::Display for CapacityWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pass takes {} characters with conditional version {}, the symbol holds {}", self.len, self.version, self.capacity)
+    }
+}
+
+/// Deviation from the specification tolerated in lenient mode
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Warning {
+    /// Item, by IATA number, contains characters outside of its charset
+    Charset {
+        item: u16,
+        value: String,
+    },
+    /// Data follows the last leg or the security section, kept in `BCBP::trailing()`
+    TrailingData,
+    /// Left aligned item, by IATA number, starts with blanks or is in lower case
+    Padding {
+        item: u16,
+        value: String,
+    },
+    /// Size item, by IATA number, is larger than the data left, which was read as the whole field
+    ///
+    /// Scanners and some printers drop the trailing blanks of a section.
+    Undersized {
+        item: u16,
+        size: usize,
+        len: usize,
+    },
+}
@@ -0,0 +1,259 @@
+//! Value types of single items
+
+use std::fmt;
+
+use date::{self, DateError};
+use spec;
+
+/// Electronic ticket indicator, item 253
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum TicketIndicator {
+    Eticket,
+    Other(char),
+}
+
+impl TicketIndicator {
+    pub fn as_char(&self) -> char {
+        match *self {
+            TicketIndicator::Eticket  => 'E',
+            TicketIndicator::Other(c) => c,
+        }
+    }
+
+    /// Whether the value is allowed by the specification
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            TicketIndicator::Eticket  => true,
+            TicketIndicator::Other(c) => c == ' ' || c.is_ascii_uppercase(),
+        }
+    }
+}
+
+impl From<char> for TicketIndicator {
+    fn from(c: char) -> TicketIndicator {
+        match c {
+            'E' => TicketIndicator::Eticket,
+            c   => TicketIndicator::Other(c),
+        }
+    }
+}
+
+/// Passenger description, item 15
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum PassengerDescription {
+    Adult,
+    Male,
+    Female,
+    Child,
+    Infant,
+    /// Seat taken by cabin baggage or similar
+    NoPassenger,
+    AdultWithInfant,
+    UnaccompaniedMinor,
+    Undisclosed,
+    /// Reserved or airline specific value
+    Other(char),
+}
+
+impl PassengerDescription {
+    pub fn as_char(&self) -> char {
+        match *self {
+            PassengerDescription::Adult              => '0',
+            PassengerDescription::Male               => '1',
+            PassengerDescription::Female             => '2',
+            PassengerDescription::Child              => '3',
+            PassengerDescription::Infant             => '4',
+            PassengerDescription::NoPassenger        => '5',
+            PassengerDescription::AdultWithInfant    => '6',
+            PassengerDescription::UnaccompaniedMinor => '7',
+            PassengerDescription::Undisclosed        => '8',
+            PassengerDescription::Other(c)           => c,
+        }
+    }
+}
+
+impl From<char> for PassengerDescription {
+    fn from(c: char) -> PassengerDescription {
+        match c {
+            '0' => PassengerDescription::Adult,
+            '1' => PassengerDescription::Male,
+            '2' => PassengerDescription::Female,
+            '3' => PassengerDescription::Child,
+            '4' => PassengerDescription::Infant,
+            '5' => PassengerDescription::NoPassenger,
+            '6' => PassengerDescription::AdultWithInfant,
+            '7' => PassengerDescription::UnaccompaniedMinor,
+            '8' => PassengerDescription::Undisclosed,
+            c   => PassengerDescription::Other(c),
+        }
+    }
+}
+
+/// Channel a passenger checked in or got the boarding pass through, items 12 and 14
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Source {
+    Web,
+    Kiosk,
+    Remote,
+    Mobile,
+    AirportAgent,
+    TransferDesk,
+    Voice,
+    /// Value outside of the documented set
+    Other(char),
+}
+
+impl Source {
+    pub fn as_char(&self) -> char {
+        match *self {
+            Source::Web          => 'W',
+            Source::Kiosk        => 'K',
+            Source::Remote       => 'R',
+            Source::Mobile       => 'M',
+            Source::AirportAgent => 'O',
+            Source::TransferDesk => 'T',
+            Source::Voice        => 'V',
+            Source::Other(c)     => c,
+        }
+    }
+}
+
+impl From<char> for Source {
+    fn from(c: char) -> Source {
+        match c {
+            'W' => Source::Web,
+            'K' => Source::Kiosk,
+            'R' => Source::Remote,
+            'M' => Source::Mobile,
+            'O' => Source::AirportAgent,
+            'T' => Source::TransferDesk,
+            'V' => Source::Voice,
+            c   => Source::Other(c),
+        }
+    }
+}
+
+/// Version of the conditional items, item 9
+///
+/// Items were added to the conditional section over the versions of the
+/// resolution, the version selects the layout the section is read with.
+/// Characters that aren't a known version are kept as `Other` and read
+/// with every item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Version {
+    V1,
+    V2,
+    V3,
+    V4,
+    V5,
+    V6,
+    V7,
+    V8,
+    Other(char),
+}
+
+impl Version {
+    pub fn as_char(&self) -> char {
+        match *self {
+            Version::V1       => '1',
+            Version::V2       => '2',
+            Version::V3       => '3',
+            Version::V4       => '4',
+            Version::V5       => '5',
+            Version::V6       => '6',
+            Version::V7       => '7',
+            Version::V8       => '8',
+            Version::Other(c) => c,
+        }
+    }
+
+    /// Version number, `None` for a character that isn't a digit
+    pub fn number(&self) -> Option<u8> {
+        self.as_char().to_digit(10).map(|n| n as u8)
+    }
+
+    /// Whether the layout of the version has the item
+    pub fn defines(&self, item: &spec::Item) -> bool {
+        self.number().is_none_or(|n| item.since <= n)
+    }
+}
+
+impl From<char> for Version {
+    fn from(c: char) -> Version {
+        match c {
+            '1' => Version::V1,
+            '2' => Version::V2,
+            '3' => Version::V3,
+            '4' => Version::V4,
+            '5' => Version::V5,
+            '6' => Version::V6,
+            '7' => Version::V7,
+            '8' => Version::V8,
+            c   => Version::Other(c),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+/// Date of issue of the boarding pass, item 22
+///
+/// Encoded as the last digit of the year followed by the day of the year,
+/// like `6235` for August 22nd 2016.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IssueDate {
+    year_digit: u8,
+    day: u32,
+}
+
+impl IssueDate {
+    /// Parses the 4 digit item, `None` when it is blank or not numeric
+    pub fn parse(src: &str) -> Option<IssueDate> {
+        if src.len() != 4 || !src.chars().all(|c| c.is_ascii_digit()) {
+            return None
+        }
+
+        Some(IssueDate {
+            year_digit: src[.. 1].parse().ok()?,
+            day: src[1 ..].parse().ok()?,
+        })
+    }
+
+    pub fn year_digit(&self) -> u8 {
+        self.year_digit
+    }
+
+    pub fn day_of_year(&self) -> u32 {
+        self.day
+    }
+
+    /// Resolves the date in the latest year ending with the year digit, not after `reference_year`
+    ///
+    /// Passes are issued before they are read, so the reference is usually
+    /// the current year or the year of the flight.
+    pub fn resolve(&self, reference_year: i32) -> Result<date::Date, DateError> {
+        let year = reference_year - (reference_year - self.year_digit as i32).rem_euclid(10);
+
+        date::from_ordinal(year, self.day)
+            .ok_or(DateError::InvalidDay { year, day: self.day })
+    }
+}
+
+impl fmt::Display for IssueDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{:03}", self.year_digit, self.day)
+    }
+}
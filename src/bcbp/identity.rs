@@ -0,0 +1,40 @@
+//! Keyed hashes of passenger identity for matching passes across systems
+//!
+//! The hashed message is the normalized `LAST/FIRST` name, the PNR of the
+//! first leg and the first frequent flyer airline and number found, joined
+//! with the ASCII unit separator (0x1F). Normalization uppercases and
+//! collapses whitespace, so any system following this layout and sharing the
+//! key produces the same digest. Missing items are left empty.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use names::normalize;
+
+use super::BCBP;
+
+impl BCBP {
+    /// Lowercase hex HMAC-SHA256 of the passenger name, PNR and frequent flyer number
+    pub fn hashed_identity(&self, key: &[u8]) -> String {
+        let name = format!("{}/{}", normalize(&self.name_last), normalize(&self.name_first));
+        let pnr = self.segments.first().map(|s| normalize(&s.pnr)).unwrap_or_default();
+
+        let ff = self.segments.iter()
+            .find(|s| s.frequent_flyer_number().is_some())
+            .map(|s| format!(
+                "{}{}",
+                normalize(s.frequent_flyer_airline().unwrap_or("")),
+                normalize(s.frequent_flyer_number().unwrap_or("")),
+            ))
+            .unwrap_or_default();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+        mac.update(format!("{}\x1f{}\x1f{}", name, pnr, ff).as_bytes());
+
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
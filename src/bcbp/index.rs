@@ -0,0 +1,97 @@
+//! Collection of boarded passes with constant time duplicate checks
+
+use std::collections::HashMap;
+
+use names::normalize;
+
+use super::{BCBP, Segment};
+use super::reservation::flight_number;
+
+/// Operating carrier, flight number, Julian flight date and check-in sequence, see `Segment::same_flight()`
+type FlightSeq = (String, String, u32, u32);
+
+fn flight_seq(segment: &Segment) -> Option<FlightSeq> {
+    if segment.sequence() == 0 {
+        return None
+    }
+
+    Some((
+        normalize(segment.airline()),
+        flight_number(segment.flight_code()),
+        segment.flight_day(),
+        segment.sequence(),
+    ))
+}
+
+/// Passes indexed by flight/date/sequence of each segment and by PNR
+///
+/// Segments without a check-in sequence number can't identify a passenger on
+/// the flight and are only indexed by PNR.
+#[derive(Debug, Clone, Default)]
+pub struct BoardingIndex {
+    passes: Vec<BCBP>,
+    by_flight: HashMap<FlightSeq, usize>,
+    by_pnr: HashMap<String, Vec<usize>>,
+}
+
+impl BoardingIndex {
+    pub fn new() -> BoardingIndex {
+        BoardingIndex::default()
+    }
+
+    /// Adds the pass unless any of its segments is already boarded, returns whether it was added
+    pub fn insert(&mut self, pass: BCBP) -> bool {
+        if pass.segments.iter().any(|s| self.is_boarded(s)) {
+            return false
+        }
+
+        let idx = self.passes.len();
+
+        for segment in &pass.segments {
+            if let Some(key) = flight_seq(segment) {
+                self.by_flight.insert(key, idx);
+            }
+
+            let pnrs = self.by_pnr.entry(segment.pnr().into()).or_default();
+
+            if !pnrs.contains(&idx) {
+                pnrs.push(idx);
+            }
+        }
+
+        self.passes.push(pass);
+        true
+    }
+
+    /// Whether a pass with the same flight, date and sequence number was added
+    pub fn is_boarded(&self, segment: &Segment) -> bool {
+        flight_seq(segment)
+            .map(|key| self.by_flight.contains_key(&key))
+            .unwrap_or(false)
+    }
+
+    /// Pass which boarded the segment's flight with the segment's sequence number
+    pub fn get(&self, segment: &Segment) -> Option<&BCBP> {
+        let idx = self.by_flight.get(&flight_seq(segment)?)?;
+
+        self.passes.get(*idx)
+    }
+
+    pub fn by_pnr(&self, pnr: &str) -> Vec<&BCBP> {
+        self.by_pnr.get(pnr)
+            .map(|ids| ids.iter().map(|idx| &self.passes[*idx]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn passes(&self) -> &[BCBP] {
+        &self.passes
+    }
+
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+}
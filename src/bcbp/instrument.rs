@@ -0,0 +1,170 @@
+//! Parse instrumentation for benchmarks and regression checks
+//!
+//! `BCBP::from_instrumented()` parses as `from_with()` does and also
+//! reports the time taken, the bytes of each section and the heap
+//! allocations made. Allocations are only counted with `CountingAllocator`
+//! installed as the global allocator of the benchmark binary:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: iata::bcbp::CountingAllocator = iata::bcbp::CountingAllocator;
+//! ```
+//!
+//! The counter is process wide, allocations of other threads running at the
+//! same time are counted too.
+
+use std::fmt;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use spec;
+use super::{BCBP, ErrorContext, ParseOptions};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// System allocator counting allocations for `ParseStats::allocations`
+///
+/// Install it with `#[global_allocator]` in benchmark binaries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    /// Allocations counted since the start of the process
+    pub fn allocations() -> usize {
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Bytes of the data in each section, only filled for passes that parse
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SectionSizes {
+    /// AIM symbology identifier, like `]Q3`
+    pub symbology: usize,
+    /// Items before the first leg
+    pub mandatory: usize,
+    /// Mandatory items of all legs
+    pub legs: usize,
+    /// Unique conditional items with their version and size
+    pub unique: usize,
+    /// Repeated conditional items of all legs with their size
+    pub repeated: usize,
+    /// Airline individual use data of all legs
+    pub airline_data: usize,
+    pub security: usize,
+    pub trailing: usize,
+}
+
+impl SectionSizes {
+    pub fn total(&self) -> usize {
+        self.symbology + self.mandatory + self.legs + self.unique + self.repeated
+            + self.airline_data + self.security + self.trailing
+    }
+}
+
+/// What `BCBP::from_instrumented()` measured
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    pub elapsed: Duration,
+    /// Allocations made while parsing, 0 without `CountingAllocator`
+    pub allocations: usize,
+    pub sections: SectionSizes,
+}
+
+impl fmt::Display for ParseStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = &self.sections;
+
+        write!(f, "{:?}, {} allocations, {} bytes: mandatory {}, legs {}, unique {}, repeated {}, airline data {}, security {}, trailing {}",
+            self.elapsed, self.allocations, s.total(), s.mandatory, s.legs, s.unique, s.repeated, s.airline_data, s.security, s.trailing)
+    }
+}
+
+fn hex_size(data: &str, at: usize) -> usize {
+    data.get(at .. at + 2)
+        .and_then(|size| usize::from_str_radix(size, 16).ok())
+        .unwrap_or(0)
+}
+
+/// Walks the data of a parsed pass again to split it into sections
+fn section_sizes(src: &str, bcbp: &BCBP) -> SectionSizes {
+    let symbology = bcbp.symbology.as_ref().map(|s| s.len()).unwrap_or(0);
+    let data = &src[symbology ..];
+    let unique = bcbp.conditional_data.as_ref().map(|d| d.len()).unwrap_or(0);
+
+    let mut ret = SectionSizes {
+        symbology,
+        mandatory: spec::MANDATORY_LEN,
+        trailing: bcbp.trailing.as_ref().map(|t| t.len()).unwrap_or(0),
+        ..SectionSizes::default()
+    };
+
+    let mut pos = spec::MANDATORY_LEN;
+
+    for i in 0 .. bcbp.segments.len() {
+        let size = hex_size(data, pos + spec::LEG_LEN - 2);
+        ret.legs += spec::LEG_LEN;
+        pos += spec::LEG_LEN;
+
+        let mut chunk = size;
+
+        if i == 0 {
+            ret.unique = unique.min(chunk);
+            chunk -= ret.unique;
+        }
+
+        if chunk > 0 {
+            let repeated = (2 + hex_size(data, pos + size - chunk)).min(chunk);
+            ret.repeated += repeated;
+            ret.airline_data += chunk - repeated;
+        }
+
+        pos += size;
+    }
+
+    ret.security = data.len().saturating_sub(pos + ret.trailing);
+    ret
+}
+
+impl BCBP {
+    /// Same as `from_with()`, also measuring time, section sizes and allocations, see `CountingAllocator`
+    pub fn from_instrumented(src: &str, options: ParseOptions) -> (Result<BCBP, ErrorContext>, ParseStats) {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+
+        let ret = BCBP::from_with(src, options);
+
+        let mut stats = ParseStats {
+            elapsed: start.elapsed(),
+            allocations: ALLOCATIONS.load(Ordering::Relaxed).wrapping_sub(allocations),
+            sections: SectionSizes::default(),
+        };
+
+        if let Ok(ref bcbp) = ret {
+            stats.sections = section_sizes(src, bcbp);
+        }
+
+        (ret, stats)
+    }
+}
@@ -0,0 +1,203 @@
+//! Conditional items set by their IATA item number
+//!
+//! Not every conditional item has a typed setter yet. `add_item()` stores
+//! a raw value for any fixed width item of the right section, and the
+//! writer lays the items out in the order of `spec` along with the typed
+//! ones, padding the ones left out with spaces and computing the size
+//! fields. A raw value takes precedence over the typed item it sets.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::BCBP;
+//!
+//! let mut bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+//! bcbp.add_item(21, "SU").unwrap();
+//! bcbp.segments_mut()[0].add_item(20, "SU").unwrap();
+//! bcbp.segments_mut()[0].add_item(236, "1234567890").unwrap();
+//!
+//! let parsed = BCBP::from(&bcbp.expect_build()).unwrap();
+//! assert_eq!(parsed.segments()[0].frequent_flyer_number(), Some("1234567890"));
+//! ```
+
+use std::fmt;
+use std::collections::BTreeMap;
+
+use spec;
+use super::{BCBP, BuildError, Segment, Version};
+
+/// Marker, version and size items, which the writer fills in
+const RESERVED: [u16; 4] = [8, 9, 10, 17];
+
+/// Conditional version written when the pass doesn't have one
+const DEFAULT_VERSION: char = '6';
+
+fn settable(section: &'static [spec::Item]) -> impl Iterator<Item = &'static spec::Item> {
+    section.iter().filter(|item| !item.is_variable() && !RESERVED.contains(&item.id))
+}
+
+/// Items the layout of the version has, everything but the size fields is written by the writer
+fn layout(section: &'static [spec::Item], version: Version) -> impl Iterator<Item = &'static spec::Item> {
+    settable(section).filter(move |item| version.defines(item))
+}
+
+fn insert(section: &'static [spec::Item], items: &mut BTreeMap<u16, String>, id: u16, value: &str) -> Result<(), BuildError> {
+    let item = settable(section).find(|item| item.id == id).ok_or(BuildError::Item(id))?;
+
+    if value.chars().count() > item.len || !value.is_ascii() || !item.charset.validate(value) {
+        return Err(BuildError::ItemValue(id, value.into()))
+    }
+
+    items.insert(id, value.into());
+    Ok(())
+}
+
+/// Width of the items up to the last one set, 0 when none is
+fn block_len(section: &'static [spec::Item], version: Version, items: &BTreeMap<u16, String>) -> usize {
+    let mut len = 0;
+    let mut ret = 0;
+
+    for item in layout(section, version) {
+        len += item.len;
+
+        if items.contains_key(&item.id) {
+            ret = len;
+        }
+    }
+
+    ret
+}
+
+fn write_block<W: fmt::Write>(section: &'static [spec::Item], version: Version, items: &BTreeMap<u16, String>, w: &mut W) -> fmt::Result {
+    let len = block_len(section, version, items);
+    let mut written = 0;
+
+    for item in layout(section, version) {
+        if written >= len {
+            break
+        }
+
+        write!(w, "{:<1$}", items.get(&item.id).map_or("", String::as_str), item.len)?;
+        written += item.len;
+    }
+
+    Ok(())
+}
+
+/// Adds a typed item unless it is blank
+fn typed<T: ToString>(items: &mut BTreeMap<u16, String>, id: u16, value: Option<T>) {
+    if let Some(value) = value.map(|v| v.to_string()).filter(|v| !v.trim().is_empty()) {
+        items.insert(id, value);
+    }
+}
+
+/// Unique items of the pass, the typed ones overridden by raw values
+fn unique_items(bcbp: &BCBP) -> BTreeMap<u16, String> {
+    let u = &bcbp.unique;
+    let mut ret = BTreeMap::new();
+
+    typed(&mut ret, 15, u.pax_type.map(|d| d.as_char()));
+    typed(&mut ret, 12, u.checkin_src);
+    typed(&mut ret, 14, u.boardingpass_src);
+    typed(&mut ret, 22, u.issue_date);
+    typed(&mut ret, 16, u.doc_type);
+    typed(&mut ret, 21, u.boardingpass_airline.as_ref());
+
+    for (&id, tags) in [23, 31, 32].iter().zip(u.bag_tags.iter()) {
+        typed(&mut ret, id, tags.as_ref());
+    }
+
+    ret.extend(bcbp.items.iter().map(|(&id, value)| (id, value.clone())));
+    ret
+}
+
+/// Repeated items of a leg, the typed ones overridden by raw values
+fn repeated_items(segment: &Segment) -> BTreeMap<u16, String> {
+    let c = &segment.conditional;
+    let mut ret = BTreeMap::new();
+
+    typed(&mut ret, 142, c.airline_code.as_ref());
+    typed(&mut ret, 143, c.document_number.as_ref());
+    typed(&mut ret, 18, c.selectee);
+    typed(&mut ret, 108, c.document_verification);
+    typed(&mut ret, 19, c.marketing_airline.as_ref());
+    typed(&mut ret, 20, c.frequent_flyer_airline.as_ref());
+    typed(&mut ret, 236, c.frequent_flyer_number.as_ref());
+    typed(&mut ret, 89, c.id_ad);
+    typed(&mut ret, 118, c.baggage_allowance.as_ref());
+    typed(&mut ret, 254, c.fast_track);
+
+    ret.extend(segment.items.iter().map(|(&id, value)| (id, value.clone())));
+    ret
+}
+
+/// Writes the conditional size field of a leg and the items that follow it
+///
+/// Items are laid out for the version of the pass, ones it doesn't define
+/// are left out.
+pub(super) fn write_conditional<W: fmt::Write>(bcbp: &BCBP, leg: usize, w: &mut W) -> fmt::Result {
+    let version = bcbp.version().unwrap_or(Version::from(DEFAULT_VERSION));
+    let segment = &bcbp.segments[leg];
+
+    let unique_items = if leg == 0 { unique_items(bcbp) } else { BTreeMap::new() };
+    let repeated_items = repeated_items(segment);
+    let airline_data = segment.airline_data.as_ref().map_or("", String::as_str);
+
+    let unique = block_len(spec::UNIQUE, version, &unique_items);
+    let repeated = block_len(spec::REPEATED, version, &repeated_items);
+
+    // The first leg carries the unique items, and their header whenever it has any items or a version at all
+    let header = leg == 0 && (unique > 0 || repeated > 0 || !airline_data.is_empty() || bcbp.unique.version.is_some());
+
+    if !header && repeated == 0 && airline_data.is_empty() {
+        return w.write_str("00")
+    }
+
+    let size = 2 + repeated + airline_data.len() + if header { 4 + unique } else { 0 };
+    write!(w, "{:02X}", size)?;
+
+    if header {
+        write!(w, ">{}{:02X}", version, unique)?;
+        write_block(spec::UNIQUE, version, &unique_items, w)?;
+    }
+
+    write!(w, "{:02X}", repeated)?;
+    write_block(spec::REPEATED, version, &repeated_items, w)?;
+    w.write_str(airline_data)
+}
+
+impl BCBP {
+    /// Sets a unique conditional item by its IATA number to a raw value
+    ///
+    /// An escape hatch for items without a typed setter. Values are padded
+    /// to the width of the item, the writer orders the items and computes
+    /// the size fields. The value replaces the typed item of the same
+    /// number in the barcode.
+    pub fn add_item(&mut self, id: u16, value: &str) -> Result<(), BuildError> {
+        insert(spec::UNIQUE, &mut self.items, id, value)
+    }
+
+    /// Raw value set with `add_item()`
+    pub fn item(&self, id: u16) -> Option<&str> {
+        self.items.get(&id).map(|s| s.as_ref())
+    }
+
+    pub fn remove_item(&mut self, id: u16) -> Option<String> {
+        self.items.remove(&id)
+    }
+}
+
+impl Segment {
+    /// Sets a repeated conditional item of the leg by its IATA number, like `BCBP::add_item()`
+    pub fn add_item(&mut self, id: u16, value: &str) -> Result<(), BuildError> {
+        insert(spec::REPEATED, &mut self.items, id, value)
+    }
+
+    /// Raw value set with `add_item()`
+    pub fn item(&self, id: u16) -> Option<&str> {
+        self.items.get(&id).map(|s| s.as_ref())
+    }
+
+    pub fn remove_item(&mut self, id: u16) -> Option<String> {
+        self.items.remove(&id)
+    }
+}
@@ -0,0 +1,59 @@
+//! Parser options and entry points, the grammar is in `parser`
+
+use super::{BCBP, ErrorContext};
+use super::context;
+
+/// How tolerant `BCBP::from_with()` is to deviations from the specification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ParseMode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    /// Whether errors of `BCBP::from_with()` carry a masked snippet of the data
+    pub snippets: bool,
+}
+
+impl ParseOptions {
+    pub fn strict() -> ParseOptions {
+        ParseOptions { mode: ParseMode::Strict, snippets: false }
+    }
+
+    pub fn lenient() -> ParseOptions {
+        ParseOptions { mode: ParseMode::Lenient, snippets: false }
+    }
+
+    pub fn with_snippets(mut self, snippets: bool) -> ParseOptions {
+        self.snippets = snippets;
+        self
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.mode == ParseMode::Lenient
+    }
+}
+
+impl BCBP {
+    pub fn from(src: &str) -> Result<BCBP, ErrorContext> {
+        BCBP::from_with(src, ParseOptions::default())
+    }
+
+    /// Parses a pass, a leading AIM symbology identifier like `]Q3` is taken off first
+    ///
+    /// Strict mode enforces the widths, charsets and sizes of the
+    /// specification. Lenient mode recovers what it can and records each
+    /// deviation in `warnings()`: charsets and padding aren't enforced, data
+    /// after the pass is kept in `trailing()`, and a conditional section cut
+    /// short of its size is read as far as it goes.
+    ///
+    /// The error tells the item parsing failed in, its offset in the data
+    /// and the masked data of the item, `error` is the kind of failure.
+    pub fn from_with(src: &str, options: ParseOptions) -> Result<BCBP, ErrorContext> {
+        context::parse(src, options)
+    }
+}
@@ -0,0 +1,456 @@
+//! BCBP parsing as `nom` combinators
+//!
+//! The combinators are generic over the error type, so they compose with any
+//! error implementing `ParseError` and `FromExternalError<_, bcbp::Error>`.
+//! `bcbp::Error` itself implements both.
+//!
+//! ```
+//! # extern crate iata;
+//! # extern crate nom;
+//! use iata::bcbp::{self, parser};
+//!
+//! let framed = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000\r\n";
+//! let (rest, pass) = parser::bcbp::<bcbp::Error>(framed).unwrap();
+//!
+//! assert_eq!(rest, "\r\n");
+//! assert_eq!(pass.name(), "JOHN/SMITH JORDAN");
+//! ```
+
+use nom::{IResult, Err};
+use nom::bytes::complete::take;
+use nom::character::complete::anychar;
+use nom::error::{ErrorKind, ParseError, FromExternalError};
+
+use spec;
+use record::Layout;
+use bagtag::TagSequence;
+use super::build::Original;
+use super::{BCBP, Segment, Error, Warning, ParseOptions, SecuritySection, PassengerDescription, IssueDate, Version};
+
+impl<'a> ParseError<&'a str> for Error {
+    fn from_error_kind(_: &'a str, kind: ErrorKind) -> Error {
+        match kind {
+            ErrorKind::Eof => Error::DataLength,
+            _              => Error::Format,
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Error) -> Error {
+        other
+    }
+}
+
+impl<'a> FromExternalError<&'a str, Error> for Error {
+    fn from_external_error(_: &'a str, _: ErrorKind, e: Error) -> Error {
+        e
+    }
+}
+
+/// Parses a complete boarding pass with default options
+pub fn bcbp<'a, E>(input: &'a str) -> IResult<&'a str, BCBP, E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    bcbp_with(ParseOptions::default())(input)
+}
+
+/// Parses a complete boarding pass, leaving anything after the security section unconsumed
+pub fn bcbp_with<'a, E>(options: ParseOptions) -> impl Fn(&'a str) -> IResult<&'a str, BCBP, E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    move |input| {
+        let mut bcbp = BCBP::new();
+        let start = input;
+
+        // Errors of the items point at the item itself
+        let (rest, format) = anychar(input)?;
+
+        if !format.eq_ignore_ascii_case(&'M') {
+            return fail(input, Error::FormatCode)
+        }
+
+        let input = rest;
+        let (rest, legs) = anychar(input)?;
+
+        let legs_count = match legs.to_digit(10) {
+            Some(n) if n >= 1 => n,
+            _ => return fail(input, Error::SegmentsCount),
+        };
+
+        let name_start = rest;
+        let (input, name) = take(20usize)(name_start)?;
+        let (rest, ticket_indicator) = anychar(input)?;
+
+        bcbp.ticket_indicator = ticket_indicator.to_ascii_uppercase().into();
+
+        if !options.is_lenient() && !bcbp.ticket_indicator.is_valid() {
+            return fail(input, Error::ElectronicTicketIndicator)
+        }
+
+        match passenger_name(name) {
+            Some((last, first)) => {
+                bcbp.name_last  = last;
+                bcbp.name_first = first;
+            },
+            None => return fail(name_start, Error::Name),
+        }
+
+        let mut input = rest;
+
+        for i in 0 .. legs_count {
+            let (rest, (mut segment, size)) = segment(&options, &mut bcbp.warnings, input)?;
+
+            let (rest, chunk) = sized(&options, &mut bcbp.warnings, rest, 6, size)?;
+
+            input = rest;
+
+            if chunk.is_empty() {
+                bcbp.segments.push(segment);
+                continue
+            }
+
+            let mut chunk = chunk;
+
+            if i == 0 {
+                let (rest, ()) = unique(&options, &mut bcbp, chunk)?;
+                chunk = rest;
+            }
+
+            // Unique items may take the whole conditional section of the first leg
+            if !chunk.is_empty() {
+                let (_, (structured, airline_data)) = repeated(&options, &mut bcbp.warnings, chunk)?;
+
+                let mut block = Block::new(structured, bcbp.version());
+
+                // The items following the size item of the block
+                block.check(&options, &mut bcbp.warnings, &spec::REPEATED[1 ..])?;
+
+                let c = &mut segment.conditional;
+
+                c.airline_code           = block.text(142);
+                c.document_number        = block.text(143);
+                c.selectee               = block.text_char(18);
+                c.document_verification  = block.text_char(108);
+                c.marketing_airline      = block.text(19);
+                c.frequent_flyer_airline = block.text(20);
+                c.frequent_flyer_number  = block.text(236);
+                c.id_ad                  = block.text_char(89);
+                c.baggage_allowance      = block.text(118);
+                c.fast_track             = block.text_char(254);
+
+                if !airline_data.is_empty() {
+                    segment.airline_data = Some(airline_data.into());
+                }
+            }
+
+            bcbp.segments.push(segment);
+        }
+
+        let signed = &start[.. start.len() - input.len()];
+
+        if input.starts_with('^') {
+            let (rest, security) = security(&options, input)?;
+            bcbp.security = Some(security);
+            input = rest;
+        }
+
+        bcbp.original = Some(Original::new(&bcbp, signed));
+
+        Ok((input, bcbp))
+    }
+}
+
+fn fail<'a, O, E>(input: &'a str, e: Error) -> IResult<&'a str, O, E>
+    where E: FromExternalError<&'a str, Error>
+{
+    Err(Err::Failure(E::from_external_error(input, ErrorKind::Verify, e)))
+}
+
+/// Checks an item against its charset, strict mode fails and lenient mode records a warning
+fn charset<'a, E>(options: &ParseOptions, warnings: &mut Vec<Warning>, input: &'a str, item: u16, value: &str) -> Result<(), Err<E>>
+    where E: FromExternalError<&'a str, Error>
+{
+    let valid = spec::item(item)
+        .map(|spec| spec.charset.validate(&value.to_uppercase()))
+        .unwrap_or(true);
+
+    if valid {
+        return Ok(())
+    }
+
+    if options.is_lenient() {
+        warnings.push(Warning::Charset { item, value: value.into() });
+        return Ok(())
+    }
+
+    Err(Err::Failure(E::from_external_error(input, ErrorKind::Verify, Error::InvalidCharset(item))))
+}
+
+fn u32_from_str_force(src: &str, radix: u32) -> u32 {
+    u32::from_str_radix(src.trim().trim_start_matches('0'), radix).unwrap_or_default()
+}
+
+/// Two digit hex size item, by IATA number, of the following variable size field
+fn size<'a, E>(item: u16) -> impl Fn(&'a str) -> IResult<&'a str, usize, E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    move |input| {
+        let (rest, digits) = take(2usize)(input)?;
+
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return fail(input, Error::invalid_size_field(item, digits.as_bytes()))
+        }
+
+        Ok((rest, usize::from_str_radix(digits, 16).unwrap_or_default()))
+    }
+}
+
+/// Field of a size item, by IATA number, lenient mode takes what is left of a cut off one
+fn sized<'a, E>(options: &ParseOptions, warnings: &mut Vec<Warning>, input: &'a str, item: u16, size: usize) -> IResult<&'a str, &'a str, E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    match take::<_, _, E>(size)(input) {
+        Ok(ok) => Ok(ok),
+        Err(_) if options.is_lenient() => {
+            warnings.push(Warning::Undersized { item, size, len: input.len() });
+            Ok(("", input))
+        },
+        Err(_) => fail(input, Error::CoditionalDataSize),
+    }
+}
+
+/// Splits the name field into last and first names
+pub(super) fn passenger_name(src: &str) -> Option<(String, String)> {
+    let src = src.trim_end();
+    let (last, first) = match src.find('/') {
+        Some(pos) => (&src[.. pos], &src[pos + 1 ..]),
+        None      => (src, ""),
+    };
+
+    if last.is_empty() || !last.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None
+    }
+
+    Some((last.to_uppercase(), first.trim().to_uppercase()))
+}
+
+fn segment<'a, E>(options: &ParseOptions, warnings: &mut Vec<Warning>, start: &'a str) -> IResult<&'a str, (Segment, usize), E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    // Every leg item but the conditional size, which is checked as hex below
+    let layout = Layout::from_items(&spec::LEG[.. spec::LEG.len() - 1]);
+
+    let record = match layout.slice(start) {
+        Ok(record) => record,
+        Err(e) => {
+            let at = start.char_indices().nth(e.offset).map_or(start.len(), |(i, _)| i);
+            return Err(Err::Error(E::from_error_kind(&start[at ..], ErrorKind::Eof)))
+        },
+    };
+
+    let (input, size) = size(6)(record.rest())?;
+
+    let mut values = spec::LEG.iter()
+        .map(|item| item.id)
+        .zip(record.values().map(|value| value.unwrap_or("")));
+
+    let mut items = [(0, ""); 10];
+    for item in items.iter_mut() {
+        *item = values.next().unwrap_or_default();
+    }
+
+    let [(_, pnr), (_, src), (_, dst), (_, airline), (_, flight_code), (_, flight_day),
+         (_, compartment), (_, seat), (_, sequence), (_, pax_status)] = items;
+
+    for &(item, value) in items.iter() {
+        // Errors point at the item itself, every value is a slice of `start`
+        let at = value.as_ptr() as usize - start.as_ptr() as usize;
+        charset(options, warnings, &start[at ..], item, value)?;
+    }
+
+    // Left aligned items scanned with leading blanks or in lower case
+    if options.is_lenient() {
+        for &(item, value) in items[.. 4].iter() {
+            if (value.starts_with(' ') && !value.trim().is_empty()) || value.chars().any(|c| c.is_lowercase()) {
+                warnings.push(Warning::Padding { item, value: value.into() });
+            }
+        }
+    }
+
+    let mut segment = Segment::new();
+
+    segment.pnr = pnr.trim().to_uppercase();
+    segment.src_airport = src.trim().to_uppercase();
+    segment.dst_airport = dst.trim().to_uppercase();
+    segment.airline = airline.trim().to_uppercase();
+    segment.flight_code = flight_code.trim().to_uppercase();
+    segment.flight_day = u32_from_str_force(flight_day, 10);
+    segment.compartment = compartment.chars().next().unwrap_or(' ').to_ascii_uppercase();
+    segment.seat = seat.trim().trim_start_matches('0').to_uppercase();
+    segment.sequence = u32_from_str_force(sequence, 10);
+    segment.pax_status = pax_status.trim().to_uppercase();
+
+    Ok((input, (segment, size)))
+}
+
+/// Conditional block read item by item in the layout of a version
+struct Block<'a> {
+    data: &'a str,
+    pos: usize,
+    version: Option<Version>,
+}
+
+impl<'a> Block<'a> {
+    /// Block of a pass without a version is read with every item
+    fn new(data: &'a str, version: Option<Version>) -> Block<'a> {
+        Block { data, pos: 0, version }
+    }
+
+    /// Takes an optional item by IATA number, `None` when cut off or not in the layout of the version
+    fn item(&mut self, id: u16) -> Option<&'a str> {
+        let item = spec::item(id)?;
+
+        if !self.version.is_none_or(|v| v.defines(item)) {
+            return None
+        }
+
+        let ret = self.data.get(self.pos .. self.pos + item.len)?;
+        self.pos += item.len;
+        Some(ret)
+    }
+
+    fn item_char(&mut self, id: u16) -> Option<char> {
+        self.item(id).and_then(|s| s.chars().next())
+    }
+
+    /// Item without padding, uppercased, `None` when blank or missing
+    fn text(&mut self, id: u16) -> Option<String> {
+        self.item(id).map(str::trim).filter(|s| !s.is_empty()).map(str::to_uppercase)
+    }
+
+    fn text_char(&mut self, id: u16) -> Option<char> {
+        self.text(id).and_then(|s| s.chars().next())
+    }
+
+    /// Checks the fixed width items of a section against their charsets, see `charset()`
+    fn check<E>(&self, options: &ParseOptions, warnings: &mut Vec<Warning>, section: &[spec::Item]) -> Result<(), Err<E>>
+        where E: FromExternalError<&'a str, Error>
+    {
+        let mut block = Block::new(self.data, self.version);
+
+        for item in section.iter().filter(|item| !item.is_variable()) {
+            if let Some(value) = block.item(item.id) {
+                charset(options, warnings, value, item.id, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Unique conditional items, stored directly into the pass
+fn unique<'a, E>(options: &ParseOptions, bcbp: &mut BCBP, input: &'a str) -> IResult<&'a str, (), E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    let (rest, marker) = anychar(input)?;
+
+    if marker != '>' && marker != '<' {
+        return fail(input, Error::CoditionalData)
+    }
+
+    // The guide allows the version and its size to be left out of a short section
+    if rest.is_empty() {
+        bcbp.conditional_data = Some(input.into());
+        return Ok((rest, ()))
+    }
+
+    let (rest, version) = anychar(rest)?;
+
+    bcbp.unique.version = Some(version);
+
+    if rest.is_empty() {
+        bcbp.conditional_data = Some(input.into());
+        return Ok((rest, ()))
+    }
+
+    let (rest, size) = size(10)(rest)?;
+
+    let (rest, block) = sized(options, &mut bcbp.warnings, rest, 10, size)?;
+
+    let mut block = Block::new(block, bcbp.version());
+
+    bcbp.conditional_data    = Some(input[.. input.len() - rest.len()].into());
+    bcbp.unique.pax_type         = block.item_char(15).map(PassengerDescription::from);
+    bcbp.unique.checkin_src      = block.item_char(12);
+    bcbp.unique.boardingpass_src = block.item_char(14);
+
+    let issue_day = block.item(22);
+    bcbp.unique.doc_type = block.item_char(16);
+    let issuer = block.item(21);
+
+    // Errors point at the item itself
+    for &(id, value) in [(22, issue_day), (21, issuer)].iter() {
+        if let Some(value) = value {
+            charset(options, &mut bcbp.warnings, value, id, value)?;
+        }
+    }
+
+    bcbp.unique.issue_date = issue_day.and_then(IssueDate::parse);
+    bcbp.unique.boardingpass_airline = issuer.map(|s| s.trim().to_uppercase());
+
+    for (&id, slot) in [23, 31, 32].iter().zip(bcbp.unique.bag_tags.iter_mut()) {
+        let tags = block.item(id);
+
+        if let Some(tags) = tags {
+            charset(options, &mut bcbp.warnings, tags, id, tags)?;
+        }
+
+        *slot = tags.and_then(TagSequence::parse);
+    }
+
+    Ok((rest, ()))
+}
+
+/// Repeated conditional items, returns the structured block and the airline use data following it
+fn repeated<'a, E>(options: &ParseOptions, warnings: &mut Vec<Warning>, input: &'a str) -> IResult<&'a str, (&'a str, &'a str), E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    let (rest, size) = match size::<E>(17)(input) {
+        Ok(ok) => ok,
+        Err(Err::Error(_)) => return fail(input, Error::CoditionalData),
+        Err(e) => return Err(e),
+    };
+
+    let (rest, structured) = sized(options, warnings, rest, 17, size)?;
+
+    Ok(("", (structured, rest)))
+}
+
+fn security<'a, E>(options: &ParseOptions, input: &'a str) -> IResult<&'a str, SecuritySection, E>
+    where E: ParseError<&'a str> + FromExternalError<&'a str, Error>
+{
+    let body = &input[1 ..];
+
+    if body.len() < 3 && !options.is_lenient() {
+        return fail(input, Error::SecurityHeader)
+    }
+
+    let digits = body.get(1..3);
+    let size = digits
+        .filter(|size| size.chars().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|size| usize::from_str_radix(size, 16).ok());
+
+    let data = size.and_then(|size| body.get(3 .. size + 3));
+
+    match (size, data) {
+        (Some(size), Some(data)) => {
+            let section = SecuritySection::Standard {
+                kind: body.chars().next().unwrap_or(' '),
+                data: data.into(),
+            };
+            Ok((&body[size + 3 ..], section))
+        },
+        _ if options.is_lenient() => Ok(("", SecuritySection::Legacy(body.into()))),
+        (Some(_), None) => fail(input, Error::SecurityDataSize),
+        (None, _)       => fail(input, Error::invalid_size_field(29, body.get(1 ..).unwrap_or_default().as_bytes())),
+    }
+}
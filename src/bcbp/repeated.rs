@@ -0,0 +1,101 @@
+//! Repeated conditional items, the structured block each leg carries before its airline use data
+
+use std::fmt;
+
+use redact::Redacted;
+
+/// Typed items of the repeated conditional section of a leg, items 142 to 254
+///
+/// Blank items are `None`.
+#[derive(Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SegmentConditional {
+    pub(super) airline_code: Option<String>,
+    pub(super) document_number: Option<String>,
+    pub(super) selectee: Option<char>,
+    pub(super) document_verification: Option<char>,
+    pub(super) marketing_airline: Option<String>,
+    pub(super) frequent_flyer_airline: Option<String>,
+    pub(super) frequent_flyer_number: Option<String>,
+    pub(super) id_ad: Option<char>,
+    pub(super) baggage_allowance: Option<String>,
+    pub(super) fast_track: Option<char>,
+}
+
+impl fmt::Debug for SegmentConditional {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SegmentConditional")
+            .field("airline_code", &self.airline_code)
+            .field("document_number", &Redacted(&self.document_number))
+            .field("selectee", &self.selectee)
+            .field("document_verification", &self.document_verification)
+            .field("marketing_airline", &self.marketing_airline)
+            .field("frequent_flyer_airline", &self.frequent_flyer_airline)
+            .field("frequent_flyer_number", &Redacted(&self.frequent_flyer_number))
+            .field("id_ad", &self.id_ad)
+            .field("baggage_allowance", &self.baggage_allowance)
+            .field("fast_track", &self.fast_track)
+            .finish()
+    }
+}
+
+impl SegmentConditional {
+    /// Airline numeric code, item 142, like `555`
+    pub fn airline_code(&self) -> Option<&str> {
+        self.airline_code.as_deref()
+    }
+
+    /// Document form and serial number, item 143
+    pub fn document_number(&self) -> Option<&str> {
+        self.document_number.as_deref()
+    }
+
+    /// Selectee indicator, item 18
+    pub fn selectee(&self) -> Option<char> {
+        self.selectee
+    }
+
+    /// International documentation verification, item 108
+    pub fn document_verification(&self) -> Option<char> {
+        self.document_verification
+    }
+
+    /// Marketing carrier designator, item 19
+    pub fn marketing_airline(&self) -> Option<&str> {
+        self.marketing_airline.as_deref()
+    }
+
+    /// Frequent flyer airline designator, item 20
+    pub fn frequent_flyer_airline(&self) -> Option<&str> {
+        self.frequent_flyer_airline.as_deref()
+    }
+
+    /// Frequent flyer number, item 236
+    pub fn frequent_flyer_number(&self) -> Option<&str> {
+        self.frequent_flyer_number.as_deref()
+    }
+
+    /// ID/AD indicator of industry discount travel, item 89
+    pub fn id_ad(&self) -> Option<char> {
+        self.id_ad
+    }
+
+    /// Free baggage allowance, item 118, like `20K` or `2PC`
+    pub fn baggage_allowance(&self) -> Option<&str> {
+        self.baggage_allowance.as_deref()
+    }
+
+    /// Fast track, item 254, `Some(false)` for an explicit `N`
+    pub fn fast_track(&self) -> Option<bool> {
+        match self.fast_track {
+            Some('Y') => Some(true),
+            Some('N') => Some(false),
+            _         => None,
+        }
+    }
+
+    /// Whether the leg has no repeated conditional items
+    pub fn is_empty(&self) -> bool {
+        *self == SegmentConditional::default()
+    }
+}
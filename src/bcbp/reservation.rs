@@ -0,0 +1,123 @@
+//! Cross-checks of passes against reservation records of a departure control system
+
+use date;
+use names::{self, normalize};
+
+use super::{BCBP, Segment};
+
+/// The reservation data a pass is expected to agree with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservationSummary {
+    pub pnr: String,
+    pub name_last: String,
+    pub name_first: String,
+    /// Operating carrier designator
+    pub airline: String,
+    pub flight_code: String,
+    pub date: date::Date,
+}
+
+/// Items of a pass that disagree with the reservation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReservationField {
+    Pnr,
+    Name,
+    Flight,
+    Date,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReservationMatch {
+    Exact,
+    /// Everything agrees, the reservation name was cut to fit the name field
+    NameTruncated,
+    /// Items of the best matching leg that disagree
+    Mismatch(Vec<ReservationField>),
+}
+
+impl ReservationMatch {
+    pub fn is_match(&self) -> bool {
+        !matches!(*self, ReservationMatch::Mismatch(_))
+    }
+}
+
+/// Flight number without padding, case or leading zeros, `0010` and `10` are the same flight
+pub(super) fn flight_number(src: &str) -> String {
+    normalize(src).trim_start_matches('0').into()
+}
+
+impl ReservationSummary {
+    fn name(&self) -> String {
+        let last = normalize(&self.name_last);
+        let first = normalize(&self.name_first);
+
+        if first.is_empty() {
+            last
+        } else {
+            format!("{}/{}", last, first)
+        }
+    }
+
+    fn leg_mismatches(&self, segment: &Segment) -> Vec<ReservationField> {
+        let mut ret = Vec::new();
+
+        if normalize(&segment.pnr) != normalize(&self.pnr) {
+            ret.push(ReservationField::Pnr);
+        }
+
+        if normalize(&segment.airline) != normalize(&self.airline)
+            || flight_number(&segment.flight_code) != flight_number(&self.flight_code)
+        {
+            ret.push(ReservationField::Flight);
+        }
+
+        if segment.try_flight_date(date::year(&self.date)) != Ok(self.date) {
+            ret.push(ReservationField::Date);
+        }
+
+        ret
+    }
+}
+
+impl Segment {
+    /// Whether both legs are the same flight: carrier, flight number, flight day and city pair
+    ///
+    /// Seats, sequence numbers and the other items of the passenger are
+    /// ignored, and so is formatting, like case, padding and leading zeros
+    /// of the flight number.
+    pub fn same_flight(&self, other: &Segment) -> bool {
+        normalize(&self.airline) == normalize(&other.airline)
+            && flight_number(&self.flight_code) == flight_number(&other.flight_code)
+            && self.flight_day == other.flight_day
+            && normalize(&self.src_airport) == normalize(&other.src_airport)
+            && normalize(&self.dst_airport) == normalize(&other.dst_airport)
+    }
+}
+
+impl BCBP {
+    /// Compares the pass with a reservation, picking the leg agreeing with it best
+    pub fn matches_reservation(&self, reservation: &ReservationSummary) -> ReservationMatch {
+        let expected = reservation.name();
+        let actual = format!("{}/{}", self.name_last, self.name_first);
+        let actual = actual.trim_end_matches('/');
+
+        let name_truncated = names::is_field_truncation(&expected, &self.name());
+
+        let mut mismatches = self.segments.iter()
+            .map(|s| reservation.leg_mismatches(s))
+            .min_by_key(|m| m.len())
+            .unwrap_or_else(|| vec![ReservationField::Pnr, ReservationField::Flight, ReservationField::Date]);
+
+        if normalize(actual) != expected && !name_truncated {
+            mismatches.insert(0, ReservationField::Name);
+        }
+
+        match (mismatches.is_empty(), name_truncated) {
+            (true, false) => ReservationMatch::Exact,
+            (true, true)  => ReservationMatch::NameTruncated,
+            (false, _)    => ReservationMatch::Mismatch(mismatches),
+        }
+    }
+}
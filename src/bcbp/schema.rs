@@ -0,0 +1,162 @@
+//! Versioned JSON shape of passes for API consumers
+//!
+//! The derived `Serialize` of `BCBP` follows its internal layout and may
+//! change between releases. `PassV1` is the documented `bcbp/1` shape: its
+//! field names and types never change, new optional fields may be added and
+//! readers must ignore fields they don't know. A breaking change gets a new
+//! schema version and a new type.
+//!
+//! ```json
+//! {
+//!   "schema": "bcbp/1",
+//!   "name_last": "JOHN",
+//!   "name_first": "SMITH JORDAN",
+//!   "ticket_indicator": "E",
+//!   "conditional_version": null,
+//!   "passenger_description": null,
+//!   "checkin_source": null,
+//!   "boarding_pass_source": null,
+//!   "boarding_pass_issue_day": null,
+//!   "boarding_pass_issuer": null,
+//!   "document_type": null,
+//!   "legs": [{
+//!     "pnr": "ABCDEF",
+//!     "from": "JFK",
+//!     "to": "SVO",
+//!     "carrier": "SU",
+//!     "flight": "1234A",
+//!     "flight_day": 1,
+//!     "compartment": "Y",
+//!     "seat": "1Z",
+//!     "sequence": 7,
+//!     "passenger_status": "0",
+//!     "frequent_flyer_airline": null,
+//!     "frequent_flyer_number": null,
+//!     "airline_data": null
+//!   }],
+//!   "security_type": null,
+//!   "security_data": null
+//! }
+//! ```
+//!
+//! Single character items are strings, absent items are `null`.
+//! `BCBP::to_schema_v1_with()` adds `airline_fields` to the legs, the
+//! airline use data decoded by a registry, and leaves it out otherwise.
+
+use std::collections::BTreeMap;
+
+use super::{BCBP, ExtensionRegistry, Segment, SecuritySection};
+
+/// Identifier of the schema, always the first field of a document
+pub const SCHEMA_V1: &str = "bcbp/1";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PassV1 {
+    pub schema: String,
+    pub name_last: String,
+    pub name_first: String,
+    pub ticket_indicator: String,
+    pub conditional_version: Option<String>,
+    pub passenger_description: Option<String>,
+    pub checkin_source: Option<String>,
+    pub boarding_pass_source: Option<String>,
+    pub boarding_pass_issue_day: Option<u32>,
+    pub boarding_pass_issuer: Option<String>,
+    pub document_type: Option<String>,
+    pub legs: Vec<LegV1>,
+    pub security_type: Option<String>,
+    pub security_data: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegV1 {
+    pub pnr: String,
+    pub from: String,
+    pub to: String,
+    pub carrier: String,
+    pub flight: String,
+    /// Julian day of the flight, 0 when not set
+    pub flight_day: u32,
+    pub compartment: String,
+    pub seat: String,
+    /// Check-in sequence, 0 when not set
+    pub sequence: u32,
+    pub passenger_status: String,
+    pub frequent_flyer_airline: Option<String>,
+    pub frequent_flyer_number: Option<String>,
+    pub airline_data: Option<String>,
+    /// Airline use data decoded by the carrier's `ConditionalExtension`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub airline_fields: Option<BTreeMap<String, String>>,
+}
+
+fn opt_char(c: Option<char>) -> Option<String> {
+    c.filter(|&c| c != ' ').map(|c| c.to_string())
+}
+
+impl<'a> From<&'a Segment> for LegV1 {
+    fn from(s: &'a Segment) -> LegV1 {
+        LegV1 {
+            pnr: s.pnr.clone(),
+            from: s.src_airport.clone(),
+            to: s.dst_airport.clone(),
+            carrier: s.airline.clone(),
+            flight: s.flight_code.clone(),
+            flight_day: s.flight_day,
+            compartment: s.compartment.to_string(),
+            seat: s.seat.clone(),
+            sequence: s.sequence,
+            passenger_status: s.pax_status.clone(),
+            frequent_flyer_airline: s.conditional.frequent_flyer_airline.clone(),
+            frequent_flyer_number: s.conditional.frequent_flyer_number.clone(),
+            airline_data: s.airline_data.clone(),
+            airline_fields: None,
+        }
+    }
+}
+
+impl<'a> From<&'a BCBP> for PassV1 {
+    fn from(bcbp: &'a BCBP) -> PassV1 {
+        let (security_type, security_data) = match bcbp.security {
+            Some(SecuritySection::Standard { kind, ref data }) => (Some(kind.to_string()), Some(data.clone())),
+            Some(SecuritySection::Legacy(ref raw)) => (None, Some(raw.clone())),
+            None => (None, None),
+        };
+
+        PassV1 {
+            schema: SCHEMA_V1.into(),
+            name_last: bcbp.name_last.clone(),
+            name_first: bcbp.name_first.clone(),
+            ticket_indicator: bcbp.ticket_indicator.as_char().to_string(),
+            conditional_version: opt_char(bcbp.unique.version),
+            passenger_description: opt_char(bcbp.unique.pax_type.map(|d| d.as_char())),
+            checkin_source: opt_char(bcbp.unique.checkin_src),
+            boarding_pass_source: opt_char(bcbp.unique.boardingpass_src),
+            // Kept as the number the 4 digits read as, for compatibility of the schema
+            boarding_pass_issue_day: bcbp.unique.issue_date.map(|d| d.year_digit() as u32 * 1000 + d.day_of_year()).filter(|&day| day > 0),
+            boarding_pass_issuer: bcbp.unique.boardingpass_airline.clone().filter(|s| !s.is_empty()),
+            document_type: opt_char(bcbp.unique.doc_type),
+            legs: bcbp.segments.iter().map(LegV1::from).collect(),
+            security_type,
+            security_data,
+        }
+    }
+}
+
+impl BCBP {
+    /// The pass in the stable `bcbp/1` JSON shape
+    pub fn to_schema_v1(&self) -> PassV1 {
+        PassV1::from(self)
+    }
+
+    /// Same as `to_schema_v1()`, with the airline use data of each leg decoded by the registry
+    pub fn to_schema_v1_with(&self, registry: &ExtensionRegistry) -> PassV1 {
+        let mut ret = PassV1::from(self);
+
+        for (leg, segment) in ret.legs.iter_mut().zip(&self.segments) {
+            leg.airline_fields = registry.decode(segment);
+        }
+
+        ret
+    }
+}
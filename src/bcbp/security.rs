@@ -0,0 +1,44 @@
+//! Security data section
+
+use std::fmt;
+
+use super::BuildError;
+
+/// Security data section, starting with `^`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum SecuritySection {
+    /// Type of security data, 2 hex digit length and the data itself
+    Standard {
+        kind: char,
+        data: String,
+    },
+    /// Data following `^` without the type and length header, accepted only in lenient mode
+    Legacy(String),
+}
+
+impl SecuritySection {
+    pub(super) fn validate(&self) -> Result<(), BuildError> {
+        match *self {
+            SecuritySection::Standard { ref data, .. } if data.len() > 0xFF => {
+                Err(BuildError::SecurityDataSize(data.len()))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn encoded_len(&self) -> usize {
+        match *self {
+            SecuritySection::Standard { ref data, .. } => 4 + data.len(),
+            SecuritySection::Legacy(ref raw) => 1 + raw.len(),
+        }
+    }
+
+    pub(super) fn write_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        match *self {
+            SecuritySection::Standard { kind, ref data } => write!(w, "^{}{:02X}{}", kind, data.len(), data),
+            SecuritySection::Legacy(ref raw) => write!(w, "^{}", raw),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Check-in sequence number allocation
+//!
+//! A departure control system numbers passengers of a flight in check-in
+//! order. `SequenceAllocator` abstracts where the last number is kept, so a
+//! prototype can start with `MemoryAllocator` and move to a database later.
+
+use std::fmt;
+use std::error;
+use std::collections::HashMap;
+
+use flight::FlightKey;
+
+/// Largest sequence number the barcode holds
+pub const MAX_SEQUENCE: u32 = 99999;
+
+/// Reasons a sequence number can't be allocated
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SequenceError {
+    /// All numbers of the flight are taken
+    Exhausted,
+    /// Failure of the backing store
+    Storage(String),
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SequenceError::Exhausted      => write!(f, "check-in sequence numbers up to {} are taken", MAX_SEQUENCE),
+            SequenceError::Storage(ref e) => write!(f, "sequence storage failed: {}", e),
+        }
+    }
+}
+
+impl error::Error for SequenceError {}
+
+/// Source of monotonically increasing sequence numbers per flight and date
+pub trait SequenceAllocator {
+    /// Takes the next number of the flight, numbers start at 1
+    fn allocate(&mut self, flight: &FlightKey) -> Result<u32, SequenceError>;
+}
+
+/// Allocator keeping the last number of each flight in memory
+#[derive(Debug, Clone, Default)]
+pub struct MemoryAllocator {
+    last: HashMap<FlightKey, u32>,
+}
+
+impl MemoryAllocator {
+    pub fn new() -> MemoryAllocator {
+        MemoryAllocator::default()
+    }
+
+    /// Last number allocated for the flight
+    pub fn last(&self, flight: &FlightKey) -> Option<u32> {
+        self.last.get(flight).cloned()
+    }
+
+    /// Continues numbering of the flight after `last`, like after a restart
+    pub fn resume(&mut self, flight: FlightKey, last: u32) {
+        self.last.insert(flight, last);
+    }
+}
+
+impl SequenceAllocator for MemoryAllocator {
+    fn allocate(&mut self, flight: &FlightKey) -> Result<u32, SequenceError> {
+        let last = self.last.get(flight).cloned().unwrap_or(0);
+
+        if last >= MAX_SEQUENCE {
+            return Err(SequenceError::Exhausted)
+        }
+
+        self.last.insert(flight.clone(), last + 1);
+        Ok(last + 1)
+    }
+}
@@ -0,0 +1,131 @@
+//! Mandatory only copies of passes for legacy readers
+//!
+//! Some old gate readers reject barcodes longer than the mandatory items.
+//! `BCBP::mandatory_only()` drops everything else and tells what was lost,
+//! so the caller can decide whether the reader still gets enough data.
+
+use std::fmt;
+use std::mem;
+use std::collections::BTreeMap;
+
+use spec;
+use super::{BCBP, SegmentConditional};
+
+/// Data `BCBP::mandatory_only()` removed, items are given by their IATA number
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stripped {
+    /// Unique conditional items, like 15 for the passenger description
+    pub items: Vec<u16>,
+    /// Repeated conditional items by leg index, legs without any are left out
+    pub leg_items: Vec<(usize, Vec<u16>)>,
+    /// The pass had a security section
+    pub security: bool,
+    /// Characters of data after the last leg or the security section
+    pub trailing: usize,
+}
+
+impl Stripped {
+    /// Whether the pass had mandatory items only
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.leg_items.is_empty() && !self.security && self.trailing == 0
+    }
+}
+
+impl fmt::Display for Stripped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = |id: u16| spec::item(id).map(|item| item.name).unwrap_or("unknown item");
+        let mut parts = Vec::new();
+
+        for &id in &self.items {
+            parts.push(format!("{} ({})", name(id), id));
+        }
+
+        for &(leg, ref items) in &self.leg_items {
+            for &id in items {
+                parts.push(format!("{} ({}) of leg {}", name(id), id, leg + 1));
+            }
+        }
+
+        if self.security {
+            parts.push("security data".into());
+        }
+
+        if self.trailing > 0 {
+            parts.push(format!("{} characters of trailing data", self.trailing));
+        }
+
+        if parts.is_empty() {
+            f.write_str("nothing")
+        } else {
+            f.write_str(&parts.join(", "))
+        }
+    }
+}
+
+impl BCBP {
+    /// Copy with the mandatory items only, and what was removed
+    ///
+    /// The copy builds with zero conditional size fields, the mandatory
+    /// items are kept as they are.
+    pub fn mandatory_only(&self) -> (BCBP, Stripped) {
+        let mut ret = self.clone();
+        let mut stripped = Stripped::default();
+
+        let unique = [
+            (9,  ret.unique.version.take().is_some()),
+            (15, ret.unique.pax_type.take().is_some()),
+            (12, ret.unique.checkin_src.take().is_some()),
+            (14, ret.unique.boardingpass_src.take().is_some()),
+            (22, ret.unique.issue_date.take().is_some()),
+            (16, ret.unique.doc_type.take().is_some()),
+            (21, ret.unique.boardingpass_airline.take().is_some()),
+            (23, ret.unique.bag_tags[0].take().is_some()),
+            (31, ret.unique.bag_tags[1].take().is_some()),
+            (32, ret.unique.bag_tags[2].take().is_some()),
+        ];
+
+        stripped.items = unique.iter().filter(|&&(_, present)| present).map(|&(id, _)| id).collect();
+        merge_raw(&mut stripped.items, &mut ret.items);
+        ret.conditional_data = None;
+
+        for (i, s) in ret.segments.iter_mut().enumerate() {
+            let repeated = [
+                (142, s.conditional.airline_code.is_some()),
+                (143, s.conditional.document_number.is_some()),
+                (18,  s.conditional.selectee.is_some()),
+                (108, s.conditional.document_verification.is_some()),
+                (19,  s.conditional.marketing_airline.is_some()),
+                (20,  s.conditional.frequent_flyer_airline.is_some()),
+                (236, s.conditional.frequent_flyer_number.is_some()),
+                (89,  s.conditional.id_ad.is_some()),
+                (118, s.conditional.baggage_allowance.is_some()),
+                (254, s.conditional.fast_track.is_some()),
+                (4,   s.airline_data.take().is_some()),
+            ];
+
+            s.conditional = SegmentConditional::default();
+
+            let mut items: Vec<u16> = repeated.iter().filter(|&&(_, present)| present).map(|&(id, _)| id).collect();
+            merge_raw(&mut items, &mut s.items);
+
+            if !items.is_empty() {
+                stripped.leg_items.push((i, items));
+            }
+        }
+
+        stripped.security = ret.security.take().is_some();
+        stripped.trailing = ret.trailing.take().map(|t| t.chars().count()).unwrap_or(0);
+        ret.warnings.clear();
+
+        (ret, stripped)
+    }
+}
+
+/// Adds the numbers of raw items set with `add_item()` and clears them
+fn merge_raw(ids: &mut Vec<u16>, raw: &mut BTreeMap<u16, String>) {
+    for id in mem::take(raw).into_keys() {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+}
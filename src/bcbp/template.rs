@@ -0,0 +1,225 @@
+//! Bulk issuance of passes for a flight
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::{PassTemplate, TemplateError};
+//! use iata::date;
+//! use iata::flight::FlightKey;
+//!
+//! let key = FlightKey::new("SU", 100, None, date::from_ymd(2017, 1, 1).unwrap(), "JFK");
+//! let mut template = PassTemplate::new(key, "SVO").with_compartment('Y');
+//!
+//! let passes: Vec<_> = template.issue_all(vec![
+//!     ("JOHN/SMITH", "ABCDEF", "1A", 1),
+//!     ("JANE/DOE", "GHIJKL", "1A", 2),
+//! ]).collect();
+//!
+//! assert_eq!(passes[0].as_ref().unwrap().expect_build(), "M1JOHN/SMITH          EABCDEF JFKSVOSU 0100 001Y001A0001 100");
+//! assert_eq!(passes[1].as_ref().err(), Some(&TemplateError::DuplicateSeat("1A".into())));
+//! ```
+
+use std::fmt;
+use std::error;
+use std::collections::HashSet;
+
+use date;
+use flight::FlightKey;
+use pax_status::PaxStatus;
+use seatmap::{SeatError, SeatMap};
+use super::{parser, BCBP, Segment, TicketIndicator, SequenceAllocator, SequenceError};
+
+/// Reasons a template can't issue a pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TemplateError {
+    /// Name is not `LAST/FIRST` with an alphabetic last name
+    Name,
+    /// Seat is already taken by a pass of the template
+    DuplicateSeat(String),
+    /// Seat is refused by the seat map of the template
+    Seat(SeatError),
+    /// Check-in sequence number is already taken by a pass of the template
+    DuplicateSequence(u32),
+    /// Sequence allocator failed
+    Sequence(SequenceError),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TemplateError::Name                 => f.write_str("passenger name must be LAST/FIRST"),
+            TemplateError::DuplicateSeat(ref s) => write!(f, "seat {} is already issued", s),
+            TemplateError::DuplicateSequence(n) => write!(f, "check-in sequence number {} is already issued", n),
+            TemplateError::Seat(ref e)          => e.fmt(f),
+            TemplateError::Sequence(ref e)      => e.fmt(f),
+        }
+    }
+}
+
+impl error::Error for TemplateError {}
+
+impl From<SequenceError> for TemplateError {
+    fn from(e: SequenceError) -> TemplateError {
+        TemplateError::Sequence(e)
+    }
+}
+
+impl From<SeatError> for TemplateError {
+    fn from(e: SeatError) -> TemplateError {
+        TemplateError::Seat(e)
+    }
+}
+
+/// Flight level items shared by the passes issued for it
+///
+/// Seats and sequence numbers of issued passes are remembered, a blank seat
+/// or a zero sequence number is never a duplicate. With a seat map, seats
+/// also have to exist on the aircraft and not be blocked.
+#[derive(Debug, Clone)]
+pub struct PassTemplate {
+    flight: FlightKey,
+    destination: String,
+    compartment: char,
+    pax_status: PaxStatus,
+    seats: HashSet<String>,
+    sequences: HashSet<u32>,
+    seat_map: Option<SeatMap>,
+}
+
+impl PassTemplate {
+    pub fn new(flight: FlightKey, destination: &str) -> PassTemplate {
+        PassTemplate {
+            flight,
+            destination: destination.trim().to_uppercase(),
+            compartment: 'Y',
+            pax_status: PaxStatus::CheckedIn,
+            seats: HashSet::new(),
+            sequences: HashSet::new(),
+            seat_map: None,
+        }
+    }
+
+    pub fn with_compartment(mut self, compartment: char) -> PassTemplate {
+        self.compartment = compartment.to_ascii_uppercase();
+        self
+    }
+
+    /// Passenger status of issued passes, checked in by default
+    pub fn with_pax_status(mut self, status: PaxStatus) -> PassTemplate {
+        self.pax_status = status;
+        self
+    }
+
+    /// Seat map to check seats against, see `iata::seatmap::AircraftTypes`
+    pub fn with_seat_map(mut self, map: SeatMap) -> PassTemplate {
+        self.seat_map = Some(map);
+        self
+    }
+
+    pub fn flight(&self) -> &FlightKey {
+        &self.flight
+    }
+
+    pub fn destination(&self) -> &str {
+        self.destination.as_ref()
+    }
+
+    /// Number of seats taken by issued passes
+    pub fn seats_issued(&self) -> usize {
+        self.seats.len()
+    }
+
+    /// Stamps out a pass, `name` is `LAST/FIRST`
+    pub fn issue(&mut self, name: &str, pnr: &str, seat: &str, sequence: u32) -> Result<BCBP, TemplateError> {
+        let (name_last, name_first) = parser::passenger_name(name).ok_or(TemplateError::Name)?;
+
+        let seat = self.check_seat(seat)?;
+
+        if sequence != 0 && self.sequences.contains(&sequence) {
+            return Err(TemplateError::DuplicateSequence(sequence))
+        }
+
+        let mut segment = Segment::new();
+        segment.pnr = pnr.trim().to_uppercase();
+        segment.src_airport = self.flight.departure.clone();
+        segment.dst_airport = self.destination.clone();
+        segment.airline = self.flight.airline.clone();
+        segment.flight_code = format!("{:04}{}", self.flight.number, self.flight.suffix.unwrap_or(' '));
+        segment.flight_day = date::ordinal(&self.flight.date);
+        segment.compartment = self.compartment;
+        segment.seat = seat.clone();
+        segment.sequence = sequence;
+        segment.pax_status = self.pax_status.as_char().to_string();
+
+        let mut bcbp = BCBP::new();
+        bcbp.name_last = name_last;
+        bcbp.name_first = name_first;
+        bcbp.ticket_indicator = TicketIndicator::Eticket;
+        bcbp.segments.push(segment);
+
+        if !seat.is_empty() {
+            self.seats.insert(seat);
+        }
+        if sequence != 0 {
+            self.sequences.insert(sequence);
+        }
+
+        Ok(bcbp)
+    }
+
+    /// Stamps out a pass numbered by the allocator
+    ///
+    /// The name and seat are checked first, so a rejected passenger doesn't
+    /// take a number.
+    pub fn issue_next<A>(&mut self, allocator: &mut A, name: &str, pnr: &str, seat: &str) -> Result<BCBP, TemplateError>
+        where A: SequenceAllocator + ?Sized
+    {
+        parser::passenger_name(name).ok_or(TemplateError::Name)?;
+        self.check_seat(seat)?;
+
+        let sequence = allocator.allocate(&self.flight)?;
+        self.issue(name, pnr, seat, sequence)
+    }
+
+    fn check_seat(&self, seat: &str) -> Result<String, TemplateError> {
+        let seat = seat.trim().trim_start_matches('0').to_uppercase();
+
+        if !seat.is_empty() && self.seats.contains(&seat) {
+            return Err(TemplateError::DuplicateSeat(seat))
+        }
+
+        if let (false, Some(map)) = (seat.is_empty(), self.seat_map.as_ref()) {
+            map.check(&seat)?;
+        }
+
+        Ok(seat)
+    }
+
+    /// Issues a pass for each `(name, pnr, seat, sequence)`, lazily and in order
+    pub fn issue_all<I, N, P, S>(&mut self, passengers: I) -> Issue<'_, I::IntoIter>
+        where I: IntoIterator<Item = (N, P, S, u32)>, N: AsRef<str>, P: AsRef<str>, S: AsRef<str>
+    {
+        Issue {
+            template: self,
+            passengers: passengers.into_iter(),
+        }
+    }
+}
+
+/// Iterator over passes issued by `PassTemplate::issue_all()`
+#[derive(Debug)]
+pub struct Issue<'a, I> {
+    template: &'a mut PassTemplate,
+    passengers: I,
+}
+
+impl<'a, I, N, P, S> Iterator for Issue<'a, I>
+    where I: Iterator<Item = (N, P, S, u32)>, N: AsRef<str>, P: AsRef<str>, S: AsRef<str>
+{
+    type Item = Result<BCBP, TemplateError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (name, pnr, seat, sequence) = self.passengers.next()?;
+        Some(self.template.issue(name.as_ref(), pnr.as_ref(), seat.as_ref(), sequence))
+    }
+}
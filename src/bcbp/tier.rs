@@ -0,0 +1,164 @@
+//! Built in decoders of frequent flyer tier markers
+//!
+//! Several carriers put the alliance status of the passenger into the
+//! airline individual use data as an asterisk followed by a letter. The
+//! letter depends on the alliance of the carrier:
+//!
+//! | Alliance       | Marker | Tier       |
+//! |----------------|--------|------------|
+//! | Star Alliance  | `*G`   | Gold       |
+//! | Star Alliance  | `*S`   | Silver     |
+//! | oneworld       | `*E`   | Emerald    |
+//! | oneworld       | `*P`   | Sapphire   |
+//! | oneworld       | `*R`   | Ruby       |
+//! | SkyTeam        | `*X`   | Elite Plus |
+//! | SkyTeam        | `*E`   | Elite      |
+
+use std::fmt;
+use std::collections::BTreeMap;
+
+use super::{ExtensionRegistry, Segment};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Alliance {
+    StarAlliance,
+    Oneworld,
+    SkyTeam,
+}
+
+const STAR_ALLIANCE: &[&str] = &[
+    "A3", "AC", "AI", "AV", "BR", "CA", "CM", "ET", "LH", "LO", "LX", "MS",
+    "NH", "NZ", "OS", "OU", "OZ", "SA", "SK", "SN", "SQ", "TG", "TK", "TP", "UA", "ZH",
+];
+
+const ONEWORLD: &[&str] = &[
+    "AA", "AS", "AY", "BA", "CX", "FJ", "IB", "JL", "MH", "QF", "QR", "RJ", "UL", "WY",
+];
+
+const SKYTEAM: &[&str] = &[
+    "AF", "AM", "AR", "CI", "DL", "GA", "KE", "KL", "KQ", "ME", "MU", "RO", "SV", "VN", "VS", "XY",
+];
+
+impl Alliance {
+    /// Alliance the airline belongs to, by its two letter designator
+    pub fn of(airline: &str) -> Option<Alliance> {
+        let airline = airline.trim().to_uppercase();
+        let airline = airline.as_str();
+
+        if STAR_ALLIANCE.contains(&airline) {
+            Some(Alliance::StarAlliance)
+        } else if ONEWORLD.contains(&airline) {
+            Some(Alliance::Oneworld)
+        } else if SKYTEAM.contains(&airline) {
+            Some(Alliance::SkyTeam)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FfTier {
+    StarGold,
+    StarSilver,
+    OneworldEmerald,
+    OneworldSapphire,
+    OneworldRuby,
+    SkyTeamElitePlus,
+    SkyTeamElite,
+}
+
+impl FfTier {
+    pub fn alliance(&self) -> Alliance {
+        match *self {
+            FfTier::StarGold | FfTier::StarSilver => Alliance::StarAlliance,
+            FfTier::OneworldEmerald | FfTier::OneworldSapphire | FfTier::OneworldRuby => Alliance::Oneworld,
+            FfTier::SkyTeamElitePlus | FfTier::SkyTeamElite => Alliance::SkyTeam,
+        }
+    }
+
+    fn from_marker(alliance: Alliance, marker: char) -> Option<FfTier> {
+        let tier = match (alliance, marker) {
+            (Alliance::StarAlliance, 'G') => FfTier::StarGold,
+            (Alliance::StarAlliance, 'S') => FfTier::StarSilver,
+            (Alliance::Oneworld, 'E')     => FfTier::OneworldEmerald,
+            (Alliance::Oneworld, 'P')     => FfTier::OneworldSapphire,
+            (Alliance::Oneworld, 'R')     => FfTier::OneworldRuby,
+            (Alliance::SkyTeam, 'X')      => FfTier::SkyTeamElitePlus,
+            (Alliance::SkyTeam, 'E')      => FfTier::SkyTeamElite,
+            _                             => return None,
+        };
+
+        Some(tier)
+    }
+
+    /// Finds the first tier marker of the alliance in airline use data
+    pub fn decode(alliance: Alliance, data: &str) -> Option<FfTier> {
+        let bytes = data.as_bytes();
+
+        bytes.iter()
+            .enumerate()
+            .filter(|&(i, &b)| b == b'*' && i + 1 < bytes.len())
+            .filter(|&(i, _)| !bytes.get(i + 2).is_some_and(u8::is_ascii_alphanumeric))
+            .filter_map(|(i, _)| FfTier::from_marker(alliance, bytes[i + 1] as char))
+            .next()
+    }
+}
+
+impl fmt::Display for FfTier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            FfTier::StarGold         => "Star Alliance Gold",
+            FfTier::StarSilver       => "Star Alliance Silver",
+            FfTier::OneworldEmerald  => "oneworld Emerald",
+            FfTier::OneworldSapphire => "oneworld Sapphire",
+            FfTier::OneworldRuby     => "oneworld Ruby",
+            FfTier::SkyTeamElitePlus => "SkyTeam Elite Plus",
+            FfTier::SkyTeamElite     => "SkyTeam Elite",
+        };
+
+        f.write_str(name)
+    }
+}
+
+impl Segment {
+    /// Alliance tier of the passenger, when the airline use data carries a known marker
+    ///
+    /// The tier is looked up by the alliance of the frequent flyer program,
+    /// falling back to the operating carrier.
+    pub fn ff_tier(&self) -> Option<FfTier> {
+        let data = self.airline_data()?;
+        let airline = self.frequent_flyer_airline().unwrap_or_else(|| self.airline());
+
+        FfTier::decode(Alliance::of(airline)?, data)
+    }
+}
+
+impl ExtensionRegistry {
+    /// Registry with tier decoders for the alliance carriers, each yields an `ff_tier` field
+    pub fn builtin() -> ExtensionRegistry {
+        let mut registry = ExtensionRegistry::new();
+
+        let alliances = [
+            (Alliance::StarAlliance, STAR_ALLIANCE),
+            (Alliance::Oneworld, ONEWORLD),
+            (Alliance::SkyTeam, SKYTEAM),
+        ];
+
+        for &(alliance, airlines) in alliances.iter() {
+            for airline in airlines {
+                registry.register(airline, move |data: &str| {
+                    let tier = FfTier::decode(alliance, data)?;
+
+                    let mut fields = BTreeMap::new();
+                    fields.insert("ff_tier".to_string(), tier.to_string());
+                    Some(fields)
+                });
+            }
+        }
+
+        registry
+    }
+}
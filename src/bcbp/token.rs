@@ -0,0 +1,70 @@
+//! Short boarding tokens for turnstiles that can't store full pass data
+//!
+//! A token is the HMAC-SHA256 of the first leg's carrier, flight number,
+//! flight day, departure airport and check-in sequence number plus a time
+//! window index, joined with the ASCII unit separator (0x1F). The first 50
+//! bits are encoded as 10 characters of Crockford's base32. A captured token
+//! stops working when its window is over; a turnstile still has to remember
+//! tokens it accepted within the window to refuse a second entry.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::BCBP;
+
+const TOKEN_LEN: usize = 10;
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Index of the window of `window_secs` seconds containing Unix time `now`
+pub fn token_window(now: i64, window_secs: u32) -> u64 {
+    (now.max(0) as u64) / u64::from(window_secs.max(1))
+}
+
+fn encode(digest: &[u8]) -> String {
+    let bits = digest.iter().take(8).fold(0u64, |acc, &b| acc << 8 | u64::from(b));
+
+    (0 .. TOKEN_LEN)
+        .map(|i| ALPHABET[(bits >> (59 - 5 * i) & 0x1F) as usize] as char)
+        .collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl BCBP {
+    /// Token of the first leg valid in the given window, `None` for a pass without legs
+    ///
+    /// See `token_window()` for the window index of a Unix time.
+    pub fn boarding_token(&self, secret: &[u8], window: u64) -> Option<String> {
+        let s = self.segments.first()?;
+
+        let message = format!(
+            "{}{}\x1f{}\x1f{}\x1f{}\x1f{}",
+            s.airline.trim(),
+            s.flight_code.trim().trim_start_matches('0'),
+            s.flight_day,
+            s.src_airport.trim(),
+            s.sequence,
+            window,
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+        mac.update(message.to_uppercase().as_bytes());
+
+        Some(encode(&mac.finalize().into_bytes()))
+    }
+
+    /// Checks a token against the current and the previous window
+    ///
+    /// The previous window is accepted too, so a token issued just before
+    /// a window boundary still opens the gate.
+    pub fn verify_boarding_token(&self, secret: &[u8], window: u64, token: &str) -> bool {
+        let token = token.trim().to_uppercase();
+
+        [Some(window), window.checked_sub(1)].iter()
+            .filter_map(|&w| w)
+            .filter_map(|w| self.boarding_token(secret, w))
+            .fold(false, |ok, expected| ok | constant_time_eq(expected.as_bytes(), token.as_bytes()))
+    }
+}
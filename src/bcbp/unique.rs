@@ -0,0 +1,84 @@
+//! Unique conditional items, the ones a pass carries once before the repeated items of its first leg
+
+use bagtag::TagSequence;
+use super::{IssueDate, PassengerDescription, Source};
+
+/// Typed items of the unique conditional section, items 9 to 32
+///
+/// Blank single character items are kept as a space, the accessors give
+/// `None` for them where a blank has no meaning.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConditionalUnique {
+    pub(super) version: Option<char>,
+    pub(super) pax_type: Option<PassengerDescription>,
+    pub(super) checkin_src: Option<char>,
+    pub(super) boardingpass_src: Option<char>,
+    pub(super) issue_date: Option<IssueDate>,
+    pub(super) doc_type: Option<char>,
+    pub(super) boardingpass_airline: Option<String>,
+    /// Items 23, 31 and 32
+    pub(super) bag_tags: [Option<TagSequence>; 3],
+}
+
+impl ConditionalUnique {
+    /// Version number, item 9
+    pub fn version(&self) -> Option<char> {
+        self.version
+    }
+
+    /// Passenger description, item 15
+    pub fn passenger_description(&self) -> Option<PassengerDescription> {
+        self.pax_type
+    }
+
+    /// Source of check-in, item 12
+    pub fn checkin_source(&self) -> Option<Source> {
+        self.checkin_src.filter(|&c| c != ' ').map(Source::from)
+    }
+
+    /// Source of boarding pass issuance, item 14
+    pub fn boarding_pass_source(&self) -> Option<Source> {
+        self.boardingpass_src.filter(|&c| c != ' ').map(Source::from)
+    }
+
+    /// Date of issue of the boarding pass, item 22
+    pub fn issue_date(&self) -> Option<IssueDate> {
+        self.issue_date
+    }
+
+    /// Document type, item 16, `B` for a boarding pass and `I` for an itinerary receipt
+    pub fn document_type(&self) -> Option<char> {
+        self.doc_type.filter(|&c| c != ' ')
+    }
+
+    /// Airline designator of the boarding pass issuer, item 21
+    pub fn issuer_airline(&self) -> Option<&str> {
+        self.boardingpass_airline.as_deref().filter(|s| !s.is_empty())
+    }
+
+    /// Baggage tag sequences of items 23, 31 and 32, blank items are left out
+    pub fn bag_tags(&self) -> Vec<&TagSequence> {
+        self.bag_tags.iter().flatten().collect()
+    }
+
+    /// Baggage tag licence plate numbers, item 23
+    pub fn bag_tag_numbers(&self) -> Option<&TagSequence> {
+        self.bag_tags[0].as_ref()
+    }
+
+    /// First non-consecutive baggage tag licence plate numbers, item 31
+    pub fn first_non_consecutive_bag_tags(&self) -> Option<&TagSequence> {
+        self.bag_tags[1].as_ref()
+    }
+
+    /// Second non-consecutive baggage tag licence plate numbers, item 32
+    pub fn second_non_consecutive_bag_tags(&self) -> Option<&TagSequence> {
+        self.bag_tags[2].as_ref()
+    }
+
+    /// Whether the pass has no unique conditional items, not even a version
+    pub fn is_empty(&self) -> bool {
+        *self == ConditionalUnique::default()
+    }
+}
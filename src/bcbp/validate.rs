@@ -0,0 +1,238 @@
+//! Allocation free validation for constrained readers
+//!
+//! Turnstile firmware often only needs to know that a barcode is a well
+//! formed pass and which flight and seat it is for. `validate_only()` walks
+//! the data in place with the offsets of `spec` and copies the few items it
+//! extracts into a fixed size `Summary`, without touching the heap.
+//!
+//! It checks what strict parsing checks for the structure: the mandatory
+//! items with their charsets, the conditional size fields and markers, the
+//! security section header and the absence of trailing data. Conditional
+//! items themselves are skipped, so a pass with a malformed baggage tag
+//! number validates here while `BCBP::from()` refuses it.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp;
+//!
+//! let summary = bcbp::validate_only(b"M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+//! assert_eq!(summary.airline(), "SU");
+//! assert_eq!(summary.flight_code(), "1234A");
+//! assert_eq!(summary.seat(), "1Z");
+//! assert_eq!(summary.sequence(), 7);
+//! ```
+
+use std::str;
+
+use spec;
+use super::Error;
+
+/// First leg of a pass as `validate_only()` extracts it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    legs: u8,
+    src_airport: [u8; 3],
+    dst_airport: [u8; 3],
+    airline: [u8; 3],
+    flight_code: [u8; 5],
+    flight_day: u16,
+    seat: [u8; 4],
+    sequence: u32,
+}
+
+fn text(bytes: &[u8]) -> &str {
+    str::from_utf8(bytes).unwrap_or("").trim()
+}
+
+impl Summary {
+    pub fn segments_count(&self) -> u8 {
+        self.legs
+    }
+
+    pub fn src_airport(&self) -> &str {
+        text(&self.src_airport)
+    }
+
+    pub fn dst_airport(&self) -> &str {
+        text(&self.dst_airport)
+    }
+
+    pub fn airline(&self) -> &str {
+        text(&self.airline)
+    }
+
+    pub fn flight_code(&self) -> &str {
+        text(&self.flight_code)
+    }
+
+    /// Day of the year, 0 when blank
+    pub fn flight_day(&self) -> u16 {
+        self.flight_day
+    }
+
+    /// Seat without leading zeros, like `Segment::seat()`
+    pub fn seat(&self) -> &str {
+        text(&self.seat).trim_start_matches('0')
+    }
+
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+}
+
+/// Size item, by IATA number, `missing` when the data ends before it
+fn hex_size(data: &[u8], at: usize, item: u16, missing: Error) -> Result<usize, Error> {
+    let digits = data.get(at .. at + 2).ok_or(missing)?;
+
+    str::from_utf8(digits).ok()
+        .filter(|size| size.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|size| usize::from_str_radix(size, 16).ok())
+        .ok_or_else(|| Error::invalid_size_field(item, digits))
+}
+
+fn number(bytes: &[u8]) -> u32 {
+    bytes.iter()
+        .filter(|b| b.is_ascii_digit())
+        .fold(0, |acc, &b| acc * 10 + u32::from(b - b'0'))
+}
+
+fn copy<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut ret = [b' '; N];
+    for (dst, src) in ret.iter_mut().zip(bytes) {
+        *dst = src.to_ascii_uppercase();
+    }
+    ret
+}
+
+/// Checks the structure of a pass and extracts its first leg, see the module docs
+pub fn validate_only(data: &[u8]) -> Result<Summary, Error> {
+    let data = match data.first() {
+        Some(b']') if data.len() >= 3 => &data[3 ..],
+        _ => data,
+    };
+
+    if data.len() < spec::MANDATORY_LEN + spec::LEG_LEN {
+        return Err(Error::DataLength)
+    }
+
+    if !data.is_ascii() {
+        return Err(Error::Format)
+    }
+
+    if !data[0].eq_ignore_ascii_case(&b'M') {
+        return Err(Error::FormatCode)
+    }
+
+    let legs = match data[1] {
+        b'1' ..= b'9' => data[1] - b'0',
+        _ => return Err(Error::SegmentsCount),
+    };
+
+    let name = str::from_utf8(&data[2 .. 22]).unwrap_or("").trim_end();
+    let last = name.split('/').next().unwrap_or("");
+
+    if last.is_empty() || !last.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return Err(Error::Name)
+    }
+
+    if !(data[22] == b' ' || data[22].is_ascii_alphabetic()) {
+        return Err(Error::ElectronicTicketIndicator)
+    }
+
+    let mut summary = None;
+    let mut pos = spec::MANDATORY_LEN;
+
+    for leg in 0 .. legs {
+        let start = pos;
+
+        for item in &spec::LEG[.. spec::LEG.len() - 1] {
+            let value = data.get(pos .. pos + item.len).ok_or(Error::DataLength)?;
+
+            if !value.iter().all(|&b| item.charset.accepts(char::from(b.to_ascii_uppercase()))) {
+                return Err(Error::InvalidCharset(item.id))
+            }
+
+            pos += item.len;
+        }
+
+        let size = hex_size(data, pos, 6, Error::CoditionalDataSize)?;
+        pos += 2;
+
+        let chunk = data.get(pos .. pos + size).ok_or(Error::CoditionalDataSize)?;
+        pos += size;
+
+        if leg == 0 {
+            let at = |id| start + spec::section_offset(spec::LEG, id).unwrap_or(0);
+
+            summary = Some(Summary {
+                legs,
+                src_airport: copy(&data[at(26) ..]),
+                dst_airport: copy(&data[at(38) ..]),
+                airline: copy(&data[at(42) ..]),
+                flight_code: copy(&data[at(43) ..]),
+                flight_day: number(&data[at(46) .. at(46) + 3]) as u16,
+                seat: copy(&data[at(104) ..]),
+                sequence: number(&data[at(107) .. at(107) + 5]),
+            });
+
+            check_conditional(chunk, true)?;
+        } else {
+            check_conditional(chunk, false)?;
+        }
+    }
+
+    let rest = &data[pos ..];
+
+    let rest = match rest.first() {
+        Some(b'^') => {
+            if rest.len() < 4 {
+                return Err(Error::SecurityHeader)
+            }
+
+            let size = hex_size(rest, 2, 29, Error::SecurityData)?;
+            rest.get(4 + size ..).ok_or(Error::SecurityDataSize)?
+        },
+        _ => rest,
+    };
+
+    if !rest.is_empty() {
+        return Err(Error::TrailingData)
+    }
+
+    summary.ok_or(Error::SegmentsCount)
+}
+
+/// Checks the size fields of a conditional section, unique items come first on the first leg
+fn check_conditional(chunk: &[u8], first: bool) -> Result<(), Error> {
+    let mut chunk = chunk;
+
+    if chunk.is_empty() {
+        return Ok(())
+    }
+
+    if first {
+        if chunk[0] != b'>' && chunk[0] != b'<' {
+            return Err(Error::CoditionalData)
+        }
+
+        // The version and its size may be left out of a short section
+        if chunk.len() <= 2 {
+            return Ok(())
+        }
+
+        let size = hex_size(chunk, 2, 10, Error::CoditionalDataSize)?;
+        chunk = chunk.get(4 + size ..).ok_or(Error::CoditionalDataSize)?;
+
+        if chunk.is_empty() {
+            return Ok(())
+        }
+    }
+
+    let size = hex_size(chunk, 0, 17, Error::CoditionalData)?;
+
+    if 2 + size > chunk.len() {
+        return Err(Error::CoditionalDataSize)
+    }
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Prints aggregate statistics of a file of scanned passes, one pass per line
+//!
+//! Usage: `bcbp-stats [--lenient] <FILE>`, `-` reads standard input. Only
+//! counts are printed, never passenger data.
+
+extern crate iata;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process;
+
+use iata::bcbp::ParseOptions;
+use iata::report::CorpusStats;
+
+fn usage() -> ! {
+    eprintln!("usage: bcbp-stats [--lenient] <FILE>");
+    process::exit(2)
+}
+
+fn main() {
+    let mut options = ParseOptions::strict();
+    let mut path = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_ref() {
+            "--lenient" => options = ParseOptions::lenient(),
+            "-h" | "--help" => usage(),
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let reader: Box<dyn BufRead> = match path.as_ref().map(|p| p.as_ref()) {
+        Some("-") => Box::new(BufReader::new(io::stdin())),
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("bcbp-stats: {}: {}", path, e);
+                process::exit(1)
+            },
+        },
+        None => usage(),
+    };
+
+    let mut stats = CorpusStats::new();
+
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("bcbp-stats: {}", e);
+                process::exit(1)
+            },
+        };
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if !line.trim().is_empty() {
+            stats.add(line, options.clone());
+        }
+    }
+
+    print!("{}", stats);
+}
@@ -0,0 +1,81 @@
+//! Boarding zones from the compartment and check-in sequence of a leg
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::BCBP;
+//! use iata::boarding::BoardingPolicy;
+//!
+//! let pass = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+//! let segment = &pass.segments()[0];
+//!
+//! assert_eq!(BoardingPolicy::sequential().boarding_zone(segment), 1);
+//! assert_eq!(BoardingPolicy::premium_first().boarding_zone(segment), 3);
+//! ```
+
+use bcbp::Segment;
+
+/// Airline rules mapping a leg to its boarding zone, zones are numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardingPolicy {
+    /// Compartment codes boarding first, each in a zone of its own
+    pub priority_compartments: String,
+    /// Passengers per general zone, counted by check-in sequence
+    pub zone_size: u32,
+    /// Number of general zones, the rest of passengers board with the last one
+    pub zones: u8,
+}
+
+impl BoardingPolicy {
+    /// All compartments together, five zones of 30 passengers in check-in order
+    pub fn sequential() -> BoardingPolicy {
+        BoardingPolicy {
+            priority_compartments: String::new(),
+            zone_size: 30,
+            zones: 5,
+        }
+    }
+
+    /// First and business class ahead of four economy zones of 40 passengers
+    pub fn premium_first() -> BoardingPolicy {
+        BoardingPolicy {
+            priority_compartments: "FJ".into(),
+            zone_size: 40,
+            zones: 4,
+        }
+    }
+
+    /// Everybody boards at once
+    pub fn single_zone() -> BoardingPolicy {
+        BoardingPolicy {
+            priority_compartments: String::new(),
+            zone_size: u32::MAX,
+            zones: 1,
+        }
+    }
+
+    /// Zone of a leg, passengers without a check-in sequence board last
+    pub fn boarding_zone(&self, segment: &Segment) -> u8 {
+        let compartment = segment.compartment().to_ascii_uppercase();
+        let priority = self.priority_compartments.to_uppercase();
+
+        if let Some(pos) = priority.chars().position(|c| c == compartment) {
+            return pos as u8 + 1
+        }
+
+        let first = priority.chars().count() as u8 + 1;
+        let zones = self.zones.max(1);
+
+        let zone = match segment.sequence() {
+            0   => zones - 1,
+            seq => ((seq - 1) / self.zone_size.max(1)).min(zones as u32 - 1) as u8,
+        };
+
+        first + zone
+    }
+}
+
+impl Segment {
+    pub fn boarding_zone(&self, policy: &BoardingPolicy) -> u8 {
+        policy.boarding_zone(self)
+    }
+}
@@ -0,0 +1,83 @@
+//! Airline designator to ICAO code mapping and ATC callsigns
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::callsign::CallsignTable;
+//!
+//! let table = CallsignTable::builtin();
+//! assert_eq!(table.callsign("SU", "1234").unwrap(), "AFL1234");
+//! assert_eq!(table.callsign("LH", "0012A").unwrap(), "DLH12A");
+//! assert_eq!(table.iata("EZY"), Some("U2"));
+//! ```
+
+use std::collections::HashMap;
+
+use bcbp::Segment;
+
+const BUILTIN: [(&str, &str); 22] = [
+    ("AA", "AAL"), ("AF", "AFR"), ("AY", "FIN"), ("AZ", "ITY"), ("BA", "BAW"), ("DL", "DAL"),
+    ("EK", "UAE"), ("FR", "RYR"), ("IB", "IBE"), ("KL", "KLM"), ("LH", "DLH"), ("LX", "SWR"),
+    ("OS", "AUA"), ("QR", "QTR"), ("S7", "SBI"), ("SK", "SAS"), ("SN", "BEL"), ("SU", "AFL"),
+    ("TK", "THY"), ("U2", "EZY"), ("UA", "UAL"), ("W6", "WZZ"),
+];
+
+/// Bidirectional IATA designator to ICAO code table
+#[derive(Debug, Clone, Default)]
+pub struct CallsignTable {
+    icao: HashMap<String, String>,
+    iata: HashMap<String, String>,
+}
+
+impl CallsignTable {
+    pub fn new() -> CallsignTable {
+        CallsignTable::default()
+    }
+
+    /// Table with a few major carriers, extend it with `insert()`
+    pub fn builtin() -> CallsignTable {
+        let mut ret = CallsignTable::new();
+
+        for &(iata, icao) in BUILTIN.iter() {
+            ret.insert(iata, icao);
+        }
+
+        ret
+    }
+
+    pub fn insert(&mut self, iata: &str, icao: &str) {
+        let iata = iata.trim().to_uppercase();
+        let icao = icao.trim().to_uppercase();
+
+        self.icao.insert(iata.clone(), icao.clone());
+        self.iata.insert(icao, iata);
+    }
+
+    pub fn icao(&self, iata: &str) -> Option<&str> {
+        self.icao.get(iata.trim()).map(|s| s.as_ref())
+    }
+
+    pub fn iata(&self, icao: &str) -> Option<&str> {
+        self.iata.get(icao.trim()).map(|s| s.as_ref())
+    }
+
+    /// IATA designator and ICAO code pairs in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.icao.iter().map(|(iata, icao)| (iata.as_ref(), icao.as_ref()))
+    }
+
+    /// Likely ATC callsign, ICAO code followed by the flight number without leading zeros
+    pub fn callsign(&self, airline: &str, flight_code: &str) -> Option<String> {
+        let icao = self.icao(airline)?;
+        let number = flight_code.trim().trim_start_matches('0');
+
+        if number.is_empty() {
+            return None
+        }
+
+        Some(format!("{}{}", icao, number))
+    }
+
+    pub fn segment_callsign(&self, segment: &Segment) -> Option<String> {
+        self.callsign(segment.airline(), segment.flight_code())
+    }
+}
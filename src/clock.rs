@@ -0,0 +1,26 @@
+use date::{self, Date};
+
+/// Source of the current date for APIs which resolve partial dates
+pub trait Clock {
+    fn today(&self) -> Date;
+}
+
+/// Clock backed by the system time in UTC
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> Date {
+        date::today()
+    }
+}
+
+/// Clock always returning the same date, mostly useful for tests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedClock(pub Date);
+
+impl Clock for FixedClock {
+    fn today(&self) -> Date {
+        self.0
+    }
+}
@@ -0,0 +1,169 @@
+//! Flight coupon status, as kept by the validating carrier for each coupon of a ticket
+//!
+//! Transitions follow the lifecycle of a coupon from sale to use or refund,
+//! so reconciliation can reject status updates arriving out of order.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::coupon::CouponStatus;
+//!
+//! let status = CouponStatus::Open
+//!     .transition(CouponStatus::CheckedIn).unwrap()
+//!     .transition(CouponStatus::Lifted).unwrap()
+//!     .transition(CouponStatus::Flown).unwrap();
+//!
+//! assert!(status.is_final());
+//! assert!(status.transition(CouponStatus::Refunded).is_err());
+//! ```
+
+use std::fmt;
+use std::error;
+
+use pax_status::PaxStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum CouponStatus {
+    /// Open for use
+    Open,
+    /// Control passed to the operating carrier at the airport
+    AirportControl,
+    CheckedIn,
+    /// Passenger boarded
+    Lifted,
+    /// Used, the flight departed with the passenger
+    Flown,
+    Refunded,
+    /// Exchanged or reissued into another ticket
+    Exchanged,
+    /// Voided on the day of sale
+    Void,
+    Suspended,
+    /// Converted to a paper coupon
+    Printed,
+    /// Under irregular operations control
+    IrregularOperations,
+    /// Closed by the carrier without use
+    Closed,
+    /// Carrier specific value
+    Other(char),
+}
+
+impl CouponStatus {
+    pub fn as_char(&self) -> char {
+        match *self {
+            CouponStatus::Open                => 'O',
+            CouponStatus::AirportControl      => 'A',
+            CouponStatus::CheckedIn           => 'C',
+            CouponStatus::Lifted              => 'L',
+            CouponStatus::Flown               => 'F',
+            CouponStatus::Refunded            => 'R',
+            CouponStatus::Exchanged           => 'E',
+            CouponStatus::Void                => 'V',
+            CouponStatus::Suspended           => 'S',
+            CouponStatus::Printed             => 'P',
+            CouponStatus::IrregularOperations => 'I',
+            CouponStatus::Closed              => 'Z',
+            CouponStatus::Other(c)            => c,
+        }
+    }
+
+    /// Whether no further transition is allowed
+    pub fn is_final(&self) -> bool {
+        matches!(*self,
+            CouponStatus::Flown | CouponStatus::Refunded | CouponStatus::Exchanged |
+            CouponStatus::Void | CouponStatus::Printed | CouponStatus::Closed)
+    }
+
+    /// Whether the coupon was used for travel or is being used right now
+    pub fn is_used(&self) -> bool {
+        matches!(*self, CouponStatus::Lifted | CouponStatus::Flown)
+    }
+
+    /// Whether the coupon can move to the status, carrier specific values never can
+    pub fn can_transition_to(&self, next: CouponStatus) -> bool {
+        use self::CouponStatus::*;
+
+        match (*self, next) {
+            (Open, AirportControl) | (Open, CheckedIn) | (Open, Refunded) |
+            (Open, Exchanged) | (Open, Void) | (Open, Suspended) |
+            (Open, Printed) | (Open, IrregularOperations) | (Open, Closed) => true,
+
+            (AirportControl, Open) | (AirportControl, CheckedIn) |
+            (AirportControl, Lifted) | (AirportControl, Flown) => true,
+
+            // Offloaded passengers get the coupon back open
+            (CheckedIn, Open) | (CheckedIn, Lifted) | (CheckedIn, Flown) => true,
+
+            (Lifted, Flown) => true,
+
+            (Suspended, Open) | (Suspended, Closed) => true,
+
+            (IrregularOperations, Open) | (IrregularOperations, Exchanged) => true,
+
+            _ => false,
+        }
+    }
+
+    /// Moves to the status, failing on illegal transitions
+    pub fn transition(self, next: CouponStatus) -> Result<CouponStatus, TransitionError> {
+        if self.can_transition_to(next) {
+            Ok(next)
+        } else {
+            Err(TransitionError { from: self, to: next })
+        }
+    }
+
+    /// Status the coupon is expected to have at the passenger status of a boarding pass
+    pub fn implied_by(status: PaxStatus) -> Option<CouponStatus> {
+        match status {
+            PaxStatus::NotCheckedIn |
+            PaxStatus::BaggageCheckedNotCheckedIn => Some(CouponStatus::Open),
+            PaxStatus::CheckedIn |
+            PaxStatus::BaggageCheckedCheckedIn |
+            PaxStatus::PassedSecurity |
+            PaxStatus::Transit |
+            PaxStatus::Standby |
+            PaxStatus::BoardingRevalidated |
+            PaxStatus::GateUpgrade            => Some(CouponStatus::CheckedIn),
+            PaxStatus::PassedGate             => Some(CouponStatus::Lifted),
+            _                                 => None,
+        }
+    }
+}
+
+impl From<char> for CouponStatus {
+    fn from(c: char) -> CouponStatus {
+        match c.to_ascii_uppercase() {
+            'O' => CouponStatus::Open,
+            'A' => CouponStatus::AirportControl,
+            'C' => CouponStatus::CheckedIn,
+            'L' => CouponStatus::Lifted,
+            'F' => CouponStatus::Flown,
+            'R' => CouponStatus::Refunded,
+            'E' => CouponStatus::Exchanged,
+            'V' => CouponStatus::Void,
+            'S' => CouponStatus::Suspended,
+            'P' => CouponStatus::Printed,
+            'I' => CouponStatus::IrregularOperations,
+            'Z' => CouponStatus::Closed,
+            c   => CouponStatus::Other(c),
+        }
+    }
+}
+
+/// Illegal coupon status transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionError {
+    pub from: CouponStatus,
+    pub to: CouponStatus,
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "coupon can't move from status '{}' to '{}'", self.from.as_char(), self.to.as_char())
+    }
+}
+
+impl error::Error for TransitionError {}
@@ -0,0 +1,56 @@
+//! Telemetry events of self-service kiosks and bag drops
+//!
+//! Events carry parsed data, so backends don't have to re-parse raw scans.
+//! With the `serde` feature they serialize as externally tagged enums.
+
+use bcbp::BCBP;
+use bagtag::LicensePlate;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CussEvent {
+    pub device_id: String,
+    /// Unix time in seconds
+    pub timestamp: i64,
+    pub kind: CussEventKind,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum CussEventKind {
+    PassScanned(Box<BCBP>),
+    /// Barcode was read but could not be parsed
+    PassRejected {
+        error: String,
+    },
+    /// Travel document read, lines of the machine readable zone as scanned
+    DocumentScanned {
+        mrz: Vec<String>,
+    },
+    TagPrinted(LicensePlate),
+    BagDropped {
+        tag: LicensePlate,
+        weight_kg: Option<f32>,
+    },
+}
+
+impl CussEvent {
+    pub fn new(device_id: &str, timestamp: i64, kind: CussEventKind) -> CussEvent {
+        CussEvent {
+            device_id: device_id.into(),
+            timestamp,
+            kind,
+        }
+    }
+
+    /// Event for a scanned barcode, rejected if it can't be parsed
+    pub fn scan(device_id: &str, timestamp: i64, barcode: &str) -> CussEvent {
+        let kind = match BCBP::from(barcode) {
+            Ok(bcbp) => CussEventKind::PassScanned(Box::new(bcbp)),
+            Err(e)   => CussEventKind::PassRejected { error: e.to_string() },
+        };
+
+        CussEvent::new(device_id, timestamp, kind)
+    }
+}
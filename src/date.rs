@@ -0,0 +1,120 @@
+//! Date and time types of the enabled date library
+//!
+//! `chrono` is used when both `chrono` and `time` features are enabled.
+
+use std::fmt;
+use std::error;
+
+/// Reasons a day of year can't be resolved to a date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DateError {
+    /// Day of year is not set
+    Missing,
+    /// Day of year does not exist in the year, like day 366 of a common year
+    InvalidDay { year: i32, day: u32 },
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DateError::Missing => f.write_str("date is not set"),
+            DateError::InvalidDay { year, day } => write!(f, "year {} has no day {}", year, day),
+        }
+    }
+}
+
+impl error::Error for DateError {}
+
+#[cfg(feature = "chrono")]
+mod imp {
+    use chrono::prelude::*;
+    use chrono::Duration;
+
+    pub type Date = NaiveDate;
+    pub type Time = NaiveTime;
+
+    pub fn from_ordinal(year: i32, day: u32) -> Option<Date> {
+        NaiveDate::from_yo_opt(year, day)
+    }
+
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    pub fn add_days(date: &Date, days: i64) -> Date {
+        *date + Duration::days(days)
+    }
+
+    /// Day of the week, 1 is Monday
+    pub fn weekday(date: &Date) -> u32 {
+        date.weekday().number_from_monday()
+    }
+
+    pub fn today() -> Date {
+        Utc::now().date_naive()
+    }
+
+    pub fn year(date: &Date) -> i32 {
+        date.year()
+    }
+
+    /// Day of the year, 1 is January 1st
+    pub fn ordinal(date: &Date) -> u32 {
+        date.ordinal()
+    }
+
+    pub fn month_day(date: &Date) -> (u32, u32) {
+        (date.month(), date.day())
+    }
+}
+
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+mod imp {
+    use std::convert::TryFrom;
+
+    use time;
+
+    pub type Date = time::Date;
+    pub type Time = time::Time;
+
+    pub fn from_ordinal(year: i32, day: u32) -> Option<Date> {
+        if day > 366 {
+            return None
+        }
+        time::Date::from_ordinal_date(year, day as u16).ok()
+    }
+
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        let month = time::Month::try_from(u8::try_from(month).ok()?).ok()?;
+        time::Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+    }
+
+    pub fn add_days(date: &Date, days: i64) -> Date {
+        *date + time::Duration::days(days)
+    }
+
+    /// Day of the week, 1 is Monday
+    pub fn weekday(date: &Date) -> u32 {
+        date.weekday().number_from_monday() as u32
+    }
+
+    pub fn today() -> Date {
+        time::OffsetDateTime::now_utc().date()
+    }
+
+    pub fn year(date: &Date) -> i32 {
+        date.year()
+    }
+
+    /// Day of the year, 1 is January 1st
+    pub fn ordinal(date: &Date) -> u32 {
+        date.ordinal() as u32
+    }
+
+    pub fn month_day(date: &Date) -> (u32, u32) {
+        (date.month() as u32, date.day() as u32)
+    }
+}
+
+pub use self::imp::*;
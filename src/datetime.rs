@@ -0,0 +1,214 @@
+//! Dates and times in the forms of airline messaging
+//!
+//! Type B messages, schedules and passes write dates as `12AUG` or
+//! `12AUG25` with English month abbreviations, whatever the locale, and
+//! times as `HHMM`.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::{date, datetime};
+//!
+//! let day = date::from_ymd(2025, 8, 12).unwrap();
+//! assert_eq!(datetime::format_ddmmm(&day), "12AUG");
+//! assert_eq!(datetime::format_ddmmmyy(&day), "12AUG25");
+//! assert_eq!(datetime::parse_ddmmm("12aug", 2025), Some(day));
+//! assert_eq!(datetime::parse_ddmmmyy("12AUG25"), Some(day));
+//!
+//! let midnight = datetime::WireTime::parse("2400").unwrap();
+//! assert_eq!((midnight.hhmm(), midnight.day_offset()), ("0000".to_string(), 1));
+//! ```
+
+use std::fmt;
+
+use date;
+
+pub const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"
+];
+
+/// Month number from 1 of an abbreviation like `AUG`, in any case
+pub fn month(src: &str) -> Option<u32> {
+    MONTHS.iter()
+        .position(|m| m.eq_ignore_ascii_case(src))
+        .map(|i| i as u32 + 1)
+}
+
+/// Month and day of a `DDMMM` date, like `12AUG`, in the order of `date::month_day()`
+pub fn parse_month_day(src: &str) -> Option<(u32, u32)> {
+    if src.len() != 5 || !src.is_ascii() || !src[.. 2].chars().all(|c| c.is_ascii_digit()) {
+        return None
+    }
+
+    let day = src[.. 2].parse().ok().filter(|day| (1 ..= 31).contains(day))?;
+
+    Some((month(&src[2 ..])?, day))
+}
+
+/// `DDMMM` date in the given year
+pub fn parse_ddmmm(src: &str, year: i32) -> Option<date::Date> {
+    let (month, day) = parse_month_day(src)?;
+    date::from_ymd(year, month, day)
+}
+
+/// `DDMMMYY` date, years are taken as 2000 to 2099
+pub fn parse_ddmmmyy(src: &str) -> Option<date::Date> {
+    if src.len() != 7 || !src.is_ascii() || !src[5 ..].chars().all(|c| c.is_ascii_digit()) {
+        return None
+    }
+
+    let year: i32 = src[5 ..].parse().ok()?;
+    parse_ddmmm(&src[.. 5], 2000 + year)
+}
+
+pub fn format_ddmmm(value: &date::Date) -> String {
+    let (month, day) = date::month_day(value);
+    format!("{:02}{}", day, MONTHS[month as usize - 1])
+}
+
+pub fn format_ddmmmyy(value: &date::Date) -> String {
+    format!("{}{:02}", format_ddmmm(value), date::year(value).rem_euclid(100))
+}
+
+/// Time of the day written as `HHMM`
+///
+/// Messages sometimes give midnight at the end of a day as `2400`, which
+/// is taken as `0000` of the next day, see `day_offset()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WireTime {
+    hour: u8,
+    minute: u8,
+    next_day: bool,
+}
+
+impl WireTime {
+    pub fn new(hour: u8, minute: u8) -> Option<WireTime> {
+        if hour > 23 || minute > 59 {
+            return None
+        }
+
+        Some(WireTime { hour, minute, next_day: false })
+    }
+
+    /// Parses `HHMM` from `0000` to `2359`, or `2400`
+    pub fn parse(src: &str) -> Option<WireTime> {
+        if src.len() != 4 || !src.bytes().all(|b| b.is_ascii_digit()) {
+            return None
+        }
+
+        if src == "2400" {
+            return Some(WireTime { hour: 0, minute: 0, next_day: true })
+        }
+
+        WireTime::new(src[.. 2].parse().ok()?, src[2 ..].parse().ok()?)
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Minutes since midnight, 0 for `2400`
+    pub fn minutes(&self) -> u32 {
+        u32::from(self.hour) * 60 + u32::from(self.minute)
+    }
+
+    /// 1 for a time written as `2400`, 0 otherwise
+    pub fn day_offset(&self) -> i8 {
+        self.next_day as i8
+    }
+
+    /// Time as `HHMM`, `2400` is written as `0000`
+    pub fn hhmm(&self) -> String {
+        format!("{:02}{:02}", self.hour, self.minute)
+    }
+}
+
+impl fmt::Display for WireTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.hhmm())
+    }
+}
+
+/// `HHMM` time with the days it falls after the reference date, like `0130/1` or `0130+1`
+///
+/// SSM and MVT messages attach the offset to times past midnight of the
+/// flight date. A `2400` time adds a day to the offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeWithOffset {
+    time: WireTime,
+    offset: i8,
+}
+
+impl TimeWithOffset {
+    pub fn new(time: WireTime, offset: i8) -> TimeWithOffset {
+        TimeWithOffset {
+            time: WireTime { next_day: false, ..time },
+            offset: offset.saturating_add(time.day_offset()),
+        }
+    }
+
+    /// Parses `HHMM` with an optional `/N`, `/-N`, `+N` or `-N` offset of up to 9 days
+    pub fn parse(src: &str) -> Option<TimeWithOffset> {
+        let time = WireTime::parse(src.get(.. 4)?)?;
+
+        let suffix = src.get(4 ..)?;
+
+        let (sign, days) = if suffix.is_empty() {
+            (1, "0")
+        } else if let Some(days) = suffix.strip_prefix("/-") {
+            (-1, days)
+        } else if let Some(days) = suffix.strip_prefix('-') {
+            (-1, days)
+        } else {
+            (1, suffix.strip_prefix('/').or_else(|| suffix.strip_prefix('+'))?)
+        };
+
+        if days.len() != 1 || !days.bytes().all(|b| b.is_ascii_digit()) {
+            return None
+        }
+
+        Some(TimeWithOffset::new(time, sign * days.parse::<i8>().ok()?))
+    }
+
+    /// Time of the day, `2400` is `0000`
+    pub fn time(&self) -> WireTime {
+        self.time
+    }
+
+    pub fn day_offset(&self) -> i8 {
+        self.offset
+    }
+
+    /// Minutes since midnight of the reference date, negative for the day before
+    pub fn minutes(&self) -> i32 {
+        i32::from(self.offset) * 24 * 60 + self.time.minutes() as i32
+    }
+
+    /// Date the time falls on for the given reference date
+    pub fn date_from(&self, day: &date::Date) -> date::Date {
+        date::add_days(day, i64::from(self.offset))
+    }
+
+    /// SSM form like `0130/1`, without an offset of 0
+    pub fn ssm(&self) -> String {
+        match self.offset {
+            0 => self.time.hhmm(),
+            d => format!("{}/{}", self.time.hhmm(), d),
+        }
+    }
+}
+
+/// Formats like `0130+1`, without an offset of 0
+impl fmt::Display for TimeWithOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.time.hhmm())?;
+
+        match self.offset {
+            0 => Ok(()),
+            d => write!(f, "{:+}", d),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Human readable descriptions of items and values with translation tables
+//!
+//! Every description has a stable key, a `Locale` maps keys to translated
+//! text and falls back to the built in English description.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::describe::{Describe, Locale};
+//! use iata::spec;
+//!
+//! let mut de = Locale::new("de");
+//! de.insert("bcbp.item.11", "Name des Passagiers");
+//!
+//! let name = spec::item(11).unwrap();
+//! assert_eq!(name.describe(), "Passenger Name");
+//! assert_eq!(name.describe_in(&de), "Name des Passagiers");
+//! assert_eq!(spec::item(7).unwrap().describe_in(&de), "Operating carrier PNR Code");
+//! ```
+
+use std::collections::HashMap;
+
+use bcbp::{Error, PassengerDescription, Source, TicketIndicator};
+use spec::Item;
+
+/// Translation table keyed by `Describe::description_key()`
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    name: String,
+    table: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn new(name: &str) -> Locale {
+        Locale {
+            name: name.into(),
+            table: HashMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    pub fn insert(&mut self, key: &str, text: &str) {
+        self.table.insert(key.into(), text.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.table.get(key).map(|s| s.as_ref())
+    }
+}
+
+pub trait Describe {
+    /// Stable key of the description, like `bcbp.item.11`
+    fn description_key(&self) -> String;
+
+    /// Description in English
+    fn describe(&self) -> String;
+
+    /// Description from the locale, English if the locale lacks it
+    fn describe_in(&self, locale: &Locale) -> String {
+        locale.get(&self.description_key())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| self.describe())
+    }
+}
+
+impl Describe for Item {
+    fn description_key(&self) -> String {
+        format!("bcbp.item.{}", self.id)
+    }
+
+    fn describe(&self) -> String {
+        self.name.into()
+    }
+}
+
+impl Describe for TicketIndicator {
+    fn description_key(&self) -> String {
+        match *self {
+            TicketIndicator::Eticket  => "bcbp.ticket_indicator.eticket".into(),
+            TicketIndicator::Other(_) => "bcbp.ticket_indicator.other".into(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match *self {
+            TicketIndicator::Eticket  => "Electronic ticket".into(),
+            TicketIndicator::Other(_) => "Other ticket".into(),
+        }
+    }
+}
+
+impl Describe for PassengerDescription {
+    fn description_key(&self) -> String {
+        match *self {
+            PassengerDescription::Other(_) => "bcbp.passenger_description.other".into(),
+            d => format!("bcbp.passenger_description.{}", d.as_char()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let text = match *self {
+            PassengerDescription::Adult              => "Adult",
+            PassengerDescription::Male               => "Male",
+            PassengerDescription::Female             => "Female",
+            PassengerDescription::Child              => "Child",
+            PassengerDescription::Infant             => "Infant",
+            PassengerDescription::NoPassenger        => "No passenger (cabin baggage)",
+            PassengerDescription::AdultWithInfant    => "Adult traveling with infant",
+            PassengerDescription::UnaccompaniedMinor => "Unaccompanied minor",
+            PassengerDescription::Undisclosed        => "Undisclosed",
+            PassengerDescription::Other(_)           => "Other passenger description",
+        };
+
+        text.into()
+    }
+}
+
+impl Describe for Source {
+    fn description_key(&self) -> String {
+        match *self {
+            Source::Other(_) => "bcbp.source.other".into(),
+            s => format!("bcbp.source.{}", s.as_char()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let text = match *self {
+            Source::Web          => "Web",
+            Source::Kiosk        => "Airport kiosk",
+            Source::Remote       => "Remote or off site kiosk",
+            Source::Mobile       => "Mobile device",
+            Source::AirportAgent => "Airport agent",
+            Source::TransferDesk => "Transfer desk",
+            Source::Voice        => "Voice",
+            Source::Other(_)     => "Other source",
+        };
+
+        text.into()
+    }
+}
+
+impl Describe for Error {
+    fn description_key(&self) -> String {
+        let name = format!("{:?}", self);
+        let variant = name.split('(').next().unwrap_or("");
+
+        format!("bcbp.error.{}", variant)
+    }
+
+    fn describe(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,55 @@
+//! Test pass for kiosk printer and scanner loopback checks
+//!
+//! Hardware self-tests print a pass and scan it back. `self_test_pass()`
+//! gives the same pass every time, for passenger `TEST/PRINTER` on flight
+//! `XX0001` of the made up airline `XX`, so no real passenger data goes
+//! through the devices. The scanned data is compared by its CRC-32 with
+//! `SELF_TEST_CHECKSUM`.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::diagnostics;
+//!
+//! let printed = diagnostics::self_test_pass().expect_build();
+//! assert!(diagnostics::verify_loopback(&printed));
+//! assert!(!diagnostics::verify_loopback(&printed.replace("0001", "0002")));
+//! ```
+
+use bcbp::BCBP;
+
+/// Barcode data of the test pass
+pub const SELF_TEST_DATA: &str = "M1TEST/PRINTER        ETEST00 XXXZZZXX 0001 001Y001A0001 000";
+
+/// CRC-32 of `SELF_TEST_DATA`
+pub const SELF_TEST_CHECKSUM: u32 = 0x7710_d921;
+
+/// The test pass, see the module docs
+pub fn self_test_pass() -> BCBP {
+    BCBP::from(SELF_TEST_DATA).expect("self-test pass is valid")
+}
+
+/// Whether a pass is the test pass, gates should refuse it
+pub fn is_self_test(bcbp: &BCBP) -> bool {
+    bcbp.name() == "TEST/PRINTER" && bcbp.segments().iter().any(|s| s.airline() == "XX")
+}
+
+/// Whether data read back by a scanner is the test pass, character for character
+pub fn verify_loopback(scanned: &str) -> bool {
+    checksum(scanned) == SELF_TEST_CHECKSUM
+}
+
+/// CRC-32 (IEEE 802.3) of the data
+pub fn checksum(data: &str) -> u32 {
+    let mut crc = !0u32;
+
+    for &b in data.as_bytes() {
+        crc ^= u32::from(b);
+
+        for _ in 0 .. 8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
@@ -0,0 +1,112 @@
+//! Per passenger CO2 estimates of legs, in the spirit of IATA RP 1726
+//!
+//! Fuel burnt over the leg distance is split between cargo and passengers,
+//! then among economy equivalent seats occupied at the load factor. Premium
+//! cabins take more floor space and are weighted accordingly. All inputs are
+//! configurable, the defaults describe a typical narrow body aircraft and are
+//! meant for rough estimates only.
+
+use bcbp::Segment;
+use station::StationTable;
+
+/// CO2 emitted by burning one kilogram of jet fuel
+pub const CO2_PER_KG_FUEL: f64 = 3.16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Cabin {
+    Economy,
+    PremiumEconomy,
+    Business,
+    First,
+}
+
+impl Cabin {
+    /// Cabin of a compartment code by the common booking class conventions
+    pub fn from_compartment(c: char) -> Cabin {
+        match c.to_ascii_uppercase() {
+            'F' | 'A' | 'P'                         => Cabin::First,
+            'J' | 'C' | 'D' | 'I' | 'Z' | 'R'       => Cabin::Business,
+            'W' | 'E'                               => Cabin::PremiumEconomy,
+            _                                       => Cabin::Economy,
+        }
+    }
+}
+
+/// Seat space of each cabin relative to an economy seat
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CabinFactors {
+    pub economy: f64,
+    pub premium_economy: f64,
+    pub business: f64,
+    pub first: f64,
+}
+
+impl Default for CabinFactors {
+    fn default() -> CabinFactors {
+        CabinFactors {
+            economy: 1.0,
+            premium_economy: 1.5,
+            business: 4.0,
+            first: 5.0,
+        }
+    }
+}
+
+impl CabinFactors {
+    pub fn get(&self, cabin: Cabin) -> f64 {
+        match cabin {
+            Cabin::Economy        => self.economy,
+            Cabin::PremiumEconomy => self.premium_economy,
+            Cabin::Business       => self.business,
+            Cabin::First          => self.first,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmissionsModel {
+    /// Fuel burnt by the aircraft per kilometre flown
+    pub fuel_kg_per_km: f64,
+    /// Capacity in economy equivalent seats
+    pub seats: f64,
+    /// Share of seats occupied, from 0 to 1
+    pub load_factor: f64,
+    /// Share of fuel attributed to belly cargo, from 0 to 1
+    pub cargo_share: f64,
+    pub cabin_factors: CabinFactors,
+}
+
+impl Default for EmissionsModel {
+    fn default() -> EmissionsModel {
+        EmissionsModel {
+            fuel_kg_per_km: 3.5,
+            seats: 180.0,
+            load_factor: 0.8,
+            cargo_share: 0.05,
+            cabin_factors: CabinFactors::default(),
+        }
+    }
+}
+
+impl EmissionsModel {
+    /// Kilograms of CO2 attributed to one passenger of the cabin
+    pub fn co2_kg(&self, distance_km: f64, cabin: Cabin) -> f64 {
+        let occupied = self.seats * self.load_factor;
+
+        if occupied <= 0.0 {
+            return 0.0
+        }
+
+        let passenger_fuel = self.fuel_kg_per_km * distance_km * (1.0 - self.cargo_share);
+
+        passenger_fuel * CO2_PER_KG_FUEL / occupied * self.cabin_factors.get(cabin)
+    }
+
+    /// Estimate for a leg, `None` if either airport is unknown to the table
+    pub fn segment_co2_kg(&self, stations: &StationTable, segment: &Segment) -> Option<f64> {
+        let distance = stations.segment_distance_km(segment)?;
+
+        Some(self.co2_kg(distance, Cabin::from_compartment(segment.compartment())))
+    }
+}
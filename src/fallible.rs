@@ -0,0 +1,59 @@
+//! Conversions of the crate results into `anyhow::Result`
+//!
+//! The typed errors implement `std::error::Error`, so `?` already converts
+//! them. `into_anyhow()` keeps the call sites uniform.
+//!
+//! ```
+//! # extern crate iata;
+//! # extern crate anyhow;
+//! use iata::bcbp::BCBP;
+//! use iata::fallible::IntoAnyhow;
+//!
+//! fn flight_date(src: &str) -> anyhow::Result<String> {
+//!     let bcbp = BCBP::from(src).into_anyhow()?;
+//!     let date = bcbp.segments()[0].try_flight_date(2017).into_anyhow()?;
+//!     Ok(date.to_string())
+//! }
+//!
+//! assert_eq!(flight_date("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap(), "2017-01-01");
+//! assert!(flight_date("M1JOHN").is_err());
+//! ```
+
+use anyhow;
+
+use bcbp::{BuildError, Error, ErrorContext, SequenceError, TemplateError};
+use coupon::TransitionError;
+use flight_status::StatusError;
+use tax::TaxError;
+use ssim::{ApplyError, ReadError};
+use date::DateError;
+#[cfg(feature = "crypto")]
+use audit::AuditError;
+#[cfg(feature = "offline")]
+use offline::SnapshotError;
+use seatmap::SeatError;
+use pass::PayloadError;
+
+pub trait IntoAnyhow<T> {
+    fn into_anyhow(self) -> anyhow::Result<T>;
+}
+
+macro_rules! into_anyhow {
+    ($($error:ty),*) => {
+        $(
+            impl<T> IntoAnyhow<T> for Result<T, $error> {
+                fn into_anyhow(self) -> anyhow::Result<T> {
+                    self.map_err(anyhow::Error::new)
+                }
+            }
+        )*
+    }
+}
+
+into_anyhow!(Error, ErrorContext, BuildError, PayloadError, TemplateError, SequenceError, SeatError, DateError, TransitionError, StatusError, TaxError, ReadError, ApplyError);
+
+#[cfg(feature = "crypto")]
+into_anyhow!(AuditError);
+
+#[cfg(feature = "offline")]
+into_anyhow!(SnapshotError);
@@ -0,0 +1,338 @@
+//! Flights as keys, legs and market segments
+//!
+//! `FlightKey` joins data across modules, like a boarding pass leg with the
+//! schedule leg it was issued for and the movement messages about it.
+//!
+//! `CityPair` is the direction aware pair of airports of a leg or segment.
+//! `marketing_flights()` lists the code share designators a pass leg is
+//! also sold as, from SSIM segment data.
+//!
+//! A leg is flown between two consecutive stops of a flight, a market
+//! segment is sold between any two of its stops. A flight JFK-SVO-LED has
+//! legs JFK-SVO and SVO-LED and segments JFK-SVO, JFK-LED and SVO-LED.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::BCBP;
+//! use iata::date;
+//! use iata::flight::FlightKey;
+//! use iata::ssim::Leg;
+//!
+//! let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 0100 001Y001Z0007 000").unwrap();
+//! let from_pass = FlightKey::from_segment(&bcbp.segments()[0], 2017).unwrap();
+//!
+//! let mut leg = Leg::new("SU", 100, 1, 1);
+//! leg.set_departure("JFK", "1300", "-0500");
+//! let from_schedule = FlightKey::from_leg(&leg, date::from_ymd(2017, 1, 1).unwrap()).unwrap();
+//!
+//! assert_eq!(from_pass, from_schedule);
+//! assert_eq!(from_pass.to_string(), "SU100/2017-01-01/JFK");
+//! ```
+
+use std::fmt;
+use std::collections::BTreeMap;
+
+use bcbp::Segment;
+use date;
+use flight_status::MvtHeader;
+use ssim::{FlightId, Leg, SegmentData};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FlightKey {
+    pub airline: String,
+    pub number: u32,
+    pub suffix: Option<char>,
+    pub date: date::Date,
+    /// Departure station of the leg
+    pub departure: String,
+}
+
+impl FlightKey {
+    pub fn new(airline: &str, number: u32, suffix: Option<char>, date: date::Date, departure: &str) -> FlightKey {
+        FlightKey {
+            airline: airline.trim().to_uppercase(),
+            number,
+            suffix: suffix.map(|c| c.to_ascii_uppercase()),
+            date,
+            departure: departure.trim().to_uppercase(),
+        }
+    }
+
+    /// Key of a boarding pass leg, the flight day is resolved in the year
+    pub fn from_segment(segment: &Segment, year: i32) -> Option<FlightKey> {
+        let id = operating_flight(segment)?;
+        let date = segment.try_flight_date(year).ok()?;
+
+        Some(FlightKey::new(&id.airline, id.number, id.suffix, date, segment.src_airport()))
+    }
+
+    /// Key of a schedule leg on the date, `None` unless the leg operates on it
+    pub fn from_leg(leg: &Leg, date: date::Date) -> Option<FlightKey> {
+        if !leg.operates_on(&date) {
+            return None
+        }
+
+        Some(FlightKey::new(leg.airline(), leg.flight_number(), leg.suffix(), date, leg.departure()))
+    }
+
+    /// Key of the flight of an MVT message, the day of the header is resolved in the month
+    ///
+    /// Departure messages are sent by the departure station, so only their
+    /// keys match the ones of the leg.
+    pub fn from_mvt(header: &MvtHeader, year: i32, month: u32) -> Option<FlightKey> {
+        let date = header.date(year, month)?;
+        let id = &header.flight;
+
+        Some(FlightKey::new(&id.airline, id.number, id.suffix, date, &header.station))
+    }
+
+    /// Flight designator without the date and station
+    pub fn flight_id(&self) -> FlightId {
+        FlightId {
+            airline: self.airline.clone(),
+            number: self.number,
+            suffix: self.suffix,
+        }
+    }
+}
+
+/// Operating flight of a boarding pass leg, by its carrier and flight number items
+fn operating_flight(segment: &Segment) -> Option<FlightId> {
+    let code = segment.flight_code().trim();
+    let digits = code.find(|c: char| !c.is_ascii_digit()).unwrap_or(code.len());
+
+    let id = FlightId::new(segment.airline(), code[.. digits].parse().ok()?);
+
+    match code[digits ..].chars().collect::<Vec<_>>()[..] {
+        []  => Some(id),
+        [c] if c.is_ascii_alphabetic() => Some(id.with_suffix(c.to_ascii_uppercase())),
+        _   => None,
+    }
+}
+
+/// Marketing flights a boarding pass leg is also sold as, from SSIM code share elements (DEI 10)
+///
+/// Elements are matched on the operating flight of the leg and, when they
+/// name them, its stations. The flight of the marketing carrier on the pass
+/// comes first, so displays can lead with the designator the passenger
+/// bought. Empty when the leg isn't a code share.
+pub fn marketing_flights<'a, I>(segment: &Segment, data: I) -> Vec<FlightId>
+    where I: IntoIterator<Item = &'a SegmentData>
+{
+    let operating = match operating_flight(segment) {
+        Some(id) => id,
+        None     => return Vec::new(),
+    };
+
+    let station = |point: &str, airport: &str| point.is_empty() || point == airport;
+    let mut ret: Vec<FlightId> = Vec::new();
+
+    for element in data {
+        let matches = element.airline() == operating.airline
+            && element.flight_number() == operating.number
+            && station(element.board_point(), segment.src_airport())
+            && station(element.off_point(), segment.dst_airport());
+
+        if !matches {
+            continue
+        }
+
+        for id in element.codeshare_flights() {
+            if id != operating && !ret.contains(&id) {
+                ret.push(id);
+            }
+        }
+    }
+
+    if let Some(airline) = segment.marketing_airline() {
+        if let Some(i) = ret.iter().position(|id| id.airline == airline) {
+            let sold = ret.remove(i);
+            ret.insert(0, sold);
+        }
+    }
+
+    ret
+}
+
+impl fmt::Display for FlightKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.flight_id(), self.date, self.departure)
+    }
+}
+
+/// Stops a flight is sold between, over one or more consecutive legs
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarketSegment {
+    pub flight: FlightId,
+    pub itinerary_variation: u32,
+    pub board_point: String,
+    pub off_point: String,
+    /// Sequence number of the leg leaving the board point
+    pub first_leg: u32,
+    /// Sequence number of the leg arriving at the off point
+    pub last_leg: u32,
+}
+
+impl MarketSegment {
+    pub fn city_pair(&self) -> CityPair {
+        CityPair::new(&self.board_point, &self.off_point)
+    }
+
+    pub fn legs_count(&self) -> u32 {
+        self.last_leg - self.first_leg + 1
+    }
+
+    pub fn is_multi_leg(&self) -> bool {
+        self.legs_count() > 1
+    }
+
+    /// Market segments of schedule legs
+    ///
+    /// Legs are grouped by flight and itinerary variation and ordered by
+    /// their sequence numbers. A leg not departing from where the previous
+    /// one arrived starts a new routing, segments never span the gap.
+    pub fn derive(legs: &[Leg]) -> Vec<MarketSegment> {
+        let mut flights: BTreeMap<_, Vec<&Leg>> = BTreeMap::new();
+
+        for leg in legs {
+            let id = (leg.airline(), leg.flight_number(), leg.suffix(), leg.itinerary_variation());
+            flights.entry(id).or_default().push(leg);
+        }
+
+        let mut ret = Vec::new();
+
+        for ((airline, number, suffix, variation), mut legs) in flights {
+            legs.sort_by_key(|leg| leg.leg_sequence());
+
+            let flight = FlightId { airline: airline.into(), number, suffix };
+
+            let mut start = 0;
+            for end in 1 ..= legs.len() {
+                let connected = end < legs.len() && legs[end - 1].arrival() == legs[end].departure();
+
+                if connected {
+                    continue
+                }
+
+                let routing = &legs[start .. end];
+
+                for (i, first) in routing.iter().enumerate() {
+                    for last in &routing[i ..] {
+                        ret.push(MarketSegment {
+                            flight: flight.clone(),
+                            itinerary_variation: variation,
+                            board_point: first.departure().into(),
+                            off_point: last.arrival().into(),
+                            first_leg: first.leg_sequence(),
+                            last_leg: last.leg_sequence(),
+                        });
+                    }
+                }
+
+                start = end;
+            }
+        }
+
+        ret
+    }
+}
+
+impl fmt::Display for MarketSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}-{}", self.flight, self.board_point, self.off_point)
+    }
+}
+
+/// Origin and destination airports, in the direction of travel
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CityPair {
+    pub origin: String,
+    pub destination: String,
+}
+
+impl CityPair {
+    pub fn new(origin: &str, destination: &str) -> CityPair {
+        CityPair {
+            origin: origin.trim().to_uppercase(),
+            destination: destination.trim().to_uppercase(),
+        }
+    }
+
+    /// Parses the `JFK-SVO` form
+    pub fn parse(src: &str) -> Option<CityPair> {
+        let mut parts = src.split('-');
+        let pair = CityPair::new(parts.next()?, parts.next()?);
+
+        if parts.next().is_some() || pair.origin.is_empty() || pair.destination.is_empty() {
+            return None
+        }
+
+        Some(pair)
+    }
+
+    /// Pair of the return trip
+    pub fn reversed(&self) -> CityPair {
+        CityPair {
+            origin: self.destination.clone(),
+            destination: self.origin.clone(),
+        }
+    }
+
+    /// Whether both pairs link the same airports, in either direction
+    pub fn same_airports(&self, other: &CityPair) -> bool {
+        self == other || *self == other.reversed()
+    }
+
+    /// Whether both airports are in the same country, `None` if either is unknown to the lookup
+    pub fn is_domestic<F, C>(&self, country_of: F) -> Option<bool>
+        where F: Fn(&str) -> Option<C>, C: PartialEq
+    {
+        Some(country_of(&self.origin)? == country_of(&self.destination)?)
+    }
+}
+
+impl fmt::Display for CityPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.origin, self.destination)
+    }
+}
+
+/// Whether the legs of a journey cross borders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RouteType {
+    Domestic,
+    International,
+    /// Both domestic and international legs
+    Mixed,
+}
+
+impl RouteType {
+    /// Classifies pairs of a journey, `None` if it's empty or an airport is unknown to the lookup
+    pub fn of<'a, I, F, C>(pairs: I, country_of: F) -> Option<RouteType>
+        where I: IntoIterator<Item = &'a CityPair>, F: Fn(&str) -> Option<C>, C: PartialEq
+    {
+        let mut domestic = false;
+        let mut international = false;
+
+        for pair in pairs {
+            if pair.is_domestic(&country_of)? {
+                domestic = true;
+            } else {
+                international = true;
+            }
+        }
+
+        match (domestic, international) {
+            (true, false) => Some(RouteType::Domestic),
+            (false, true) => Some(RouteType::International),
+            (true, true)  => Some(RouteType::Mixed),
+            (false, false) => None,
+        }
+    }
+
+    /// Whether any leg crosses a border
+    pub fn is_international(&self) -> bool {
+        *self != RouteType::Domestic
+    }
+}
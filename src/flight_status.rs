@@ -0,0 +1,330 @@
+//! Operational status of flights, fed by movement and schedule change events
+//!
+//! Movement (MVT), schedule change (ASM) and AIDX messages report what
+//! happened to a flight. Their parsers produce `FlightEvent`s, and
+//! `StatusTracker` keeps the current `FlightState` of each `FlightKey`,
+//! rejecting events arriving out of order like boarding after departure.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::date;
+//! use iata::datetime::TimeWithOffset;
+//! use iata::flight::FlightKey;
+//! use iata::flight_status::{FlightEvent, FlightStatus, StatusTracker};
+//!
+//! let key = FlightKey::new("SU", 100, None, date::from_ymd(2017, 1, 1).unwrap(), "JFK");
+//! let mut tracker = StatusTracker::new();
+//!
+//! tracker.apply(&key, FlightEvent::BoardingStarted).unwrap();
+//! tracker.apply(&key, FlightEvent::OffBlock(TimeWithOffset::parse("2350").unwrap())).unwrap();
+//! tracker.apply(&key, FlightEvent::Airborne(TimeWithOffset::parse("0005/1").unwrap())).unwrap();
+//!
+//! assert_eq!(tracker.get(&key).unwrap().status(), FlightStatus::Airborne);
+//! assert!(tracker.apply(&key, FlightEvent::BoardingStarted).is_err());
+//! ```
+
+use std::fmt;
+use std::error;
+use std::collections::HashMap;
+
+use date;
+use datetime::TimeWithOffset;
+use flight::FlightKey;
+use ssim::FlightId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum FlightStatus {
+    Scheduled,
+    /// New estimated departure time was announced
+    Delayed,
+    Boarding,
+    /// Off blocks, not airborne yet
+    Departed,
+    Airborne,
+    /// On blocks at the destination or the diversion airport
+    Arrived,
+    /// Heading to another airport than scheduled
+    Diverted,
+    Cancelled,
+}
+
+impl FlightStatus {
+    /// Whether no further transition is allowed
+    pub fn is_final(&self) -> bool {
+        matches!(*self, FlightStatus::Arrived)
+    }
+
+    /// Whether the aircraft left the gate
+    pub fn is_operating(&self) -> bool {
+        matches!(*self, FlightStatus::Departed | FlightStatus::Airborne | FlightStatus::Diverted)
+    }
+
+    /// Whether the flight can move to the status
+    ///
+    /// Events may be missed, so a status can be skipped, but not gone back
+    /// to. A new estimate keeps a delayed flight delayed.
+    pub fn can_transition_to(&self, next: FlightStatus) -> bool {
+        use self::FlightStatus::*;
+
+        match (*self, next) {
+            (Scheduled, _) => next != Scheduled,
+
+            (Delayed, Delayed) | (Delayed, Boarding) | (Delayed, Departed) | (Delayed, Airborne) |
+            (Delayed, Arrived) | (Delayed, Cancelled) => true,
+
+            (Boarding, Delayed) | (Boarding, Departed) | (Boarding, Airborne) |
+            (Boarding, Arrived) | (Boarding, Cancelled) => true,
+
+            (Departed, Airborne) | (Departed, Arrived) | (Departed, Diverted) => true,
+
+            (Airborne, Arrived) | (Airborne, Diverted) => true,
+
+            (Diverted, Arrived) => true,
+
+            // Reinstated by a schedule change
+            (Cancelled, Scheduled) => true,
+
+            _ => false,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            FlightStatus::Scheduled => "scheduled",
+            FlightStatus::Delayed   => "delayed",
+            FlightStatus::Boarding  => "boarding",
+            FlightStatus::Departed  => "departed",
+            FlightStatus::Airborne  => "airborne",
+            FlightStatus::Arrived   => "arrived",
+            FlightStatus::Diverted  => "diverted",
+            FlightStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for FlightStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parsed message about a flight, times are relative to the flight date
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FlightEvent {
+    /// Estimated departure, MVT `ED`
+    Delayed(TimeWithOffset),
+    BoardingStarted,
+    /// Off block time, MVT `AD`
+    OffBlock(TimeWithOffset),
+    /// Take off time, MVT `AD`
+    Airborne(TimeWithOffset),
+    /// On block time, MVT `AA`
+    Arrived(TimeWithOffset),
+    /// Diversion to another airport
+    Diverted(String),
+    /// ASM `CNL`
+    Cancelled,
+    /// ASM `RIN`
+    Reinstated,
+}
+
+impl FlightEvent {
+    /// Status the flight has after the event
+    pub fn status(&self) -> FlightStatus {
+        match *self {
+            FlightEvent::Delayed(_)      => FlightStatus::Delayed,
+            FlightEvent::BoardingStarted => FlightStatus::Boarding,
+            FlightEvent::OffBlock(_)     => FlightStatus::Departed,
+            FlightEvent::Airborne(_)     => FlightStatus::Airborne,
+            FlightEvent::Arrived(_)      => FlightStatus::Arrived,
+            FlightEvent::Diverted(_)     => FlightStatus::Diverted,
+            FlightEvent::Cancelled       => FlightStatus::Cancelled,
+            FlightEvent::Reinstated      => FlightStatus::Scheduled,
+        }
+    }
+}
+
+/// Flight identification line of an MVT message, like `SU100/01.VPBAA.JFK`
+///
+/// The line gives the day of the month the flight was scheduled to depart,
+/// the aircraft registration and the station sending the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MvtHeader {
+    pub flight: FlightId,
+    /// Day of the month of the scheduled departure
+    pub day: u32,
+    pub registration: String,
+    /// Station sending the message, the departure station for departure messages
+    pub station: String,
+}
+
+impl MvtHeader {
+    pub fn parse(src: &str) -> Option<MvtHeader> {
+        let (flight, rest) = src.trim().split_once('/')?;
+        let mut fields = rest.split('.');
+
+        let (day, registration, station) = match (fields.next(), fields.next(), fields.next(), fields.next()) {
+            (Some(day), Some(registration), Some(station), None) => (day, registration, station),
+            _ => return None,
+        };
+
+        if day.len() != 2 || !day.chars().all(|c| c.is_ascii_digit()) || station.len() != 3 || !station.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None
+        }
+
+        let day = day.parse().ok().filter(|day| (1 ..= 31).contains(day))?;
+
+        Some(MvtHeader {
+            flight: FlightId::parse(flight)?,
+            day,
+            registration: registration.trim().to_uppercase(),
+            station: station.to_uppercase(),
+        })
+    }
+
+    /// Scheduled departure date in the month, `None` when the month has no such day
+    pub fn date(&self, year: i32, month: u32) -> Option<date::Date> {
+        date::from_ymd(year, month, self.day)
+    }
+}
+
+/// Event that doesn't fit the current status of the flight
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusError {
+    pub flight: FlightKey,
+    pub from: FlightStatus,
+    pub to: FlightStatus,
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "flight {} can't move from {} to {}", self.flight, self.from, self.to)
+    }
+}
+
+impl error::Error for StatusError {}
+
+/// Current status of a flight along with the times reported so far
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlightState {
+    status: FlightStatus,
+    estimated_departure: Option<TimeWithOffset>,
+    off_block: Option<TimeWithOffset>,
+    airborne: Option<TimeWithOffset>,
+    on_block: Option<TimeWithOffset>,
+    diverted_to: Option<String>,
+}
+
+impl FlightState {
+    pub fn new() -> FlightState {
+        FlightState {
+            status: FlightStatus::Scheduled,
+            estimated_departure: None,
+            off_block: None,
+            airborne: None,
+            on_block: None,
+            diverted_to: None,
+        }
+    }
+
+    pub fn status(&self) -> FlightStatus {
+        self.status
+    }
+
+    /// Latest estimated departure, kept after departure
+    pub fn estimated_departure(&self) -> Option<TimeWithOffset> {
+        self.estimated_departure
+    }
+
+    pub fn off_block(&self) -> Option<TimeWithOffset> {
+        self.off_block
+    }
+
+    pub fn airborne(&self) -> Option<TimeWithOffset> {
+        self.airborne
+    }
+
+    pub fn on_block(&self) -> Option<TimeWithOffset> {
+        self.on_block
+    }
+
+    /// Airport the flight was diverted to
+    pub fn diverted_to(&self) -> Option<&str> {
+        self.diverted_to.as_ref().map(|s| s.as_ref())
+    }
+
+    fn apply(&mut self, event: FlightEvent) {
+        self.status = event.status();
+
+        match event {
+            FlightEvent::Delayed(time)  => self.estimated_departure = Some(time),
+            FlightEvent::OffBlock(time) => self.off_block = Some(time),
+            FlightEvent::Airborne(time) => self.airborne = Some(time),
+            FlightEvent::Arrived(time)  => self.on_block = Some(time),
+            FlightEvent::Diverted(to)   => self.diverted_to = Some(to.trim().to_uppercase()),
+            FlightEvent::Reinstated     => *self = FlightState::new(),
+            FlightEvent::BoardingStarted | FlightEvent::Cancelled => {},
+        }
+    }
+}
+
+impl Default for FlightState {
+    fn default() -> FlightState {
+        FlightState::new()
+    }
+}
+
+/// Current state of each flight events were received for
+#[derive(Debug, Clone, Default)]
+pub struct StatusTracker {
+    flights: HashMap<FlightKey, FlightState>,
+}
+
+impl StatusTracker {
+    pub fn new() -> StatusTracker {
+        StatusTracker::default()
+    }
+
+    /// Applies an event, a flight seen for the first time starts as scheduled
+    ///
+    /// An event the flight can't move to leaves its state unchanged.
+    pub fn apply(&mut self, flight: &FlightKey, event: FlightEvent) -> Result<&FlightState, StatusError> {
+        let from = self.flights.get(flight).map(|s| s.status).unwrap_or(FlightStatus::Scheduled);
+        let to = event.status();
+
+        if !from.can_transition_to(to) {
+            return Err(StatusError { flight: flight.clone(), from, to })
+        }
+
+        let state = self.flights.entry(flight.clone()).or_default();
+        state.apply(event);
+        Ok(state)
+    }
+
+    pub fn get(&self, flight: &FlightKey) -> Option<&FlightState> {
+        self.flights.get(flight)
+    }
+
+    /// Forgets a flight, like after it arrived
+    pub fn remove(&mut self, flight: &FlightKey) -> Option<FlightState> {
+        self.flights.remove(flight)
+    }
+
+    /// Flights with the given status
+    pub fn with_status(&self, status: FlightStatus) -> impl Iterator<Item = &FlightKey> + '_ {
+        self.flights.iter()
+            .filter(move |&(_, state)| state.status == status)
+            .map(|(key, _)| key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.flights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flights.is_empty()
+    }
+}
@@ -0,0 +1,198 @@
+//! Generator of realistic random boarding passes for load testing
+//!
+//! Output is deterministic for a seed, so a failing load test can be replayed.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::gen::Generator;
+//! use iata::bcbp::BCBP;
+//!
+//! for src in Generator::new(42).take(10) {
+//!     assert!(BCBP::from(&src).is_ok());
+//! }
+//! ```
+
+const AIRLINES: [(&str, &str, u32); 8] = [
+    ("SU", "555", 12),
+    ("LH", "220", 10),
+    ("AF", "057", 8),
+    ("BA", "125", 8),
+    ("KL", "074", 6),
+    ("SK", "117", 4),
+    ("U2", "888", 4),
+    ("FR", "224", 3),
+];
+
+const AIRPORTS: [(&str, u32); 12] = [
+    ("SVO", 10), ("FRA", 10), ("LHR", 10), ("CDG", 9), ("AMS", 8), ("MUC", 6),
+    ("JFK", 6), ("LED", 4), ("CPH", 4), ("DME", 3), ("STN", 3), ("ARN", 3),
+];
+
+const LAST_NAMES: [&str; 10] = [
+    "SMITH", "IVANOV", "MUELLER", "MARTIN", "JOHNSON", "PETROVA", "GARCIA", "DUBOIS", "NIELSEN", "VERYLONGLASTNAMEFOR",
+];
+
+const FIRST_NAMES: [&str; 10] = [
+    "JOHN", "ANNA", "MARIA", "ALEXANDER", "JEAN PIERRE", "SOFIA", "IVAN", "OLGA", "PETER MR", "EMMA MRS",
+];
+
+const COMPARTMENTS: [(char, u32); 4] = [('Y', 12), ('M', 4), ('C', 2), ('F', 1)];
+
+const CHECKIN_SOURCES: [char; 8] = ['W', 'K', 'R', 'M', 'O', 'T', 'V', ' '];
+
+/// Seeded generator of BCBP strings
+#[derive(Debug, Clone)]
+pub struct Generator {
+    state: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Generator { state: seed }
+    }
+
+    // SplitMix64, good enough for test data and dependency free
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u32) -> u32 {
+        (self.next_u64() % u64::from(n)) as u32
+    }
+
+    fn chance(&mut self, percent: u32) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u32) as usize]
+    }
+
+    fn weighted<'a, T>(&mut self, items: &'a [T], weight: fn(&T) -> u32) -> &'a T {
+        let total = items.iter().map(weight).sum();
+        let mut n = self.below(total);
+
+        for item in items {
+            if n < weight(item) {
+                return item
+            }
+            n -= weight(item);
+        }
+
+        &items[items.len() - 1]
+    }
+
+    fn digits(&mut self, len: usize) -> String {
+        (0 .. len).map(|_| (b'0' + self.below(10) as u8) as char).collect()
+    }
+
+    fn letters(&mut self, len: usize) -> String {
+        (0 .. len).map(|_| (b'A' + self.below(26) as u8) as char).collect()
+    }
+
+    /// Generates the next boarding pass
+    pub fn pass(&mut self) -> String {
+        let legs = match self.below(10) {
+            0 ..= 5 => 1,
+            6 ..= 8 => 2,
+            _       => 3,
+        };
+
+        let mut name = format!("{}/{}", self.pick(&LAST_NAMES), self.pick(&FIRST_NAMES));
+        name.truncate(20);
+
+        let pnr = self.letters(6);
+        let version = if self.chance(70) { Some((b'2' + self.below(6) as u8) as char) } else { None };
+
+        let mut ret = format!("M{}{:<20}E", legs, name);
+        let mut airport = self.weighted(&AIRPORTS, |a| a.1).0;
+        let day = 1 + self.below(365);
+
+        for leg in 0 .. legs {
+            let airline = self.weighted(&AIRLINES, |a| a.2);
+
+            let mut dst = self.weighted(&AIRPORTS, |a| a.1).0;
+            while dst == airport {
+                dst = self.weighted(&AIRPORTS, |a| a.1).0;
+            }
+
+            let compartment = self.weighted(&COMPARTMENTS, |c| c.1).0;
+            let seat = format!("{:03}{}", 1 + self.below(40), (b'A' + self.below(6) as u8) as char);
+
+            let conditional = match version {
+                Some(v) => self.conditional(v, leg == 0, airline),
+                None    => String::new(),
+            };
+
+            ret.push_str(&format!("{:<7}{}{}{:<3}{:<5}{:03}{}{}{:04} 1{:02X}{}",
+                pnr, airport, dst, airline.0, 100 + self.below(9000), day,
+                compartment, seat, 1 + self.below(400), conditional.len(), conditional));
+
+            airport = dst;
+        }
+
+        ret
+    }
+
+    fn conditional(&mut self, version: char, first: bool, airline: &(&str, &str, u32)) -> String {
+        let mut ret = String::new();
+
+        if first {
+            let tags = if self.chance(40) {
+                format!("0{}{}{:03}", airline.1, self.digits(6), 1 + self.below(2))
+            } else {
+                " ".repeat(13)
+            };
+
+            let unique = format!("{}{}{}{}{}{:<3}{}",
+                self.below(9),
+                self.pick(&CHECKIN_SOURCES),
+                self.pick(&CHECKIN_SOURCES),
+                self.digits(4),
+                if self.chance(90) { 'B' } else { 'I' },
+                airline.0,
+                tags);
+
+            ret.push_str(&format!(">{}{:02X}{}", version, unique.len(), unique));
+        }
+
+        let ff = if self.chance(30) {
+            format!("{:<3}{:<16}", airline.0, self.digits(10))
+        } else {
+            " ".repeat(19)
+        };
+
+        let mut repeated = format!("{}{}{}{}{:<3}{} {}K",
+            airline.1,
+            self.digits(10),
+            self.below(2),
+            self.below(3),
+            airline.0,
+            ff,
+            20 + 3 * self.below(4));
+
+        if version >= '6' {
+            repeated.push(if self.chance(10) { 'Y' } else { 'N' });
+        }
+
+        ret.push_str(&format!("{:02X}{}", repeated.len(), repeated));
+
+        if self.chance(20) {
+            ret.push_str(&format!("*{}", self.digits(8)));
+        }
+
+        ret
+    }
+}
+
+impl Iterator for Generator {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        Some(self.pass())
+    }
+}
@@ -0,0 +1,85 @@
+//! GeoJSON export of the route of a pass
+//!
+//! Each leg between airports of the station table becomes a LineString
+//! feature from the departure to the arrival airport, with the flight,
+//! carrier, airports and date of the leg as properties. Legs with an
+//! airport missing from the table are left out.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::BCBP;
+//! use iata::clock::FixedClock;
+//! use iata::date;
+//! use iata::geojson;
+//! use iata::station::StationTable;
+//!
+//! let pass = BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A182Y001Z0007 000").unwrap();
+//! let clock = FixedClock(date::from_ordinal(2017, 150).unwrap());
+//!
+//! let route = geojson::route_with(&pass, &StationTable::builtin(), &clock);
+//! assert!(route.contains(r#""coordinates":[[-73.7781,40.6413],[37.4146,55.9726]]"#));
+//! assert!(route.contains(r#""date":"2017-07-01""#));
+//! ```
+
+use bcbp::{BCBP, Segment};
+use clock::{Clock, SystemClock};
+use station::StationTable;
+
+/// JSON string literal
+fn string(value: &str) -> String {
+    let mut ret = String::with_capacity(value.len() + 2);
+    ret.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"'  => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            c if (c as u32) < 0x20 => ret.push_str(&format!("\\u{:04x}", c as u32)),
+            c    => ret.push(c),
+        }
+    }
+
+    ret.push('"');
+    ret
+}
+
+/// LineString feature of a leg, `None` when an airport is missing from the table
+///
+/// The date is resolved within the year of the clock and is `null` when
+/// the leg has no flight day.
+pub fn feature<C: Clock>(segment: &Segment, stations: &StationTable, clock: &C) -> Option<String> {
+    let from = stations.get(segment.src_airport())?;
+    let to = stations.get(segment.dst_airport())?;
+
+    let date = match segment.try_flight_date_current_year_with(clock) {
+        Ok(date) => string(&date.format("%Y-%m-%d").to_string()),
+        Err(_)   => "null".to_string(),
+    };
+
+    Some(format!(
+        concat!(
+            r#"{{"type":"Feature","geometry":{{"type":"LineString","coordinates":[[{},{}],[{},{}]]}},"#,
+            r#""properties":{{"flight":{},"carrier":{},"from":{},"to":{},"date":{}}}}}"#,
+        ),
+        from.longitude, from.latitude, to.longitude, to.latitude,
+        string(&format!("{}{}", segment.airline(), segment.flight_code())),
+        string(segment.airline()),
+        string(&from.code),
+        string(&to.code),
+        date,
+    ))
+}
+
+/// FeatureCollection of the legs of a pass
+pub fn route(bcbp: &BCBP, stations: &StationTable) -> String {
+    route_with(bcbp, stations, &SystemClock)
+}
+
+/// Same as `route()`, but resolves flight dates with the given clock
+pub fn route_with<C: Clock>(bcbp: &BCBP, stations: &StationTable, clock: &C) -> String {
+    let features: Vec<String> = bcbp.segments().iter()
+        .filter_map(|segment| feature(segment, stations, clock))
+        .collect();
+
+    format!(r#"{{"type":"FeatureCollection","features":[{}]}}"#, features.join(","))
+}
@@ -0,0 +1,157 @@
+//! iCalendar export of boarding passes
+//!
+//! Each leg becomes a VEVENT titled with the flight and city pair, located
+//! at the departure airport. Passes carry the day of the flight but not its
+//! time, so by default an event takes the whole day, which is the local day
+//! at the departure airport. With a departure time, `event()` starts the
+//! event at that wall clock time in the time zone of the airport and ends it
+//! after the estimated block time.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::BCBP;
+//! use iata::clock::FixedClock;
+//! use iata::date;
+//! use iata::ics;
+//! use iata::station::StationTable;
+//!
+//! let pass = BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A182Y001Z0007 000").unwrap();
+//! let clock = FixedClock(date::from_ordinal(2017, 150).unwrap());
+//!
+//! let calendar = ics::calendar_with(&pass, &StationTable::builtin(), &clock);
+//! assert!(calendar.contains("SUMMARY:SU1234A JFK→SVO\r\n"));
+//! assert!(calendar.contains("DTSTART;VALUE=DATE:20170701\r\n"));
+//! ```
+
+use chrono::prelude::*;
+use chrono::Duration;
+
+use bcbp::{BCBP, Segment};
+use clock::{Clock, SystemClock};
+use station::StationTable;
+
+const PRODID: &str = "-//iata-rs//BCBP//EN";
+
+/// Content lines are folded at 75 octets
+const LINE_LEN: usize = 75;
+
+/// Escapes a TEXT value
+fn escape(value: &str) -> String {
+    let mut ret = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => { ret.push('\\'); ret.push(c) },
+            '\n'             => ret.push_str("\\n"),
+            '\r'             => {},
+            c                => ret.push(c),
+        }
+    }
+
+    ret
+}
+
+/// Appends a content line, folded on character boundaries and terminated with CRLF
+fn line(out: &mut String, content: &str) {
+    let mut len = 0;
+
+    for c in content.chars() {
+        if len + c.len_utf8() > LINE_LEN {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+
+        out.push(c);
+        len += c.len_utf8();
+    }
+
+    out.push_str("\r\n");
+}
+
+fn utc(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// VEVENT of a leg, `None` when its flight date can't be resolved
+///
+/// The flight date is resolved within the year of the clock, which also
+/// gives the timestamp of the event. A departure time unknown to the
+/// station table or skipped by a daylight saving transition falls back to
+/// a whole day event.
+pub fn event<C: Clock>(segment: &Segment, stations: &StationTable, clock: &C, departure: Option<NaiveTime>) -> Option<String> {
+    let date = segment.try_flight_date_current_year_with(clock).ok()?;
+    let origin = stations.get(segment.src_airport());
+
+    let start = match (departure, origin) {
+        (Some(time), Some(station)) => station.local_to_utc(date, time).ok(),
+        _ => None,
+    };
+
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VEVENT");
+
+    let uid = format!("{}{}-{}-{}-{}", segment.airline(), segment.flight_code(), date.format("%Y%m%d"), segment.src_airport(), segment.sequence());
+    line(&mut out, &format!("UID:{}", uid));
+    line(&mut out, &format!("DTSTAMP:{}T000000Z", clock.today().format("%Y%m%d")));
+
+    match start {
+        Some(start) => {
+            line(&mut out, &format!("DTSTART:{}", utc(&start)));
+
+            if let Some(block) = stations.segment_block_time(segment) {
+                line(&mut out, &format!("DTEND:{}", utc(&(start + block))));
+            }
+        },
+        None => {
+            line(&mut out, &format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+            line(&mut out, &format!("DTEND;VALUE=DATE:{}", (date + Duration::days(1)).format("%Y%m%d")));
+        },
+    }
+
+    let summary = format!("{}{} {}→{}", segment.airline(), segment.flight_code(), segment.src_airport(), segment.dst_airport());
+    line(&mut out, &format!("SUMMARY:{}", escape(&summary)));
+
+    let location = match origin {
+        Some(station) => format!("{} ({})", station.name, station.code),
+        None          => segment.src_airport().to_string(),
+    };
+    line(&mut out, &format!("LOCATION:{}", escape(&location)));
+
+    let mut description = format!("Booking {}", segment.pnr());
+
+    if !segment.seat().is_empty() {
+        description.push_str(&format!(", seat {}", segment.seat()));
+    }
+
+    if segment.sequence() > 0 {
+        description.push_str(&format!(", sequence {}", segment.sequence()));
+    }
+
+    line(&mut out, &format!("DESCRIPTION:{}", escape(&description)));
+    line(&mut out, "END:VEVENT");
+
+    Some(out)
+}
+
+/// VCALENDAR with a whole day VEVENT per leg, legs without a flight date are left out
+pub fn calendar(bcbp: &BCBP, stations: &StationTable) -> String {
+    calendar_with(bcbp, stations, &SystemClock)
+}
+
+/// Same as `calendar()`, but resolves flight dates with the given clock
+pub fn calendar_with<C: Clock>(bcbp: &BCBP, stations: &StationTable, clock: &C) -> String {
+    let mut out = String::new();
+
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, &format!("PRODID:{}", PRODID));
+
+    for segment in bcbp.segments() {
+        if let Some(event) = event(segment, stations, clock, None) {
+            out.push_str(&event);
+        }
+    }
+
+    line(&mut out, "END:VCALENDAR");
+    out
+}
@@ -1,5 +1,85 @@
-#[macro_use]
 extern crate nom;
+#[cfg(feature = "chrono")]
 extern crate chrono;
+#[cfg(feature = "time")]
+extern crate time;
+#[cfg(feature = "station")]
+extern crate chrono_tz;
+#[cfg(feature = "crypto")]
+extern crate hmac;
+#[cfg(feature = "crypto")]
+extern crate sha2;
+#[cfg(feature = "offline")]
+extern crate flate2;
+#[cfg(feature = "anyhow")]
+extern crate anyhow;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+compile_error!("either `chrono` or `time` feature must be enabled");
+
+pub mod spec;
+pub mod record;
+pub mod phonetic;
+pub mod names;
+pub mod bagtag;
+pub mod pax_status;
+pub mod seatmap;
+pub mod coupon;
+pub mod tax;
+pub mod redact;
+
+/// Declares modules working with dates, which are left out without a date
+/// library so the `compile_error!` above is the only error
+macro_rules! dated {
+    ($($module:item)*) => {
+        $(
+            #[cfg(any(feature = "chrono", feature = "time"))]
+            $module
+        )*
+    };
+}
 
-pub mod bcbp;
+dated! {
+    pub mod bcbp;
+    pub mod prelude;
+    pub mod clock;
+    pub mod date;
+    pub mod datetime;
+    pub mod describe;
+    pub mod callsign;
+    pub mod boarding;
+    pub mod assistance;
+    pub mod ssim;
+    pub mod flight;
+    pub mod flight_status;
+    pub mod pass;
+    pub mod cuss;
+    pub mod scan;
+    pub mod diagnostics;
+    pub mod airline_use;
+    #[cfg(feature = "reference")]
+    pub mod reference;
+    #[cfg(feature = "report")]
+    pub mod report;
+    #[cfg(feature = "gen")]
+    pub mod gen;
+    #[cfg(feature = "testkit")]
+    pub mod testkit;
+    #[cfg(feature = "station")]
+    pub mod station;
+    #[cfg(feature = "emissions")]
+    pub mod emissions;
+    #[cfg(feature = "ics")]
+    pub mod ics;
+    #[cfg(feature = "geojson")]
+    pub mod geojson;
+    #[cfg(feature = "crypto")]
+    pub mod audit;
+    #[cfg(feature = "offline")]
+    pub mod offline;
+    #[cfg(feature = "anyhow")]
+    pub mod fallible;
+}
@@ -0,0 +1,182 @@
+//! Passenger name comparison tolerant to how names get mangled on the way to a pass
+//!
+//! Names are compared as `LAST/FIRST`. A reservation name doesn't fit the
+//! 20 character name field, first names are cut to initials, titles get
+//! appended to first names and letters outside of ASCII are transliterated
+//! in different ways, like `MÜLLER`, `MUELLER` and `MULLER`.
+//!
+//! | Score | Names                                                   |
+//! |-------|---------------------------------------------------------|
+//! | 100   | Same after collapsing whitespace and uppercasing        |
+//! | 90    | Same after dropping titles or folding transliterations  |
+//! | 80    | One is cut at the name field length or to initials      |
+//! | 50    | Same last name, different first name                    |
+//! | 0     | Anything else                                           |
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::names::{self, Tolerance};
+//!
+//! assert_eq!(names::match_score("Smith/John", "SMITH/JOHN"), 100);
+//! assert_eq!(names::match_score("MÜLLER/ANNA MRS", "MUELLER/ANNA"), 90);
+//! assert_eq!(names::match_score("SMITH/J", "SMITH/JOHN"), 80);
+//! assert_eq!(names::match_score_with("SMITH/J", "SMITH/JOHN", &Tolerance::strict()), 50);
+//! ```
+
+/// Length of the name field of a boarding pass
+pub const NAME_LEN: usize = 20;
+
+const TITLES: [&str; 11] = ["MR", "MRS", "MS", "MISS", "MSTR", "MSS", "DR", "PROF", "REV", "CHD", "INF"];
+
+/// Differences `match_score_with()` tolerates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tolerance {
+    /// Name cut at the name field length, first names cut to initials
+    pub truncation: bool,
+    /// Diacritics and their digraph spellings, like `Ü`, `UE` and `U`
+    pub transliteration: bool,
+    /// Titles like `MR` or `DR` in the first name
+    pub titles: bool,
+}
+
+impl Tolerance {
+    /// Only case and whitespace differences
+    pub fn strict() -> Tolerance {
+        Tolerance {
+            truncation: false,
+            transliteration: false,
+            titles: false,
+        }
+    }
+
+    pub fn lenient() -> Tolerance {
+        Tolerance {
+            truncation: true,
+            transliteration: true,
+            titles: true,
+        }
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance::lenient()
+    }
+}
+
+/// Uppercases and collapses whitespace
+pub fn normalize(src: &str) -> String {
+    src.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+/// Whether `field` is `full` cut to fit the name field of a pass
+pub fn is_field_truncation(full: &str, field: &str) -> bool {
+    full.len() > NAME_LEN
+        && full.is_ascii()
+        && full[.. NAME_LEN].trim_end() == field.trim_end()
+}
+
+fn split(name: &str) -> (String, String) {
+    match name.find('/') {
+        Some(pos) => (name[.. pos].trim().into(), name[pos + 1 ..].trim().into()),
+        None      => (name.trim().into(), String::new()),
+    }
+}
+
+fn without_titles(first: &str) -> String {
+    first.split(' ')
+        .filter(|word| !TITLES.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn transliterate(src: &str) -> String {
+    let mut ret = String::with_capacity(src.len());
+
+    for c in src.chars() {
+        match c {
+            'Ä' | 'Æ'             => ret.push_str("AE"),
+            'Ö' | 'Ø' | 'Œ'       => ret.push_str("OE"),
+            'Ü'                   => ret.push_str("UE"),
+            'Å'                   => ret.push_str("AA"),
+            'ß'                   => ret.push_str("SS"),
+            'À' ..= 'Ã'           => ret.push('A'),
+            'Ç'                   => ret.push('C'),
+            'È' ..= 'Ë'           => ret.push('E'),
+            'Ì' ..= 'Ï'           => ret.push('I'),
+            'Ñ'                   => ret.push('N'),
+            'Ò' ..= 'Õ'           => ret.push('O'),
+            'Ù' ..= 'Û'           => ret.push('U'),
+            'Ý'                   => ret.push('Y'),
+            '-'                   => ret.push(' '),
+            '\'' | '.'            => {},
+            c                     => ret.push(c),
+        }
+    }
+
+    // Digraphs and their single letter spellings compare equal
+    normalize(&ret)
+        .replace("AE", "A")
+        .replace("OE", "O")
+        .replace("UE", "U")
+        .replace("AA", "A")
+}
+
+fn is_initials_of(short: &str, long: &str) -> bool {
+    let short: Vec<&str> = short.split(' ').collect();
+    let long: Vec<&str> = long.split(' ').collect();
+
+    !short[0].is_empty()
+        && short.len() <= long.len()
+        && short.iter().zip(long.iter()).all(|(s, l)| l.starts_with(s))
+}
+
+/// Similarity of two `LAST/FIRST` names from 0 to 100, with every tolerance enabled
+pub fn match_score(a: &str, b: &str) -> u8 {
+    match_score_with(a, b, &Tolerance::lenient())
+}
+
+/// Similarity of two `LAST/FIRST` names from 0 to 100
+pub fn match_score_with(a: &str, b: &str, tolerance: &Tolerance) -> u8 {
+    let (a, b) = (normalize(a), normalize(b));
+
+    if a == b {
+        return 100
+    }
+
+    if tolerance.truncation && (is_field_truncation(&a, &b) || is_field_truncation(&b, &a)) {
+        return 80
+    }
+
+    let (mut last_a, mut first_a) = split(&a);
+    let (mut last_b, mut first_b) = split(&b);
+
+    if tolerance.titles {
+        first_a = without_titles(&first_a);
+        first_b = without_titles(&first_b);
+    }
+
+    if tolerance.transliteration {
+        last_a = transliterate(&last_a);
+        last_b = transliterate(&last_b);
+        first_a = transliterate(&first_a);
+        first_b = transliterate(&first_b);
+    }
+
+    if last_a != last_b {
+        return 0
+    }
+
+    if first_a == first_b {
+        return 90
+    }
+
+    if tolerance.truncation && (is_initials_of(&first_a, &first_b) || is_initials_of(&first_b, &first_a)) {
+        return 80
+    }
+
+    50
+}
@@ -0,0 +1,235 @@
+//! Reference data snapshots for provisioning offline devices
+//!
+//! A snapshot is a single gzip file with airports, airline codes, minimum
+//! connecting times and named keys, like the secret of boarding tokens.
+//! Kiosks and gates get it at provisioning and load it at startup, so they
+//! work without reaching a backend. Keys are stored as they are, protect
+//! snapshots like any other secret.
+//!
+//! Inside the gzip stream the snapshot is text, a `iata-reference 1` header
+//! followed by one tab separated record per line, tagged `S` for stations,
+//! `A` for airlines, `M` for connecting times and `K` for hex keys.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::offline::{ConnectTime, ReferenceData};
+//!
+//! let mut data = ReferenceData::builtin();
+//! data.mct.insert("SVO".into(), ConnectTime { domestic: 50, international: 90 });
+//! data.keys.insert("boarding-token".into(), b"secret".to_vec());
+//!
+//! let mut snapshot = Vec::new();
+//! data.write_to(&mut snapshot).unwrap();
+//!
+//! let loaded = ReferenceData::read_from(&snapshot[..]).unwrap();
+//! assert_eq!(loaded.stations.country("JFK"), Some("US"));
+//! assert_eq!(loaded.min_connect_time("SVO", true), Some(90));
+//! ```
+
+use std::fmt;
+use std::error;
+use std::fs::File;
+use std::path::Path;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use callsign::CallsignTable;
+use station::{Station, StationTable};
+
+const HEADER: &str = "iata-reference 1";
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// Reading, writing or decompressing failed
+    Io(io::Error),
+    /// Data doesn't start with a supported header
+    Version(String),
+    /// Record that can't be read
+    Record { line: u64 },
+    /// Name or value with a tab or a line break, which a snapshot can't hold
+    Field(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SnapshotError::Io(ref e)        => write!(f, "{}", e),
+            SnapshotError::Version(ref v)   => write!(f, "unsupported snapshot header {:?}", v),
+            SnapshotError::Record { line }  => write!(f, "malformed record on line {}", line),
+            SnapshotError::Field(ref s)     => write!(f, "{:?} contains a tab or a line break", s),
+        }
+    }
+}
+
+impl error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            SnapshotError::Io(ref e) => Some(e),
+            _                        => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> SnapshotError {
+        SnapshotError::Io(e)
+    }
+}
+
+/// Minimum connecting times of an airport in minutes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectTime {
+    pub domestic: u32,
+    pub international: u32,
+}
+
+/// Reference data a device needs to work offline
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceData {
+    pub stations: StationTable,
+    pub airlines: CallsignTable,
+    /// Minimum connecting times by IATA airport code
+    pub mct: BTreeMap<String, ConnectTime>,
+    /// Named secrets, like the key of `BCBP::boarding_token()`
+    pub keys: BTreeMap<String, Vec<u8>>,
+}
+
+impl ReferenceData {
+    pub fn new() -> ReferenceData {
+        ReferenceData::default()
+    }
+
+    /// Builtin stations and airlines, without connecting times and keys
+    pub fn builtin() -> ReferenceData {
+        ReferenceData {
+            stations: StationTable::builtin(),
+            airlines: CallsignTable::builtin(),
+            ..ReferenceData::default()
+        }
+    }
+
+    /// Minimum connecting time at the airport in minutes
+    pub fn min_connect_time(&self, airport: &str, international: bool) -> Option<u32> {
+        self.mct.get(&airport.trim().to_uppercase())
+            .map(|t| if international { t.international } else { t.domestic })
+    }
+
+    /// Writes a snapshot to a file, replacing it
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_to(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Loads a snapshot written by `export()`
+    pub fn import<P: AsRef<Path>>(path: P) -> Result<ReferenceData, SnapshotError> {
+        ReferenceData::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes a compressed snapshot, records are sorted so equal data gives equal files
+    pub fn write_to<W: Write>(&self, w: W) -> Result<(), SnapshotError> {
+        let mut lines = Vec::new();
+
+        let mut stations: Vec<&Station> = self.stations.iter().collect();
+        stations.sort_by(|a, b| a.code.cmp(&b.code));
+
+        for s in stations {
+            lines.push(record(&["S", &s.code, &s.country, &s.name, s.timezone.name(), &s.latitude.to_string(), &s.longitude.to_string()])?);
+        }
+
+        let mut airlines: Vec<(&str, &str)> = self.airlines.iter().collect();
+        airlines.sort();
+
+        for (iata, icao) in airlines {
+            lines.push(record(&["A", iata, icao])?);
+        }
+
+        for (airport, time) in &self.mct {
+            lines.push(record(&["M", airport, &time.domestic.to_string(), &time.international.to_string()])?);
+        }
+
+        for (name, key) in &self.keys {
+            let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+            lines.push(record(&["K", name, &hex])?);
+        }
+
+        let mut gz = GzEncoder::new(w, Compression::default());
+        writeln!(gz, "{}", HEADER)?;
+
+        for line in lines {
+            writeln!(gz, "{}", line)?;
+        }
+
+        gz.finish()?;
+        Ok(())
+    }
+
+    /// Reads a compressed snapshot
+    pub fn read_from<R: Read>(r: R) -> Result<ReferenceData, SnapshotError> {
+        let mut lines = BufReader::new(GzDecoder::new(r)).lines();
+        let header = lines.next().transpose()?.unwrap_or_default();
+
+        if header != HEADER {
+            return Err(SnapshotError::Version(header))
+        }
+
+        let mut ret = ReferenceData::new();
+
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+
+            if line.is_empty() {
+                continue
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            ret.insert(&fields).ok_or(SnapshotError::Record { line: i as u64 + 2 })?;
+        }
+
+        Ok(ret)
+    }
+
+    fn insert(&mut self, fields: &[&str]) -> Option<()> {
+        match *fields {
+            ["S", code, country, name, timezone, latitude, longitude] => self.stations.insert(Station {
+                code: code.into(),
+                country: country.into(),
+                name: name.into(),
+                timezone: timezone.parse().ok()?,
+                latitude: latitude.parse().ok()?,
+                longitude: longitude.parse().ok()?,
+            }),
+            ["A", iata, icao] => self.airlines.insert(iata, icao),
+            ["M", airport, domestic, international] => {
+                let time = ConnectTime {
+                    domestic: domestic.parse().ok()?,
+                    international: international.parse().ok()?,
+                };
+                self.mct.insert(airport.into(), time);
+            },
+            ["K", name, hex] => {
+                let key = (0 .. hex.len()).step_by(2)
+                    .map(|i| hex.get(i .. i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+                    .collect::<Option<Vec<u8>>>()?;
+                self.keys.insert(name.into(), key);
+            },
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
+
+fn record(fields: &[&str]) -> Result<String, SnapshotError> {
+    if let Some(field) = fields.iter().find(|f| f.contains(['\t', '\r', '\n'])) {
+        return Err(SnapshotError::Field(field.to_string()))
+    }
+
+    Ok(fields.join("\t"))
+}
@@ -0,0 +1,209 @@
+//! Issuing side facade over a pass, its signature and the payload of its barcode
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::BCBP;
+//! use iata::pass::{Pass, PayloadFormat};
+//!
+//! let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+//! let pass = Pass::new(bcbp).with_signature('1', "GIWVC5EH7J");
+//!
+//! assert!(pass.payload(PayloadFormat::Text).unwrap().ends_with("^10AGIWVC5EH7J"));
+//! assert!(pass.payload(PayloadFormat::WalletJson).unwrap().contains("PKBarcodeFormatPDF417"));
+//!
+//! let small = pass.with_capacity(80);
+//! assert!(small.capacity_warning(0).is_none());
+//! assert!(small.capacity_warning(6).is_some());
+//! ```
+//!
+//! The crate has no 2D symbology encoder and draws no symbols. `Pdf417` and
+//! `Aztec` give the message to feed into one, checked against the capacity
+//! of the symbol.
+
+use std::fmt;
+use std::error;
+
+use bcbp::{BCBP, BuildError, CapacityWarning, SecuritySection};
+
+/// Text compaction capacity of the largest PDF417 symbol
+const PDF417_CAPACITY: usize = 1850;
+/// Byte mode capacity of the largest Aztec symbol
+const AZTEC_CAPACITY: usize = 1914;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PayloadFormat {
+    /// Barcode data as is, not checked against a capacity
+    Text,
+    /// Message of a PDF417 symbol, the barcode data for an external encoder
+    Pdf417,
+    /// Message of an Aztec symbol, the barcode data for an external encoder
+    Aztec,
+    /// `barcodes` entry of a wallet pass, which the wallet draws as a PDF417 symbol
+    WalletJson,
+}
+
+/// Reasons the payload of a pass can't be given
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PayloadError {
+    /// The pass can't be encoded
+    Build(BuildError),
+    /// Barcode data doesn't fit the symbol
+    Capacity {
+        len: usize,
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for PayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PayloadError::Build(ref e) => write!(f, "{}", e),
+            PayloadError::Capacity { len, capacity } => write!(f, "pass takes {} characters, the symbol holds {}", len, capacity),
+        }
+    }
+}
+
+impl error::Error for PayloadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            PayloadError::Build(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<BuildError> for PayloadError {
+    fn from(e: BuildError) -> PayloadError {
+        PayloadError::Build(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Pass {
+    bcbp: BCBP,
+    signature: Option<SecuritySection>,
+    capacity: Option<usize>,
+}
+
+fn json_string(src: &str) -> String {
+    let mut ret = String::with_capacity(src.len() + 2);
+    ret.push('"');
+
+    for c in src.chars() {
+        match c {
+            '"'  => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            c if (c as u32) < 0x20 => ret.push_str(&format!("\\u{:04x}", c as u32)),
+            c => ret.push(c),
+        }
+    }
+
+    ret.push('"');
+    ret
+}
+
+impl Pass {
+    pub fn new(bcbp: BCBP) -> Pass {
+        Pass {
+            bcbp,
+            signature: None,
+            capacity: None,
+        }
+    }
+
+    /// Signs the pass, replacing the security section of the wrapped `BCBP`
+    pub fn with_signature(mut self, kind: char, data: &str) -> Pass {
+        self.signature = Some(SecuritySection::Standard {
+            kind,
+            data: data.into(),
+        });
+        self
+    }
+
+    /// Characters the printed symbol holds, the largest symbol of the symbology by default
+    ///
+    /// `payload()` checks the symbol formats against it, up to the capacity
+    /// of the largest symbol.
+    pub fn with_capacity(mut self, capacity: usize) -> Pass {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Capacity of the symbol of a format, `None` for `Text`
+    fn capacity(&self, format: PayloadFormat) -> Option<usize> {
+        let max = match format {
+            PayloadFormat::Pdf417 | PayloadFormat::WalletJson => PDF417_CAPACITY,
+            PayloadFormat::Aztec => AZTEC_CAPACITY,
+            PayloadFormat::Text  => return None,
+        };
+
+        Some(self.capacity.map_or(max, |capacity| capacity.min(max)))
+    }
+
+    /// Warns when the signed pass with conditional items up to `version` doesn't fit the symbol
+    ///
+    /// The symbol is a PDF417 one unless `with_capacity()` was given.
+    pub fn capacity_warning(&self, version: u8) -> Option<CapacityWarning> {
+        let capacity = self.capacity.unwrap_or(PDF417_CAPACITY);
+
+        match self.signature {
+            Some(ref signature) => {
+                let mut bcbp = self.bcbp.clone();
+                bcbp.set_security(Some(signature.clone()));
+                bcbp.check_capacity(version, capacity)
+            },
+            None => self.bcbp.check_capacity(version, capacity),
+        }
+    }
+
+    pub fn bcbp(&self) -> &BCBP {
+        &self.bcbp
+    }
+
+    pub fn bcbp_mut(&mut self) -> &mut BCBP {
+        &mut self.bcbp
+    }
+
+    pub fn signature(&self) -> Option<&SecuritySection> {
+        self.signature.as_ref().or_else(|| self.bcbp.security())
+    }
+
+    pub fn into_bcbp(self) -> BCBP {
+        self.bcbp
+    }
+
+    fn data(&self) -> Result<String, BuildError> {
+        match self.signature {
+            Some(ref signature) => {
+                let mut bcbp = self.bcbp.clone();
+                bcbp.set_security(Some(signature.clone()));
+                bcbp.try_build()
+            },
+            None => self.bcbp.try_build(),
+        }
+    }
+
+    /// Signed barcode data in the format, symbol formats fail when it doesn't fit the symbol
+    ///
+    /// The data is the message of the symbol, not an image of it.
+    pub fn payload(&self, format: PayloadFormat) -> Result<String, PayloadError> {
+        let data = self.data()?;
+
+        if let Some(capacity) = self.capacity(format) {
+            if data.len() > capacity {
+                return Err(PayloadError::Capacity { len: data.len(), capacity })
+            }
+        }
+
+        match format {
+            PayloadFormat::WalletJson => Ok(format!(
+                "{{\"format\":\"PKBarcodeFormatPDF417\",\"message\":{},\"messageEncoding\":\"iso-8859-1\",\"altText\":{}}}",
+                json_string(&data),
+                json_string(&self.bcbp.name()),
+            )),
+            _ => Ok(data),
+        }
+    }
+}
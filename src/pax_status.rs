@@ -0,0 +1,80 @@
+//! Passenger status values, item 117
+
+/// Status of a passenger on a leg
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum PaxStatus {
+    /// Ticket issued, passenger not checked in
+    NotCheckedIn,
+    /// Ticket issued, passenger checked in
+    CheckedIn,
+    /// Baggage checked, passenger not checked in
+    BaggageCheckedNotCheckedIn,
+    /// Baggage checked, passenger checked in
+    BaggageCheckedCheckedIn,
+    PassedSecurity,
+    /// Passed the gate, coupon used
+    PassedGate,
+    Transit,
+    Standby,
+    BoardingRevalidated,
+    /// Original boarding line used at ticket issuance
+    OriginalBoardingLine,
+    /// Up or downgrade required at gate close out
+    GateUpgrade,
+    /// Airline specific value
+    Other(char),
+}
+
+impl PaxStatus {
+    pub fn as_char(&self) -> char {
+        match *self {
+            PaxStatus::NotCheckedIn               => '0',
+            PaxStatus::CheckedIn                  => '1',
+            PaxStatus::BaggageCheckedNotCheckedIn => '2',
+            PaxStatus::BaggageCheckedCheckedIn    => '3',
+            PaxStatus::PassedSecurity             => '4',
+            PaxStatus::PassedGate                 => '5',
+            PaxStatus::Transit                    => '6',
+            PaxStatus::Standby                    => '7',
+            PaxStatus::BoardingRevalidated        => '8',
+            PaxStatus::OriginalBoardingLine       => '9',
+            PaxStatus::GateUpgrade                => 'A',
+            PaxStatus::Other(c)                   => c,
+        }
+    }
+
+    pub fn is_standby(&self) -> bool {
+        *self == PaxStatus::Standby
+    }
+
+    /// Whether the passenger has not been through check-in, so travel documents weren't checked yet
+    pub fn needs_document_check(&self) -> bool {
+        matches!(*self, PaxStatus::NotCheckedIn | PaxStatus::BaggageCheckedNotCheckedIn)
+    }
+
+    /// Whether the value is defined by the specification rather than by an airline
+    pub fn is_standard(&self) -> bool {
+        !matches!(*self, PaxStatus::Other(_))
+    }
+}
+
+impl From<char> for PaxStatus {
+    fn from(c: char) -> PaxStatus {
+        match c.to_ascii_uppercase() {
+            '0' => PaxStatus::NotCheckedIn,
+            '1' => PaxStatus::CheckedIn,
+            '2' => PaxStatus::BaggageCheckedNotCheckedIn,
+            '3' => PaxStatus::BaggageCheckedCheckedIn,
+            '4' => PaxStatus::PassedSecurity,
+            '5' => PaxStatus::PassedGate,
+            '6' => PaxStatus::Transit,
+            '7' => PaxStatus::Standby,
+            '8' => PaxStatus::BoardingRevalidated,
+            '9' => PaxStatus::OriginalBoardingLine,
+            'A' => PaxStatus::GateUpgrade,
+            c   => PaxStatus::Other(c),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! ICAO/IATA spelling alphabet
+//!
+//! ```
+//! # extern crate iata;
+//! assert_eq!(iata::phonetic::spell("JFK"), "Juliett Foxtrot Kilo");
+//! assert_eq!(iata::phonetic::spell("SU 12"), "Sierra Uniform One Two");
+//! ```
+
+const LETTERS: [&str; 26] = [
+    "Alfa", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "India",
+    "Juliett", "Kilo", "Lima", "Mike", "November", "Oscar", "Papa", "Quebec", "Romeo",
+    "Sierra", "Tango", "Uniform", "Victor", "Whiskey", "X-ray", "Yankee", "Zulu",
+];
+
+const DIGITS: [&str; 10] = [
+    "Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine",
+];
+
+/// Code word of a letter or digit, case insensitive
+pub fn word(c: char) -> Option<&'static str> {
+    let c = c.to_ascii_uppercase();
+
+    match c {
+        'A' ..= 'Z' => Some(LETTERS[c as usize - 'A' as usize]),
+        '0' ..= '9' => Some(DIGITS[c as usize - '0' as usize]),
+        _ => None,
+    }
+}
+
+/// Spells a code word by word, characters without a code word are skipped
+pub fn spell(code: &str) -> String {
+    code.chars()
+        .filter_map(word)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
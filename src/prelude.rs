@@ -0,0 +1,26 @@
+//! Commonly used types in one import
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::prelude::*;
+//!
+//! let bcbp = BCBP::from_with("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000", ParseOptions::strict()).unwrap();
+//! assert_eq!(bcbp.segments()[0].passenger_status(), Some(PaxStatus::NotCheckedIn));
+//! assert_eq!(bcbp.segments()[0].city_pair(), CityPair::new("JFK", "SVO"));
+//! ```
+//!
+//! Exports are versioned, `v1` only ever gains items when a release adds
+//! them, so `use iata::prelude::v1::*` keeps compiling. The prelude itself
+//! follows the latest version.
+
+pub mod v1 {
+    pub use bcbp::{BCBP, Segment, ParseOptions, Error, BuildError, Warning, SecuritySection};
+    pub use bcbp::{TicketIndicator, PassengerDescription, Source, IssueDate};
+    pub use pax_status::PaxStatus;
+    pub use bagtag::TagSequence;
+    pub use flight::{FlightKey, CityPair, RouteType};
+    pub use pass::{Pass, PayloadFormat};
+    pub use date::{Date, Time, DateError};
+}
+
+pub use self::v1::*;
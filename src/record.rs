@@ -0,0 +1,208 @@
+//! Declarative parsing of fixed width records
+//!
+//! A `Layout` is a table of fields with their offsets, widths, charsets and
+//! optionality, so a new fixed width format is described as data rather than
+//! parsing code. The BCBP leg items are sliced with a layout built from the
+//! spec tables.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::record::{Field, Layout};
+//! use iata::spec::Charset;
+//!
+//! let layout = Layout::new(vec![
+//!     Field::new("airline", 0, 2, Charset::Alphanumeric),
+//!     Field::new("flight", 2, 4, Charset::Numeric),
+//!     Field::new("suffix", 6, 1, Charset::Alpha).optional(),
+//! ]);
+//!
+//! let record = layout.parse("SU0100").unwrap();
+//! assert_eq!(record.get("flight"), Some("0100"));
+//! assert_eq!(record.get("suffix"), None);
+//!
+//! let e = layout.parse("SU01X0").unwrap_err();
+//! assert_eq!((e.field, e.offset), ("flight", 4));
+//! ```
+
+use std::fmt;
+use std::error;
+
+use spec::{Charset, Item};
+
+/// Field of a record, offsets and widths are in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    pub offset: usize,
+    pub len: usize,
+    pub charset: Charset,
+    /// Optional fields may be cut off by the end of the record
+    pub optional: bool,
+}
+
+impl Field {
+    pub const fn new(name: &'static str, offset: usize, len: usize, charset: Charset) -> Field {
+        Field {
+            name,
+            offset,
+            len,
+            charset,
+            optional: false,
+        }
+    }
+
+    pub const fn optional(mut self) -> Field {
+        self.optional = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    fields: Vec<Field>,
+}
+
+impl Layout {
+    pub fn new(fields: Vec<Field>) -> Layout {
+        Layout { fields }
+    }
+
+    /// Layout of consecutive spec items, up to the first variable sized one
+    pub fn from_items(items: &[Item]) -> Layout {
+        let mut offset = 0;
+        let fields = items.iter()
+            .take_while(|item| !item.is_variable())
+            .map(|item| {
+                let field = Field::new(item.name, offset, item.len, item.charset);
+                offset += item.len;
+                field
+            })
+            .collect();
+
+        Layout::new(fields)
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Width of the record in characters
+    pub fn len(&self) -> usize {
+        self.fields.iter().map(|f| f.offset + f.len).max().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cuts the fields out of the record without checking their charsets
+    pub fn slice<'a>(&self, src: &'a str) -> Result<Record<'a>, RecordError> {
+        let mut values = Vec::with_capacity(self.fields.len());
+
+        for field in &self.fields {
+            let value = chars(src, field.offset, field.len);
+
+            if value.is_none() && !field.optional {
+                return Err(RecordError::new(field, field.offset, RecordErrorKind::Missing))
+            }
+
+            values.push((field.name, value));
+        }
+
+        let len = chars(src, 0, self.len()).map_or(src.len(), str::len);
+
+        Ok(Record {
+            values,
+            rest: &src[len ..],
+        })
+    }
+
+    /// Cuts the fields out of the record and checks them against their charsets
+    pub fn parse<'a>(&self, src: &'a str) -> Result<Record<'a>, RecordError> {
+        let record = self.slice(src)?;
+
+        for (field, &(_, value)) in self.fields.iter().zip(record.values.iter()) {
+            let value = value.unwrap_or("");
+
+            if let Some(pos) = value.chars().position(|c| !field.charset.accepts(c)) {
+                return Err(RecordError::new(field, field.offset + pos, RecordErrorKind::Charset))
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+/// `len` characters from the character offset, `None` past the end
+fn chars(src: &str, offset: usize, len: usize) -> Option<&str> {
+    let mut bounds = src.char_indices().map(|(i, _)| i).chain(Some(src.len()));
+
+    let start = bounds.nth(offset)?;
+    let end = if len == 0 { start } else { bounds.nth(len - 1)? };
+
+    Some(&src[start .. end])
+}
+
+/// Fields cut out of a record by a `Layout`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'a> {
+    values: Vec<(&'static str, Option<&'a str>)>,
+    rest: &'a str,
+}
+
+impl<'a> Record<'a> {
+    /// Value of the field, `None` for unknown fields and cut off optional ones
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.values.iter()
+            .find(|&&(n, _)| n == name)
+            .and_then(|&(_, value)| value)
+    }
+
+    /// Values in the order of the layout fields
+    pub fn values(&self) -> impl Iterator<Item = Option<&'a str>> + '_ {
+        self.values.iter().map(|&(_, value)| value)
+    }
+
+    /// Data following the record
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RecordErrorKind {
+    /// Required field lies past the end of the record
+    Missing,
+    /// Field contains characters outside of its charset
+    Charset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordError {
+    pub field: &'static str,
+    /// Character offset of the error in the record
+    pub offset: usize,
+    pub kind: RecordErrorKind,
+}
+
+impl RecordError {
+    fn new(field: &Field, offset: usize, kind: RecordErrorKind) -> RecordError {
+        RecordError {
+            field: field.name,
+            offset,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            RecordErrorKind::Missing => write!(f, "field '{}' is missing at offset {}", self.field, self.offset),
+            RecordErrorKind::Charset => write!(f, "field '{}' contains invalid characters at offset {}", self.field, self.offset),
+        }
+    }
+}
+
+impl error::Error for RecordError {}
@@ -0,0 +1,110 @@
+//! Redaction of passenger data in debug output
+//!
+//! Boarding passes end up in logs through `{:?}`, so `Debug` of the crate's
+//! types hides names, booking references and similar data. Building with the
+//! `full_debug` feature prints everything, which is meant for development only.
+//!
+//! `RedactionRules` mask passenger data in the pass itself, for data shipped
+//! off a device, see `BCBP::redact()`.
+
+use std::fmt;
+use std::ops::Deref;
+
+/// Wrapper printing `<redacted>` instead of the value in `Debug` output
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Redacted<T>(pub T);
+
+impl<T> Redacted<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Redacted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if cfg!(feature = "full_debug") {
+            self.0.fmt(f)
+        } else {
+            f.write_str("<redacted>")
+        }
+    }
+}
+
+/// Items `BCBP::redact()` masks, everything by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedactionRules {
+    pub name: bool,
+    pub pnr: bool,
+    pub frequent_flyer_number: bool,
+    /// Document form and serial number of the ticket, item 143
+    pub document_number: bool,
+    /// Airline individual use data, which may carry passenger data
+    pub airline_data: bool,
+    pub security_data: bool,
+}
+
+impl RedactionRules {
+    /// Keeps everything
+    pub fn none() -> RedactionRules {
+        RedactionRules {
+            name: false,
+            pnr: false,
+            frequent_flyer_number: false,
+            document_number: false,
+            airline_data: false,
+            security_data: false,
+        }
+    }
+
+    /// Whether the rules mask the item, by IATA number
+    pub fn covers(&self, item: u16) -> bool {
+        match item {
+            11  => self.name,
+            7   => self.pnr,
+            236 => self.frequent_flyer_number,
+            143 => self.document_number,
+            4   => self.airline_data,
+            30  => self.security_data,
+            _   => false,
+        }
+    }
+
+    pub fn all() -> RedactionRules {
+        RedactionRules {
+            name: true,
+            pnr: true,
+            frequent_flyer_number: true,
+            document_number: true,
+            airline_data: true,
+            security_data: true,
+        }
+    }
+}
+
+impl Default for RedactionRules {
+    fn default() -> RedactionRules {
+        RedactionRules::all()
+    }
+}
+
+/// Replaces every character but spaces with `X`, keeping the layout of the value
+pub fn mask(value: &str) -> String {
+    mask_with(value, 'X')
+}
+
+/// Same as `mask()` with `0`, for numeric items
+pub fn mask_numeric(value: &str) -> String {
+    mask_with(value, '0')
+}
+
+fn mask_with(value: &str, fill: char) -> String {
+    value.chars().map(|c| if c == ' ' { c } else { fill }).collect()
+}
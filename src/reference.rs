@@ -0,0 +1,167 @@
+//! Reference decoder and differential comparison harness
+//!
+//! The decoder is a straight port of the mandatory item walk from the BCBP
+//! implementation guide: fixed width slicing driven by `spec`, no combinators and no
+//! normalization beyond trimming. It is intentionally kept naive, so
+//! `compare()` can be run against arbitrary (fuzzed) inputs to find places
+//! where `BCBP::from` disagrees with the specification.
+
+use std::panic;
+use std::collections::HashMap;
+
+use bcbp::BCBP;
+use spec;
+
+/// Mandatory items of a single leg as laid out in the barcode
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefLeg {
+    pub pnr: String,
+    pub src_airport: String,
+    pub dst_airport: String,
+    pub airline: String,
+    pub flight_code: String,
+    pub flight_day: String,
+    pub compartment: char,
+    pub seat: String,
+    pub sequence: String,
+    pub pax_status: String,
+    pub conditional_size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefPass {
+    pub name: String,
+    pub ticket_flag: char,
+    pub legs: Vec<RefLeg>,
+}
+
+/// Single disagreement between the reference decoder and the parser
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub field: String,
+    pub reference: String,
+    pub parsed: String,
+}
+
+fn field<'a>(src: &'a str, pos: &mut usize, len: usize) -> Option<&'a str> {
+    let ret = src.get(*pos .. *pos + len)?;
+    *pos += len;
+    Some(ret)
+}
+
+/// Slices items of a section, keyed by IATA item number
+fn items<'a>(src: &'a str, pos: &mut usize, section: &[spec::Item]) -> Option<HashMap<u16, &'a str>> {
+    let mut ret = HashMap::new();
+
+    for item in section {
+        ret.insert(item.id, field(src, pos, item.len)?);
+    }
+
+    Some(ret)
+}
+
+/// Decodes mandatory items following the implementation guide, `None` if the input is malformed
+pub fn decode(src: &str) -> Option<RefPass> {
+    let mut pos = 0;
+
+    let header = items(src, &mut pos, spec::MANDATORY)?;
+
+    if header[&1] != "M" {
+        return None
+    }
+
+    let legs = header[&5].parse::<usize>().ok()?;
+
+    if legs < 1 {
+        return None
+    }
+
+    let mut ret = RefPass {
+        name: header[&11].trim().to_string(),
+        ticket_flag: header[&253].chars().next()?,
+        legs: Vec::new(),
+    };
+
+    for _ in 0 .. legs {
+        let leg = items(src, &mut pos, spec::LEG)?;
+
+        let conditional_size = usize::from_str_radix(leg[&6], 16).ok()?;
+        field(src, &mut pos, conditional_size)?;
+
+        ret.legs.push(RefLeg {
+            pnr: leg[&7].trim().into(),
+            src_airport: leg[&26].trim().into(),
+            dst_airport: leg[&38].trim().into(),
+            airline: leg[&42].trim().into(),
+            flight_code: leg[&43].trim().into(),
+            flight_day: leg[&46].trim().into(),
+            compartment: leg[&71].chars().next()?,
+            seat: leg[&104].trim().into(),
+            sequence: leg[&107].trim().into(),
+            pax_status: leg[&113].trim().into(),
+            conditional_size,
+        });
+    }
+
+    Some(ret)
+}
+
+fn number(src: &str) -> u32 {
+    src.parse().unwrap_or(0)
+}
+
+fn check(ret: &mut Vec<Divergence>, field: &str, reference: &str, parsed: &str) {
+    if reference != parsed {
+        ret.push(Divergence {
+            field: field.into(),
+            reference: reference.into(),
+            parsed: parsed.into(),
+        });
+    }
+}
+
+/// Runs the reference decoder and `BCBP::from` on the same input and lists where they disagree
+///
+/// A panic inside the parser is reported as a divergence of the `panic` field.
+pub fn compare(src: &str) -> Vec<Divergence> {
+    let mut ret = Vec::new();
+
+    let reference = decode(&src.to_uppercase());
+    let parsed = match panic::catch_unwind(|| BCBP::from(src)) {
+        Ok(parsed) => parsed,
+        Err(_)     => {
+            check(&mut ret, "panic", "", "parser panicked");
+            return ret
+        }
+    };
+
+    let (reference, parsed) = match (reference, parsed) {
+        (Some(r), Ok(p)) => (r, p),
+        (None, Err(_))   => return ret,
+        (r, p) => {
+            check(&mut ret, "result", &format!("{:?}", r.is_some()), &format!("{:?}", p.is_ok()));
+            return ret
+        }
+    };
+
+    check(&mut ret, "name", &reference.name, &parsed.name());
+    check(&mut ret, "ticket_flag", &reference.ticket_flag.to_string(), &parsed.electronic_ticket_indicator().as_char().to_string());
+    check(&mut ret, "legs", &reference.legs.len().to_string(), &parsed.segments().len().to_string());
+
+    for (i, (r, p)) in reference.legs.iter().zip(parsed.segments().iter()).enumerate() {
+        let name = |f: &str| format!("legs[{}].{}", i, f);
+
+        check(&mut ret, &name("pnr"), &r.pnr, p.pnr());
+        check(&mut ret, &name("src_airport"), &r.src_airport, p.src_airport());
+        check(&mut ret, &name("dst_airport"), &r.dst_airport, p.dst_airport());
+        check(&mut ret, &name("airline"), &r.airline, p.airline());
+        check(&mut ret, &name("flight_code"), &r.flight_code, p.flight_code());
+        check(&mut ret, &name("flight_day"), &number(&r.flight_day).to_string(), &p.flight_day().to_string());
+        check(&mut ret, &name("compartment"), &r.compartment.to_string(), &p.compartment().to_string());
+        check(&mut ret, &name("seat"), r.seat.trim_start_matches('0'), p.seat());
+        check(&mut ret, &name("sequence"), &number(&r.sequence).to_string(), &p.sequence().to_string());
+        check(&mut ret, &name("pax_status"), &r.pax_status, p.pax_status());
+    }
+
+    ret
+}
@@ -0,0 +1,195 @@
+//! Bulk export of parsed passes for analytics
+//!
+//! Each segment becomes a row, pass level items are repeated on every row of
+//! the pass. Column names match the accessor names of `bcbp::BCBP` and
+//! `bcbp::Segment`.
+//!
+//! `CorpusStats` aggregates a file of scans for barcode quality audits. It
+//! keeps counts only, never names, booking references or other passenger
+//! data, and backs the `bcbp-stats` binary.
+//!
+//! `validate_corpus()` grades every input of a corpus against the
+//! specification, with errors and warnings per input.
+
+use std::io;
+use std::fmt;
+use std::collections::BTreeMap;
+
+use bcbp::{BCBP, ParseOptions, Segment};
+use spec;
+
+mod validate;
+
+pub use self::validate::{Finding, InputReport, Severity, ValidationReport, VALIDATION_CSV_COLUMNS, validate_corpus};
+
+pub const CSV_COLUMNS: [&str; 18] = [
+    "name_last",
+    "name_first",
+    "electronic_ticket_indicator",
+    "conditional_version",
+    "pax_type",
+    "document_type",
+    "leg",
+    "pnr",
+    "src_airport",
+    "dst_airport",
+    "airline",
+    "flight_code",
+    "flight_day",
+    "compartment",
+    "seat",
+    "sequence",
+    "pax_status",
+    "airline_data",
+];
+
+fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+fn opt_char(c: Option<char>) -> String {
+    c.map(|c| c.to_string()).unwrap_or_default()
+}
+
+/// Writes passes as RFC 4180 CSV with a header row
+pub fn write_csv<'a, I, W>(passes: I, writer: &mut W) -> io::Result<()>
+    where I: IntoIterator<Item = &'a BCBP>,
+          W: io::Write
+{
+    writeln!(writer, "{}", CSV_COLUMNS.join(","))?;
+
+    for pass in passes {
+        for (leg, s) in pass.segments().iter().enumerate() {
+            let row = [
+                pass.name_last().to_string(),
+                pass.name_first().to_string(),
+                pass.electronic_ticket_indicator().as_char().to_string(),
+                opt_char(pass.conditional_version()),
+                opt_char(pass.passenger_description().map(|d| d.as_char())),
+                opt_char(pass.document_type()),
+                (leg + 1).to_string(),
+                s.pnr().to_string(),
+                s.src_airport().to_string(),
+                s.dst_airport().to_string(),
+                s.airline().to_string(),
+                s.flight_code().to_string(),
+                s.flight_day().to_string(),
+                s.compartment().to_string(),
+                s.seat().to_string(),
+                s.sequence().to_string(),
+                s.pax_status().to_string(),
+                s.airline_data().unwrap_or("").to_string(),
+            ];
+
+            let row: Vec<String> = row.iter().map(|v| escape(v)).collect();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Conditional items counted by `CorpusStats`, by IATA number
+const AUDITED_ITEMS: [u16; 10] = [15, 12, 14, 22, 16, 23, 20, 236, 4, 25];
+
+/// Aggregate counts over a corpus of scanned passes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CorpusStats {
+    pub total: usize,
+    pub parsed: usize,
+    /// Passes by conditional version, `None` for passes without conditional items
+    pub versions: BTreeMap<Option<char>, usize>,
+    /// Legs by operating carrier
+    pub airlines: BTreeMap<String, usize>,
+    /// Passes using a conditional item, by IATA number
+    pub items: BTreeMap<u16, usize>,
+    /// Rejected passes by parser error
+    pub errors: BTreeMap<String, usize>,
+}
+
+impl CorpusStats {
+    pub fn new() -> CorpusStats {
+        CorpusStats::default()
+    }
+
+    /// Parses a scan and counts it
+    pub fn add(&mut self, src: &str, options: ParseOptions) {
+        self.total += 1;
+
+        match BCBP::from_with(src, options) {
+            Ok(pass) => self.add_pass(&pass),
+            Err(e)   => *self.errors.entry(e.to_string()).or_insert(0) += 1,
+        }
+    }
+
+    fn add_pass(&mut self, pass: &BCBP) {
+        self.parsed += 1;
+        *self.versions.entry(pass.conditional_version()).or_insert(0) += 1;
+
+        for s in pass.segments() {
+            *self.airlines.entry(s.airline().trim().to_string()).or_insert(0) += 1;
+        }
+
+        let any_leg = |f: fn(&Segment) -> bool| pass.segments().iter().any(f);
+
+        for &item in AUDITED_ITEMS.iter() {
+            let used = match item {
+                15  => pass.passenger_description().is_some(),
+                12  => pass.checkin_source().is_some(),
+                14  => pass.boarding_pass_source().is_some(),
+                22  => pass.issue_date().is_some(),
+                16  => pass.document_type().is_some(),
+                23  => pass.conditional_unique().bag_tag_numbers().is_some(),
+                20  => any_leg(|s| s.frequent_flyer_airline().is_some()),
+                236 => any_leg(|s| s.frequent_flyer_number().is_some()),
+                4   => any_leg(|s| s.airline_data().is_some()),
+                25  => pass.security().is_some(),
+                _   => false,
+            };
+
+            if used {
+                *self.items.entry(item).or_insert(0) += 1;
+            }
+        }
+    }
+
+    pub fn failed(&self) -> usize {
+        self.total - self.parsed
+    }
+}
+
+impl fmt::Display for CorpusStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "passes: {}, parsed: {}, failed: {}", self.total, self.parsed, self.failed())?;
+
+        writeln!(f, "\nconditional versions:")?;
+        for (version, count) in &self.versions {
+            match *version {
+                Some(v) => writeln!(f, "  {:<8} {}", v, count)?,
+                None    => writeln!(f, "  {:<8} {}", "none", count)?,
+            }
+        }
+
+        writeln!(f, "\nlegs by airline:")?;
+        for (airline, count) in &self.airlines {
+            writeln!(f, "  {:<8} {}", airline, count)?;
+        }
+
+        writeln!(f, "\nconditional items:")?;
+        for (&item, count) in &self.items {
+            let name = spec::item(item).map(|i| i.name).unwrap_or("unknown item");
+            writeln!(f, "  {:<8} {} ({})", item, count, name)?;
+        }
+
+        writeln!(f, "\nerrors:")?;
+        for (error, count) in &self.errors {
+            writeln!(f, "  {:<8} {}", count, error)?;
+        }
+
+        Ok(())
+    }
+}
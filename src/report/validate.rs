@@ -0,0 +1,203 @@
+//! Compliance report of a corpus of barcodes
+//!
+//! Airlines audit the barcodes their departure control system prints
+//! against Resolution 792. `validate_corpus()` parses every input leniently
+//! so it can say what is wrong with a pass rather than only that it's
+//! wrong, and grades each finding:
+//!
+//! - `Error` for data the resolution doesn't allow, like a character outside
+//!   of the charset of an item, data after the last section or a security
+//!   section whose length doesn't match its size, a conditional section
+//!   shorter than its size, and data that can't be read at all.
+//! - `Warning` for data readers tolerate but shouldn't have to, like blank
+//!   padded or lower case items and conditional versions older than 6.
+//!
+//! The report serializes with the `serde` feature, `write_csv()` writes a
+//! row per finding.
+
+use std::io;
+
+use bcbp::{BCBP, ParseOptions, SecuritySection, Warning};
+use super::escape;
+
+/// Current version of the conditional items, older ones are reported
+const CURRENT_VERSION: char = '6';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Severity::Warning => "warning",
+            Severity::Error   => "error",
+        }
+    }
+}
+
+/// Single problem of an input
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Finding {
+    pub severity: Severity,
+    /// Stable machine readable code, like `charset` or `trailing_data`
+    pub code: String,
+    /// IATA number of the item concerned
+    pub item: Option<u16>,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, code: &'static str, item: Option<u16>, message: String) -> Finding {
+        Finding { severity, code: code.to_string(), item, message }
+    }
+}
+
+/// Findings of one input, along with what could be read of it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InputReport {
+    /// Position of the input, counted from 0
+    pub index: usize,
+    /// Conditional version, `None` for mandatory only passes or unreadable data
+    pub version: Option<char>,
+    /// Operating carrier of the first leg
+    pub airline: Option<String>,
+    pub findings: Vec<Finding>,
+}
+
+impl InputReport {
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|f| f.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|f| f.severity == Severity::Warning)
+    }
+
+    /// Most severe finding, `None` for a compliant pass
+    pub fn severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|f| f.severity).max()
+    }
+
+    /// Whether the pass has no errors, warnings are allowed
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+}
+
+/// Report of `validate_corpus()`, one entry per input in order
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValidationReport {
+    pub inputs: Vec<InputReport>,
+}
+
+pub const VALIDATION_CSV_COLUMNS: [&str; 7] = ["index", "version", "airline", "severity", "code", "item", "message"];
+
+impl ValidationReport {
+    /// Inputs with at least one finding of the severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.inputs.iter().filter(|i| i.findings.iter().any(|f| f.severity == severity)).count()
+    }
+
+    /// Inputs without errors
+    pub fn valid(&self) -> usize {
+        self.inputs.iter().filter(|i| i.is_valid()).count()
+    }
+
+    /// Writes RFC 4180 CSV with a header row and a row per finding, compliant inputs get none
+    pub fn write_csv<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{}", VALIDATION_CSV_COLUMNS.join(","))?;
+
+        for input in &self.inputs {
+            for finding in &input.findings {
+                let row = [
+                    input.index.to_string(),
+                    input.version.map(|c| c.to_string()).unwrap_or_default(),
+                    input.airline.clone().unwrap_or_default(),
+                    finding.severity.as_str().to_string(),
+                    finding.code.clone(),
+                    finding.item.map(|i| i.to_string()).unwrap_or_default(),
+                    finding.message.clone(),
+                ];
+
+                let row: Vec<String> = row.iter().map(|v| escape(v)).collect();
+                writeln!(writer, "{}", row.join(","))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates every input, see the module docs for the severities
+pub fn validate_corpus<I, S>(inputs: I) -> ValidationReport
+    where I: IntoIterator<Item = S>,
+          S: AsRef<str>
+{
+    let inputs = inputs.into_iter()
+        .enumerate()
+        .map(|(index, src)| validate_input(index, src.as_ref()))
+        .collect();
+
+    ValidationReport { inputs }
+}
+
+fn validate_input(index: usize, src: &str) -> InputReport {
+    let mut report = InputReport { index, version: None, airline: None, findings: Vec::new() };
+
+    let pass = match BCBP::from_with(src, ParseOptions::lenient()) {
+        Ok(pass) => pass,
+        Err(e)   => {
+            report.findings.push(Finding::new(Severity::Error, "unreadable", e.item, e.to_string()));
+            return report
+        },
+    };
+
+    report.version = pass.conditional_version();
+    report.airline = pass.segments().first().map(|s| s.airline().to_string());
+
+    for warning in pass.warnings() {
+        let finding = match *warning {
+            Warning::Charset { item, ref value } => {
+                let message = format!("item {} has characters outside of its charset: {:?}", item, value);
+                Finding::new(Severity::Error, "charset", Some(item), message)
+            },
+            Warning::TrailingData => {
+                let message = "data follows the last leg or the security section".to_string();
+                Finding::new(Severity::Error, "trailing_data", None, message)
+            },
+            Warning::Padding { item, ref value } => {
+                let message = format!("item {} is blank padded or in lower case: {:?}", item, value);
+                Finding::new(Severity::Warning, "padding", Some(item), message)
+            },
+            Warning::Undersized { item, size, len } => {
+                let message = format!("item {} announces {} characters, {} are left", item, size, len);
+                Finding::new(Severity::Error, "undersized", Some(item), message)
+            },
+        };
+
+        report.findings.push(finding);
+    }
+
+    if let Some(&SecuritySection::Legacy(_)) = pass.security() {
+        let message = "security data length doesn't match its size field".to_string();
+        report.findings.push(Finding::new(Severity::Error, "security_size", Some(29), message));
+    }
+
+    match pass.conditional_version() {
+        Some(v) if v.is_ascii_digit() && v < CURRENT_VERSION => {
+            let message = format!("conditional version {} is older than {}", v, CURRENT_VERSION);
+            report.findings.push(Finding::new(Severity::Warning, "outdated_version", Some(9), message));
+        },
+        _ => {},
+    }
+
+    report
+}
@@ -0,0 +1,53 @@
+//! Scans shipped from gates and checkpoints to backends
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::BCBP;
+//! use iata::redact::RedactionRules;
+//! use iata::scan::ScanEvent;
+//!
+//! let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+//! let event = ScanEvent::new(bcbp, 1483272000, "GATE-B12-1")
+//!     .with_location("JFK/B12")
+//!     .redacted(&RedactionRules::all());
+//!
+//! assert_eq!(event.bcbp.name(), "XXXX/XXXXX XXXXXX");
+//! assert_eq!(event.bcbp.segments()[0].seat(), "1Z");
+//! ```
+
+use bcbp::BCBP;
+use redact::RedactionRules;
+
+/// Pass read by a device, with the time and place of the scan
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScanEvent {
+    pub bcbp: BCBP,
+    /// Unix time in seconds
+    pub scanned_at: i64,
+    pub device_id: String,
+    /// Airport and checkpoint, like `JFK/B12`
+    pub location: Option<String>,
+}
+
+impl ScanEvent {
+    pub fn new(bcbp: BCBP, scanned_at: i64, device_id: &str) -> ScanEvent {
+        ScanEvent {
+            bcbp,
+            scanned_at,
+            device_id: device_id.into(),
+            location: None,
+        }
+    }
+
+    pub fn with_location(mut self, location: &str) -> ScanEvent {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Masks passenger data of the pass before the event leaves the device
+    pub fn redacted(mut self, rules: &RedactionRules) -> ScanEvent {
+        self.bcbp.redact(rules);
+        self
+    }
+}
@@ -0,0 +1,182 @@
+//! Seat maps of aircraft types for checking seats of issued passes
+//!
+//! A `SeatMap` is a block of rows by seat letters with some seats blocked,
+//! like crew rests or inoperative seats, and rows missing from the numbering,
+//! like row 13 on many carriers. `AircraftTypes` keeps the maps by IATA
+//! aircraft type code, as found in SSIM legs.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::seatmap::{AircraftTypes, Seat, SeatError, SeatMap};
+//!
+//! let mut types = AircraftTypes::new();
+//! types.register("320", SeatMap::new(1, 30, "ABCDEF").with_missing_row(13).with_blocked(Seat::new(1, 'A')));
+//!
+//! let map = types.get("320").unwrap();
+//! assert_eq!(map.check("012C"), Ok(Seat::new(12, 'C')));
+//! assert_eq!(map.check("13A"), Err(SeatError::NotOnAircraft(Seat::new(13, 'A'))));
+//! assert_eq!(map.check("1A"), Err(SeatError::Blocked(Seat::new(1, 'A'))));
+//! ```
+
+use std::fmt;
+use std::error;
+use std::collections::{BTreeSet, HashMap};
+
+/// Seat as row number and letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Seat {
+    pub row: u16,
+    pub letter: char,
+}
+
+impl Seat {
+    pub fn new(row: u16, letter: char) -> Seat {
+        Seat {
+            row,
+            letter: letter.to_ascii_uppercase(),
+        }
+    }
+
+    /// Seat of a pass like `012A` or `12A`, `None` for blank or malformed seats
+    pub fn parse(src: &str) -> Option<Seat> {
+        let src = src.trim();
+        let letter = src.chars().last().filter(char::is_ascii_alphabetic)?;
+        let digits = &src[.. src.len() - 1];
+
+        if digits.is_empty() || digits.len() > 3 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None
+        }
+
+        match digits.parse() {
+            Ok(0) | Err(_) => None,
+            Ok(row)        => Some(Seat::new(row, letter)),
+        }
+    }
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.row, self.letter)
+    }
+}
+
+/// Reasons a seat is refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SeatError {
+    /// Seat number is not a row and a letter
+    Malformed(String),
+    /// Row or letter doesn't exist on the aircraft
+    NotOnAircraft(Seat),
+    /// Seat exists but is not for sale
+    Blocked(Seat),
+}
+
+impl fmt::Display for SeatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SeatError::Malformed(ref s)  => write!(f, "malformed seat number {:?}", s),
+            SeatError::NotOnAircraft(s)  => write!(f, "seat {} doesn't exist on the aircraft", s),
+            SeatError::Blocked(s)        => write!(f, "seat {} is blocked", s),
+        }
+    }
+}
+
+impl error::Error for SeatError {}
+
+/// Rows by seat letters of a cabin layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatMap {
+    first_row: u16,
+    last_row: u16,
+    letters: String,
+    missing_rows: BTreeSet<u16>,
+    blocked: BTreeSet<Seat>,
+}
+
+impl SeatMap {
+    /// Layout of rows `first_row` to `last_row` with the given seat letters in every row
+    pub fn new(first_row: u16, last_row: u16, letters: &str) -> SeatMap {
+        SeatMap {
+            first_row,
+            last_row,
+            letters: letters.to_uppercase(),
+            missing_rows: BTreeSet::new(),
+            blocked: BTreeSet::new(),
+        }
+    }
+
+    /// Skips a row number, like 13
+    pub fn with_missing_row(mut self, row: u16) -> SeatMap {
+        self.missing_rows.insert(row);
+        self
+    }
+
+    pub fn with_blocked(mut self, seat: Seat) -> SeatMap {
+        self.blocked.insert(seat);
+        self
+    }
+
+    /// Whether the seat exists, blocked seats included
+    pub fn contains(&self, seat: Seat) -> bool {
+        seat.row >= self.first_row
+            && seat.row <= self.last_row
+            && !self.missing_rows.contains(&seat.row)
+            && self.letters.contains(seat.letter)
+    }
+
+    pub fn is_blocked(&self, seat: Seat) -> bool {
+        self.blocked.contains(&seat)
+    }
+
+    /// Number of seats which can be sold
+    pub fn capacity(&self) -> usize {
+        let rows = (self.first_row ..= self.last_row)
+            .filter(|row| !self.missing_rows.contains(row))
+            .count();
+
+        let blocked = self.blocked.iter().filter(|&&seat| self.contains(seat)).count();
+
+        rows * self.letters.chars().count() - blocked
+    }
+
+    /// Checks a seat number of a pass, like `012A`
+    pub fn check(&self, seat: &str) -> Result<Seat, SeatError> {
+        let seat = Seat::parse(seat).ok_or_else(|| SeatError::Malformed(seat.trim().into()))?;
+
+        if !self.contains(seat) {
+            return Err(SeatError::NotOnAircraft(seat))
+        }
+
+        if self.is_blocked(seat) {
+            return Err(SeatError::Blocked(seat))
+        }
+
+        Ok(seat)
+    }
+}
+
+/// Seat maps by IATA aircraft type code, like `320`
+#[derive(Debug, Clone, Default)]
+pub struct AircraftTypes {
+    maps: HashMap<String, SeatMap>,
+}
+
+impl AircraftTypes {
+    pub fn new() -> AircraftTypes {
+        AircraftTypes::default()
+    }
+
+    /// Adds or replaces the seat map of an aircraft type
+    pub fn register(&mut self, aircraft_type: &str, map: SeatMap) {
+        self.maps.insert(aircraft_type.trim().to_uppercase(), map);
+    }
+
+    pub fn is_registered(&self, aircraft_type: &str) -> bool {
+        self.get(aircraft_type).is_some()
+    }
+
+    pub fn get(&self, aircraft_type: &str) -> Option<&SeatMap> {
+        self.maps.get(&aircraft_type.trim().to_uppercase())
+    }
+}
@@ -0,0 +1,128 @@
+//! BCBP item layout generated from `spec/bcbp.toml`
+//!
+//! Items of each section are listed in the order they appear in the barcode.
+//!
+//! Offsets of mandatory items are `const fn`s, so raw data can be indexed
+//! without running the parser:
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::spec;
+//!
+//! const SEAT: usize = spec::mandatory_offset(0, 0, 104).unwrap();
+//!
+//! let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+//! assert_eq!(&src[SEAT .. SEAT + 4], "001Z");
+//! assert_eq!(spec::leg_offset(1, 0), 60);
+//! ```
+
+/// Characters allowed in an item, space padding is always allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Charset {
+    Alpha,
+    Numeric,
+    Alphanumeric,
+    Any,
+}
+
+impl Charset {
+    pub fn accepts(&self, c: char) -> bool {
+        c == ' ' || match *self {
+            Charset::Alpha        => c.is_ascii_uppercase(),
+            Charset::Numeric      => c.is_ascii_digit(),
+            Charset::Alphanumeric => c.is_ascii_uppercase() || c.is_ascii_digit(),
+            Charset::Any          => true,
+        }
+    }
+
+    pub fn validate(&self, value: &str) -> bool {
+        value.chars().all(|c| self.accepts(c))
+    }
+}
+
+/// Single item of the BCBP layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Item {
+    /// IATA item number
+    pub id: u16,
+    pub name: &'static str,
+    /// Width in characters, 0 for variable sized items
+    pub len: usize,
+    /// First conditional version defining the item
+    pub since: u8,
+    pub charset: Charset,
+}
+
+impl Item {
+    pub fn is_variable(&self) -> bool {
+        self.len == 0
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/spec.rs"));
+
+/// Looks an item up by its IATA number in all sections
+pub fn item(id: u16) -> Option<&'static Item> {
+    [MANDATORY, LEG, UNIQUE, REPEATED, SECURITY].iter()
+        .flat_map(|section| section.iter())
+        .find(|item| item.id == id)
+}
+
+/// Sum of fixed item widths of a section
+pub const fn fixed_len(section: &[Item]) -> usize {
+    let mut ret = 0;
+    let mut i = 0;
+
+    while i < section.len() {
+        ret += section[i].len;
+        i += 1;
+    }
+
+    ret
+}
+
+/// Width of the items preceding the first leg
+pub const MANDATORY_LEN: usize = fixed_len(MANDATORY);
+
+/// Width of the mandatory items of a leg, up to the size of its conditional items
+pub const LEG_LEN: usize = fixed_len(LEG);
+
+/// Offset of an item, by IATA number, from the start of its section
+pub const fn section_offset(section: &[Item], id: u16) -> Option<usize> {
+    let mut offset = 0;
+    let mut i = 0;
+
+    while i < section.len() {
+        if section[i].id == id {
+            return Some(offset)
+        }
+        offset += section[i].len;
+        i += 1;
+    }
+
+    None
+}
+
+/// Offset of leg `leg`, counted from 0
+///
+/// Conditional items of earlier legs come in between, `conditional` is
+/// their total size, the sum of item 6 of the earlier legs.
+pub const fn leg_offset(leg: usize, conditional: usize) -> usize {
+    MANDATORY_LEN + leg * LEG_LEN + conditional
+}
+
+/// Offset of a mandatory item, by IATA number, in leg `leg`, see `leg_offset()`
+///
+/// Items preceding the legs have the same offset for every leg. Barcode
+/// data is ASCII, so offsets are in bytes as well as characters.
+pub const fn mandatory_offset(leg: usize, conditional: usize, id: u16) -> Option<usize> {
+    if let Some(offset) = section_offset(MANDATORY, id) {
+        return Some(offset)
+    }
+
+    match section_offset(LEG, id) {
+        Some(offset) => Some(leg_offset(leg, conditional) + offset),
+        None         => None,
+    }
+}
@@ -0,0 +1,810 @@
+//! Standard Schedules Information data sets, IATA SSIM chapter 7
+//!
+//! A data set is a sequence of 200 character records: a header (type 1),
+//! then for every carrier a carrier record (type 2), its flight legs
+//! (type 3) with their segment data (type 4) and a trailer (type 5).
+//! Records keep their raw text, accessors cut the fields out on demand, so
+//! fields without an accessor survive a read and write unchanged.
+
+use std::fmt;
+
+use date;
+use datetime::{self, WireTime};
+use flight::CityPair;
+use record::{Field, Layout};
+use spec::Charset;
+
+pub mod reader;
+pub mod writer;
+pub mod schedule;
+
+pub use self::reader::{SsimReader, ReadError, ReadErrorKind};
+pub use self::writer::SsimWriter;
+pub use self::schedule::{Schedule, Message, MessageKind, Action, FlightId, Period, Change, ChangeLog, ApplyError};
+
+/// Width of every record
+pub const RECORD_LEN: usize = 200;
+
+const RECORD_TYPE: Field = Field::new("Record Type", 0, 1, Charset::Numeric);
+const SERIAL: Field = Field::new("Record Serial Number", 194, 6, Charset::Numeric);
+
+const HEADER_TITLE: Field = Field::new("Title of Contents", 1, 34, Charset::Any);
+const HEADER_SEASONS: Field = Field::new("Number of Seasons", 40, 1, Charset::Numeric);
+const HEADER_DATASET_SERIAL: Field = Field::new("Data Set Serial Number", 191, 3, Charset::Numeric);
+
+const CARRIER_TIME_MODE: Field = Field::new("Time Mode", 1, 1, Charset::Alpha);
+const CARRIER_AIRLINE: Field = Field::new("Airline Designator", 2, 3, Charset::Alphanumeric);
+const CARRIER_SEASON: Field = Field::new("Season", 10, 3, Charset::Alphanumeric);
+const CARRIER_VALID_FROM: Field = Field::new("Period of Schedule Validity From", 14, 7, Charset::Alphanumeric);
+const CARRIER_VALID_TO: Field = Field::new("Period of Schedule Validity To", 21, 7, Charset::Alphanumeric);
+const CARRIER_CREATION_DATE: Field = Field::new("Creation Date", 28, 7, Charset::Alphanumeric);
+const CARRIER_RELEASE_DATE: Field = Field::new("Release (Sell) Date", 65, 7, Charset::Alphanumeric);
+const CARRIER_STATUS: Field = Field::new("Schedule Status", 72, 1, Charset::Alpha);
+
+const LEG_SUFFIX: Field = Field::new("Operational Suffix", 1, 1, Charset::Alpha);
+const LEG_AIRLINE: Field = Field::new("Airline Designator", 2, 3, Charset::Alphanumeric);
+const LEG_FLIGHT: Field = Field::new("Flight Number", 5, 4, Charset::Numeric);
+const LEG_VARIATION: Field = Field::new("Itinerary Variation Identifier", 9, 2, Charset::Numeric);
+const LEG_SEQUENCE: Field = Field::new("Leg Sequence Number", 11, 2, Charset::Numeric);
+const LEG_SERVICE_TYPE: Field = Field::new("Service Type", 13, 1, Charset::Alpha);
+const LEG_FROM: Field = Field::new("Period of Operation From", 14, 7, Charset::Alphanumeric);
+const LEG_TO: Field = Field::new("Period of Operation To", 21, 7, Charset::Alphanumeric);
+const LEG_DAYS: Field = Field::new("Days of Operation", 28, 7, Charset::Numeric);
+const LEG_DEPARTURE: Field = Field::new("Departure Station", 36, 3, Charset::Alpha);
+const LEG_STD: Field = Field::new("Scheduled Time of Passenger Departure", 39, 4, Charset::Numeric);
+const LEG_DEPARTURE_VARIATION: Field = Field::new("UTC/Local Time Variation", 47, 5, Charset::Any);
+const LEG_ARRIVAL: Field = Field::new("Arrival Station", 54, 3, Charset::Alpha);
+const LEG_STA: Field = Field::new("Scheduled Time of Passenger Arrival", 61, 4, Charset::Numeric);
+const LEG_ARRIVAL_VARIATION: Field = Field::new("UTC/Local Time Variation", 65, 5, Charset::Any);
+const LEG_AIRCRAFT: Field = Field::new("Aircraft Type", 72, 3, Charset::Alphanumeric);
+const LEG_PRBD: Field = Field::new("Passenger Reservations Booking Designator", 75, 20, Charset::Any);
+
+const SEGMENT_DEI: Field = Field::new("Data Element Identifier", 30, 3, Charset::Numeric);
+const SEGMENT_BOARD_POINT: Field = Field::new("Segment Board Point", 33, 3, Charset::Alpha);
+const SEGMENT_OFF_POINT: Field = Field::new("Segment Off Point", 36, 3, Charset::Alpha);
+const SEGMENT_DATA: Field = Field::new("Data", 39, 155, Charset::Any);
+
+const TRAILER_AIRLINE: Field = Field::new("Airline Designator", 2, 3, Charset::Alphanumeric);
+const TRAILER_RELEASE_DATE: Field = Field::new("Release Date", 5, 7, Charset::Alphanumeric);
+const TRAILER_SERIAL_CHECK: Field = Field::new("Serial Number Check Reference", 187, 6, Charset::Numeric);
+const TRAILER_CONTINUATION: Field = Field::new("Continuation/End Code", 193, 1, Charset::Alpha);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RecordType {
+    Header,
+    Carrier,
+    Leg,
+    SegmentData,
+    Trailer,
+}
+
+impl RecordType {
+    pub fn as_char(&self) -> char {
+        match *self {
+            RecordType::Header      => '1',
+            RecordType::Carrier     => '2',
+            RecordType::Leg         => '3',
+            RecordType::SegmentData => '4',
+            RecordType::Trailer     => '5',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<RecordType> {
+        match c {
+            '1' => Some(RecordType::Header),
+            '2' => Some(RecordType::Carrier),
+            '3' => Some(RecordType::Leg),
+            '4' => Some(RecordType::SegmentData),
+            '5' => Some(RecordType::Trailer),
+            _   => None,
+        }
+    }
+
+    /// Fields checked when a record of the type is read
+    fn layout(&self) -> Layout {
+        let fields = match *self {
+            RecordType::Header      => vec![RECORD_TYPE, HEADER_SEASONS, HEADER_DATASET_SERIAL, SERIAL],
+            RecordType::Carrier     => vec![RECORD_TYPE, CARRIER_TIME_MODE, CARRIER_AIRLINE, CARRIER_STATUS, SERIAL],
+            RecordType::Leg         => vec![
+                RECORD_TYPE, LEG_SUFFIX, LEG_AIRLINE, LEG_FLIGHT, LEG_VARIATION, LEG_SEQUENCE,
+                LEG_SERVICE_TYPE, LEG_FROM, LEG_TO, LEG_DAYS, LEG_DEPARTURE, LEG_STD,
+                LEG_ARRIVAL, LEG_STA, LEG_AIRCRAFT, SERIAL,
+            ],
+            RecordType::SegmentData => vec![RECORD_TYPE, SEGMENT_DEI, SEGMENT_BOARD_POINT, SEGMENT_OFF_POINT, SERIAL],
+            RecordType::Trailer     => vec![RECORD_TYPE, TRAILER_AIRLINE, TRAILER_SERIAL_CHECK, TRAILER_CONTINUATION, SERIAL],
+        };
+
+        Layout::new(fields)
+    }
+}
+
+/// Field value with padding removed, empty if the data is too short
+fn get<'a>(data: &'a str, field: &Field) -> &'a str {
+    data.get(field.offset .. field.offset + field.len).unwrap_or("").trim()
+}
+
+/// Blank record of the type, serial numbers are filled in when it's written
+fn blank(kind: RecordType) -> String {
+    format!("{:<width$}", kind.as_char(), width = RECORD_LEN)
+}
+
+/// Writes a left aligned value, non ASCII characters are replaced by blanks
+fn set(data: &mut String, field: &Field, value: &str) {
+    let value: String = value.chars()
+        .map(|c| if c.is_ascii() { c } else { ' ' })
+        .chain(::std::iter::repeat(' '))
+        .take(field.len)
+        .collect();
+
+    data.replace_range(field.offset .. field.offset + field.len, &value);
+}
+
+/// Writes a zero padded number, keeping its lowest digits if it doesn't fit
+fn set_number(data: &mut String, field: &Field, value: u32) {
+    let value = format!("{:0width$}", value, width = field.len);
+    set(data, field, &value[value.len() - field.len ..]);
+}
+
+fn set_date(data: &mut String, field: &Field, value: Option<date::Date>) {
+    set(data, field, &format_date(value));
+}
+
+fn get_char(data: &str, field: &Field) -> Option<char> {
+    get(data, field).chars().next()
+}
+
+fn get_number(data: &str, field: &Field) -> u32 {
+    get(data, field).parse().unwrap_or(0)
+}
+
+/// Parses a `DDMMMYY` date, `None` for blank and open ended `00XXX00` dates
+pub fn parse_date(src: &str) -> Option<date::Date> {
+    datetime::parse_ddmmmyy(src)
+}
+
+/// Formats a `DDMMMYY` date, `00XXX00` for an open end
+pub fn format_date(value: Option<date::Date>) -> String {
+    match value {
+        Some(value) => datetime::format_ddmmmyy(&value),
+        None => "00XXX00".into(),
+    }
+}
+
+/// Whether schedule times are given in UTC or in local time of the station
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TimeMode {
+    Utc,
+    Local,
+}
+
+impl TimeMode {
+    pub fn as_char(&self) -> char {
+        match *self {
+            TimeMode::Utc   => 'U',
+            TimeMode::Local => 'L',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<TimeMode> {
+        match c.to_ascii_uppercase() {
+            'U' => Some(TimeMode::Utc),
+            'L' => Some(TimeMode::Local),
+            _   => None,
+        }
+    }
+}
+
+/// Parses a UTC/local time variation like `+0300` or `-0530` into minutes
+pub fn parse_utc_variation(src: &str) -> Option<i32> {
+    let sign = match src.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _   => return None,
+    };
+
+    let (hours, minutes) = hhmm(src.get(1 ..)?)?;
+    Some(sign * (hours * 60 + minutes))
+}
+
+fn hhmm(src: &str) -> Option<(i32, i32)> {
+    if src.len() != 4 || !src.bytes().all(|b| b.is_ascii_digit()) {
+        return None
+    }
+
+    let hours = src[0 .. 2].parse().ok()?;
+    let minutes = src[2 .. 4].parse().ok()?;
+
+    if hours > 23 || minutes > 59 {
+        return None
+    }
+
+    Some((hours, minutes))
+}
+
+/// Time of a schedule record tagged with its time mode
+///
+/// Conversions may move the time to the day before or after, which
+/// `day_offset()` tells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledTime {
+    minutes: i32,
+    mode: TimeMode,
+    utc_offset: i32,
+}
+
+impl ScheduledTime {
+    /// Parses an `HHMM` time of the station at the UTC variation, `2400` is midnight of the next day
+    pub fn parse(time: &str, mode: TimeMode, utc_variation: &str) -> Option<ScheduledTime> {
+        let time = WireTime::parse(time)?;
+
+        Some(ScheduledTime {
+            minutes: time.minutes() as i32 + i32::from(time.day_offset()) * 24 * 60,
+            mode,
+            utc_offset: parse_utc_variation(utc_variation)?,
+        })
+    }
+
+    pub fn mode(&self) -> TimeMode {
+        self.mode
+    }
+
+    /// Offset of the station from UTC in minutes
+    pub fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    pub fn to_mode(&self, mode: TimeMode) -> ScheduledTime {
+        let minutes = match (self.mode, mode) {
+            (TimeMode::Local, TimeMode::Utc) => self.minutes - self.utc_offset,
+            (TimeMode::Utc, TimeMode::Local) => self.minutes + self.utc_offset,
+            _                                => self.minutes,
+        };
+
+        ScheduledTime { minutes, mode, ..*self }
+    }
+
+    pub fn to_utc(&self) -> ScheduledTime {
+        self.to_mode(TimeMode::Utc)
+    }
+
+    pub fn to_local(&self) -> ScheduledTime {
+        self.to_mode(TimeMode::Local)
+    }
+
+    /// Days the time moved by conversions, like -1 for the evening before
+    pub fn day_offset(&self) -> i32 {
+        self.minutes.div_euclid(24 * 60)
+    }
+
+    /// Time of the day as `HHMM`
+    pub fn hhmm(&self) -> String {
+        let minutes = self.minutes.rem_euclid(24 * 60);
+        format!("{:02}{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+impl fmt::Display for ScheduledTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suffix = match self.mode {
+            TimeMode::Utc   => "Z",
+            TimeMode::Local => "L",
+        };
+
+        write!(f, "{}{}", self.hhmm(), suffix)?;
+
+        match self.day_offset() {
+            0 => Ok(()),
+            d => write!(f, "{:+}", d),
+        }
+    }
+}
+
+/// Days of the week a flight operates, 1 is Monday
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Days(u8);
+
+impl Days {
+    pub fn all() -> Days {
+        Days(0x7F)
+    }
+
+    /// Parses the `1234567` form, blanks mark days without operation
+    pub fn parse(src: &str) -> Days {
+        let mut days = 0;
+
+        for c in src.chars() {
+            if let Some(d @ 1 ..= 7) = c.to_digit(10) {
+                days |= 1 << (d - 1);
+            }
+        }
+
+        Days(days)
+    }
+
+    pub fn contains(&self, weekday: u32) -> bool {
+        (1 ..= 7).contains(&weekday) && self.0 & (1 << (weekday - 1)) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn intersection(&self, other: Days) -> Days {
+        Days(self.0 & other.0)
+    }
+
+    pub fn difference(&self, other: Days) -> Days {
+        Days(self.0 & !other.0)
+    }
+}
+
+impl fmt::Display for Days {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for d in 1 ..= 7 {
+            if self.contains(d) {
+                write!(f, "{}", d)?;
+            } else {
+                f.write_str(" ")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Record type 1, opens the data set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    data: String,
+}
+
+impl Header {
+    pub fn new(dataset_serial: u32) -> Header {
+        let mut data = blank(RecordType::Header);
+        set(&mut data, &HEADER_TITLE, "AIRLINE STANDARD SCHEDULE DATA SET");
+        set_number(&mut data, &HEADER_SEASONS, 1);
+        set_number(&mut data, &HEADER_DATASET_SERIAL, dataset_serial);
+        Header { data }
+    }
+
+    pub fn title(&self) -> &str {
+        get(&self.data, &HEADER_TITLE)
+    }
+
+    pub fn seasons(&self) -> u32 {
+        get_number(&self.data, &HEADER_SEASONS)
+    }
+
+    pub fn dataset_serial(&self) -> u32 {
+        get_number(&self.data, &HEADER_DATASET_SERIAL)
+    }
+}
+
+/// Record type 2, opens the schedule of a carrier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Carrier {
+    data: String,
+}
+
+impl Carrier {
+    /// Carrier record in UTC time mode
+    pub fn new(airline: &str) -> Carrier {
+        let mut data = blank(RecordType::Carrier);
+        set(&mut data, &CARRIER_TIME_MODE, "U");
+        set(&mut data, &CARRIER_AIRLINE, airline);
+        set_date(&mut data, &CARRIER_VALID_FROM, None);
+        set_date(&mut data, &CARRIER_VALID_TO, None);
+        Carrier { data }
+    }
+
+    pub fn set_time_mode(&mut self, mode: TimeMode) {
+        set(&mut self.data, &CARRIER_TIME_MODE, mode.as_char().encode_utf8(&mut [0; 4]));
+    }
+
+    pub fn set_season(&mut self, season: &str) {
+        set(&mut self.data, &CARRIER_SEASON, season);
+    }
+
+    pub fn set_validity(&mut self, from: Option<date::Date>, to: Option<date::Date>) {
+        set_date(&mut self.data, &CARRIER_VALID_FROM, from);
+        set_date(&mut self.data, &CARRIER_VALID_TO, to);
+    }
+
+    pub fn set_creation_date(&mut self, value: date::Date) {
+        set_date(&mut self.data, &CARRIER_CREATION_DATE, Some(value));
+    }
+
+    pub fn set_release_date(&mut self, value: date::Date) {
+        set_date(&mut self.data, &CARRIER_RELEASE_DATE, Some(value));
+    }
+
+    pub fn set_status(&mut self, status: char) {
+        set(&mut self.data, &CARRIER_STATUS, status.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Time mode of the legs of the carrier, UTC unless given
+    pub fn time_mode(&self) -> TimeMode {
+        get_char(&self.data, &CARRIER_TIME_MODE)
+            .and_then(TimeMode::from_char)
+            .unwrap_or(TimeMode::Utc)
+    }
+
+    pub fn airline(&self) -> &str {
+        get(&self.data, &CARRIER_AIRLINE)
+    }
+
+    /// IATA season, like `W17`
+    pub fn season(&self) -> &str {
+        get(&self.data, &CARRIER_SEASON)
+    }
+
+    pub fn valid_from(&self) -> Option<date::Date> {
+        parse_date(get(&self.data, &CARRIER_VALID_FROM))
+    }
+
+    pub fn valid_to(&self) -> Option<date::Date> {
+        parse_date(get(&self.data, &CARRIER_VALID_TO))
+    }
+
+    pub fn creation_date(&self) -> Option<date::Date> {
+        parse_date(get(&self.data, &CARRIER_CREATION_DATE))
+    }
+
+    pub fn release_date(&self) -> Option<date::Date> {
+        parse_date(get(&self.data, &CARRIER_RELEASE_DATE))
+    }
+
+    /// `P` for planning and `C` for confirmed schedules
+    pub fn status(&self) -> Option<char> {
+        get_char(&self.data, &CARRIER_STATUS)
+    }
+}
+
+/// Record type 3, a flight leg over a period of operation
+///
+/// The time mode of a leg comes from its carrier record, `SsimReader` tags
+/// the legs it reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leg {
+    data: String,
+    time_mode: TimeMode,
+}
+
+impl Leg {
+    /// Leg operating every day from an open start, with the other fields blank
+    pub fn new(airline: &str, flight_number: u32, itinerary_variation: u32, leg_sequence: u32) -> Leg {
+        let mut data = blank(RecordType::Leg);
+        set(&mut data, &LEG_AIRLINE, airline);
+        set_number(&mut data, &LEG_FLIGHT, flight_number);
+        set_number(&mut data, &LEG_VARIATION, itinerary_variation);
+        set_number(&mut data, &LEG_SEQUENCE, leg_sequence);
+        set_date(&mut data, &LEG_FROM, None);
+        set_date(&mut data, &LEG_TO, None);
+        set(&mut data, &LEG_DAYS, "1234567");
+        Leg { data, time_mode: TimeMode::Utc }
+    }
+
+    pub fn time_mode(&self) -> TimeMode {
+        self.time_mode
+    }
+
+    /// Tags the times of the leg with the mode, without changing them
+    pub fn set_time_mode(&mut self, mode: TimeMode) {
+        self.time_mode = mode;
+    }
+
+    /// Converts the departure and arrival times to the mode
+    ///
+    /// Times without a valid UTC variation are left as they are.
+    pub fn convert_times(&mut self, mode: TimeMode) {
+        if let Some(departure) = self.scheduled_departure() {
+            set(&mut self.data, &LEG_STD, &departure.to_mode(mode).hhmm());
+        }
+
+        if let Some(arrival) = self.scheduled_arrival() {
+            set(&mut self.data, &LEG_STA, &arrival.to_mode(mode).hhmm());
+        }
+
+        self.time_mode = mode;
+    }
+
+    pub fn scheduled_departure(&self) -> Option<ScheduledTime> {
+        ScheduledTime::parse(self.departure_time(), self.time_mode, self.departure_utc_variation())
+    }
+
+    pub fn scheduled_arrival(&self) -> Option<ScheduledTime> {
+        ScheduledTime::parse(self.arrival_time(), self.time_mode, self.arrival_utc_variation())
+    }
+
+    pub fn set_service_type(&mut self, service_type: char) {
+        set(&mut self.data, &LEG_SERVICE_TYPE, service_type.encode_utf8(&mut [0; 4]));
+    }
+
+    pub fn set_period(&mut self, from: Option<date::Date>, to: Option<date::Date>) {
+        set_date(&mut self.data, &LEG_FROM, from);
+        set_date(&mut self.data, &LEG_TO, to);
+    }
+
+    pub fn set_days(&mut self, days: Days) {
+        set(&mut self.data, &LEG_DAYS, &days.to_string());
+    }
+
+    /// Sets the station, the `HHMM` time and the UTC variation like `+0300`
+    pub fn set_departure(&mut self, station: &str, time: &str, utc_variation: &str) {
+        set(&mut self.data, &LEG_DEPARTURE, station);
+        set(&mut self.data, &LEG_STD, time);
+        set(&mut self.data, &LEG_DEPARTURE_VARIATION, utc_variation);
+    }
+
+    /// Sets the station, the `HHMM` time and the UTC variation like `+0300`
+    pub fn set_arrival(&mut self, station: &str, time: &str, utc_variation: &str) {
+        set(&mut self.data, &LEG_ARRIVAL, station);
+        set(&mut self.data, &LEG_STA, time);
+        set(&mut self.data, &LEG_ARRIVAL_VARIATION, utc_variation);
+    }
+
+    pub fn set_aircraft_type(&mut self, aircraft_type: &str) {
+        set(&mut self.data, &LEG_AIRCRAFT, aircraft_type);
+    }
+
+    pub fn set_prbd(&mut self, prbd: &str) {
+        set(&mut self.data, &LEG_PRBD, prbd);
+    }
+
+    pub fn suffix(&self) -> Option<char> {
+        get_char(&self.data, &LEG_SUFFIX)
+    }
+
+    pub fn airline(&self) -> &str {
+        get(&self.data, &LEG_AIRLINE)
+    }
+
+    pub fn flight_number(&self) -> u32 {
+        get_number(&self.data, &LEG_FLIGHT)
+    }
+
+    pub fn itinerary_variation(&self) -> u32 {
+        get_number(&self.data, &LEG_VARIATION)
+    }
+
+    pub fn leg_sequence(&self) -> u32 {
+        get_number(&self.data, &LEG_SEQUENCE)
+    }
+
+    pub fn service_type(&self) -> Option<char> {
+        get_char(&self.data, &LEG_SERVICE_TYPE)
+    }
+
+    pub fn period_from(&self) -> Option<date::Date> {
+        parse_date(get(&self.data, &LEG_FROM))
+    }
+
+    /// Last day of operation, `None` for open ended periods
+    pub fn period_to(&self) -> Option<date::Date> {
+        parse_date(get(&self.data, &LEG_TO))
+    }
+
+    pub fn days(&self) -> Days {
+        Days::parse(get(&self.data, &LEG_DAYS))
+    }
+
+    /// Whether the leg operates on the date, by its period and days of operation
+    pub fn operates_on(&self, date: &date::Date) -> bool {
+        self.period_from().is_none_or(|from| from <= *date)
+            && self.period_to().is_none_or(|to| *date <= to)
+            && self.days().contains(date::weekday(date))
+    }
+
+    pub fn departure(&self) -> &str {
+        get(&self.data, &LEG_DEPARTURE)
+    }
+
+    /// Passenger departure time as `HHMM`
+    pub fn departure_time(&self) -> &str {
+        get(&self.data, &LEG_STD)
+    }
+
+    /// Offset of the departure station from UTC, like `+0100`
+    pub fn departure_utc_variation(&self) -> &str {
+        get(&self.data, &LEG_DEPARTURE_VARIATION)
+    }
+
+    pub fn arrival(&self) -> &str {
+        get(&self.data, &LEG_ARRIVAL)
+    }
+
+    pub fn city_pair(&self) -> CityPair {
+        CityPair::new(self.departure(), self.arrival())
+    }
+
+    /// Passenger arrival time as `HHMM`
+    pub fn arrival_time(&self) -> &str {
+        get(&self.data, &LEG_STA)
+    }
+
+    pub fn arrival_utc_variation(&self) -> &str {
+        get(&self.data, &LEG_ARRIVAL_VARIATION)
+    }
+
+    pub fn aircraft_type(&self) -> &str {
+        get(&self.data, &LEG_AIRCRAFT)
+    }
+
+    /// Booking designators, like `FJCYBMHQKLV`
+    pub fn prbd(&self) -> &str {
+        get(&self.data, &LEG_PRBD)
+    }
+}
+
+/// Record type 4, a data element of a leg or of a segment over several legs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentData {
+    data: String,
+}
+
+impl SegmentData {
+    /// Data element of the leg, applying to its departure and arrival stations
+    pub fn new(leg: &Leg, dei: u32, value: &str) -> SegmentData {
+        let mut data = blank(RecordType::SegmentData);
+        data.replace_range(LEG_SUFFIX.offset .. LEG_SERVICE_TYPE.offset, &leg.data[LEG_SUFFIX.offset .. LEG_SERVICE_TYPE.offset]);
+        set_number(&mut data, &SEGMENT_DEI, dei);
+        set(&mut data, &SEGMENT_BOARD_POINT, leg.departure());
+        set(&mut data, &SEGMENT_OFF_POINT, leg.arrival());
+        set(&mut data, &SEGMENT_DATA, value);
+        SegmentData { data }
+    }
+
+    pub fn airline(&self) -> &str {
+        get(&self.data, &LEG_AIRLINE)
+    }
+
+    pub fn flight_number(&self) -> u32 {
+        get_number(&self.data, &LEG_FLIGHT)
+    }
+
+    pub fn leg_sequence(&self) -> u32 {
+        get_number(&self.data, &LEG_SEQUENCE)
+    }
+
+    /// Data element identifier, like 10 for code share details
+    pub fn dei(&self) -> u32 {
+        get_number(&self.data, &SEGMENT_DEI)
+    }
+
+    pub fn board_point(&self) -> &str {
+        get(&self.data, &SEGMENT_BOARD_POINT)
+    }
+
+    pub fn off_point(&self) -> &str {
+        get(&self.data, &SEGMENT_OFF_POINT)
+    }
+
+    pub fn city_pair(&self) -> CityPair {
+        CityPair::new(self.board_point(), self.off_point())
+    }
+
+    pub fn value(&self) -> &str {
+        get(&self.data, &SEGMENT_DATA)
+    }
+
+    /// Marketing flights of a code share element (DEI 10), like `AF 4567/KL 9876`
+    ///
+    /// Empty for other elements, designators that can't be read are skipped.
+    pub fn codeshare_flights(&self) -> Vec<FlightId> {
+        if self.dei() != 10 {
+            return Vec::new()
+        }
+
+        self.value().split('/').filter_map(FlightId::parse).collect()
+    }
+}
+
+/// Record type 5, closes the schedule of a carrier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+    data: String,
+}
+
+impl Trailer {
+    /// Trailer closing the data set, `serial_check` is the serial of the preceding record
+    pub fn new(airline: &str, release_date: Option<date::Date>, serial_check: u32) -> Trailer {
+        let mut data = blank(RecordType::Trailer);
+        set(&mut data, &TRAILER_AIRLINE, airline);
+        if release_date.is_some() {
+            set_date(&mut data, &TRAILER_RELEASE_DATE, release_date);
+        }
+        set_number(&mut data, &TRAILER_SERIAL_CHECK, serial_check);
+        set(&mut data, &TRAILER_CONTINUATION, "E");
+        Trailer { data }
+    }
+
+    pub fn airline(&self) -> &str {
+        get(&self.data, &TRAILER_AIRLINE)
+    }
+
+    pub fn release_date(&self) -> Option<date::Date> {
+        parse_date(get(&self.data, &TRAILER_RELEASE_DATE))
+    }
+
+    /// Serial number of the record preceding the trailer
+    pub fn serial_check(&self) -> u32 {
+        get_number(&self.data, &TRAILER_SERIAL_CHECK)
+    }
+
+    /// `C` when the schedule continues in another data set, `E` at its end
+    pub fn continuation(&self) -> Option<char> {
+        get_char(&self.data, &TRAILER_CONTINUATION)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Record {
+    Header(Header),
+    Carrier(Carrier),
+    Leg(Leg),
+    SegmentData(SegmentData),
+    Trailer(Trailer),
+}
+
+impl Record {
+    /// Checks and wraps a 200 character record
+    fn parse(data: String) -> Result<Record, ReadErrorKind> {
+        let kind = data.chars().next()
+            .ok_or(ReadErrorKind::RecordType(' '))
+            .and_then(|c| RecordType::from_char(c).ok_or(ReadErrorKind::RecordType(c)))?;
+
+        kind.layout().parse(&data).map_err(ReadErrorKind::Record)?;
+
+        let record = match kind {
+            RecordType::Header      => Record::Header(Header { data }),
+            RecordType::Carrier     => Record::Carrier(Carrier { data }),
+            RecordType::Leg         => Record::Leg(Leg { data, time_mode: TimeMode::Utc }),
+            RecordType::SegmentData => Record::SegmentData(SegmentData { data }),
+            RecordType::Trailer     => Record::Trailer(Trailer { data }),
+        };
+
+        Ok(record)
+    }
+
+    pub fn record_type(&self) -> RecordType {
+        match *self {
+            Record::Header(_)      => RecordType::Header,
+            Record::Carrier(_)     => RecordType::Carrier,
+            Record::Leg(_)         => RecordType::Leg,
+            Record::SegmentData(_) => RecordType::SegmentData,
+            Record::Trailer(_)     => RecordType::Trailer,
+        }
+    }
+
+    /// Raw text of the record
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Record::Header(ref r)      => &r.data,
+            Record::Carrier(ref r)     => &r.data,
+            Record::Leg(ref r)         => &r.data,
+            Record::SegmentData(ref r) => &r.data,
+            Record::Trailer(ref r)     => &r.data,
+        }
+    }
+
+    /// Position of the record in the data set, counted from 1
+    pub fn serial(&self) -> u32 {
+        get_number(self.as_str(), &SERIAL)
+    }
+
+    fn data_mut(&mut self) -> &mut String {
+        match *self {
+            Record::Header(ref mut r)      => &mut r.data,
+            Record::Carrier(ref mut r)     => &mut r.data,
+            Record::Leg(ref mut r)         => &mut r.data,
+            Record::SegmentData(ref mut r) => &mut r.data,
+            Record::Trailer(ref mut r)     => &mut r.data,
+        }
+    }
+
+    pub fn set_serial(&mut self, serial: u32) {
+        set_number(self.data_mut(), &SERIAL, serial);
+    }
+}
@@ -0,0 +1,182 @@
+//! Streaming reader of SSIM data sets
+//!
+//! Records are read one at a time into a reused buffer, so memory use does
+//! not grow with the file. A malformed record is reported and skipped, the
+//! next call carries on with the following record.
+
+use std::fmt;
+use std::error;
+use std::io::{self, BufRead, Read};
+
+use record::RecordError;
+use super::{Record, TimeMode, RECORD_LEN};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ReadErrorKind {
+    /// The underlying reader failed, reading stops
+    Io(io::Error),
+    /// Record contains non ASCII characters
+    Encoding,
+    /// Record type isn't one of 1 to 5
+    RecordType(char),
+    /// Field is missing or contains invalid characters
+    Record(RecordError),
+}
+
+/// Record that couldn't be read
+#[derive(Debug)]
+pub struct ReadError {
+    /// Position of the record in the file, counted from 1
+    pub record: u64,
+    pub kind: ReadErrorKind,
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "record {}: ", self.record)?;
+
+        match self.kind {
+            ReadErrorKind::Io(ref e)         => write!(f, "{}", e),
+            ReadErrorKind::Encoding          => f.write_str("record contains non ASCII characters"),
+            ReadErrorKind::RecordType(c)     => write!(f, "unknown record type {:?}", c),
+            ReadErrorKind::Record(ref e)     => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self.kind {
+            ReadErrorKind::Io(ref e)     => Some(e),
+            ReadErrorKind::Record(ref e) => Some(e),
+            _                            => None,
+        }
+    }
+}
+
+/// Iterator over the records of a data set
+///
+/// Records may be separated by line breaks or follow each other without
+/// separators. Zero filled padding records and blank lines are skipped.
+/// Legs are tagged with the time mode of the carrier record preceding them.
+///
+/// ```
+/// # extern crate iata;
+/// use iata::ssim::{SsimReader, Record};
+///
+/// let leg = format!("{:<194}{:06}", "3 SU 01000101J15JAN1731MAR171234567 SVO08000800+0300D LED09300930+0300  320", 2);
+/// let data = format!("{}\n{:<200}\n", leg, "3 broken");
+///
+/// let mut reader = SsimReader::from_reader(data.as_bytes());
+///
+/// match reader.next() {
+///     Some(Ok(Record::Leg(leg))) => assert_eq!((leg.airline(), leg.flight_number()), ("SU", 100)),
+///     _ => panic!(),
+/// }
+/// assert!(reader.next().unwrap().is_err());
+/// assert!(reader.next().is_none());
+/// ```
+pub struct SsimReader<R> {
+    inner: R,
+    buf: Vec<u8>,
+    count: u64,
+    done: bool,
+    time_mode: TimeMode,
+}
+
+impl<R: BufRead> SsimReader<R> {
+    pub fn from_reader(inner: R) -> SsimReader<R> {
+        SsimReader {
+            inner,
+            buf: Vec::with_capacity(RECORD_LEN + 2),
+            count: 0,
+            done: false,
+            time_mode: TimeMode::Utc,
+        }
+    }
+
+    /// Number of records read so far, including malformed ones
+    pub fn records_read(&self) -> u64 {
+        self.count
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads the next record into the buffer, `false` at the end of input
+    fn fill(&mut self) -> io::Result<bool> {
+        self.buf.clear();
+
+        (&mut self.inner).take(RECORD_LEN as u64).read_until(b'\n', &mut self.buf)?;
+
+        if self.buf.is_empty() {
+            return Ok(false)
+        }
+
+        if self.buf.last() == Some(&b'\n') {
+            self.buf.pop();
+        } else {
+            // Full record, take off the line break following it if any
+            let next = self.inner.fill_buf()?.first().cloned();
+            if next == Some(b'\r') {
+                self.inner.consume(1);
+            }
+            if self.inner.fill_buf()?.first() == Some(&b'\n') {
+                self.inner.consume(1);
+            }
+        }
+
+        if self.buf.last() == Some(&b'\r') {
+            self.buf.pop();
+        }
+
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> Iterator for SsimReader<R> {
+    type Item = Result<Record, ReadError>;
+
+    fn next(&mut self) -> Option<Result<Record, ReadError>> {
+        while !self.done {
+            match self.fill() {
+                Ok(true)  => {},
+                Ok(false) => {
+                    self.done = true;
+                    break
+                },
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(ReadError { record: self.count + 1, kind: ReadErrorKind::Io(e) }))
+                },
+            }
+
+            let padding = self.buf.iter().all(|&b| b == b' ') || self.buf.iter().all(|&b| b == b'0');
+            if padding {
+                continue
+            }
+
+            self.count += 1;
+
+            let mut record = if self.buf.is_ascii() {
+                String::from_utf8(self.buf.clone())
+                    .map_err(|_| ReadErrorKind::Encoding)
+                    .and_then(Record::parse)
+            } else {
+                Err(ReadErrorKind::Encoding)
+            };
+
+            match record {
+                Ok(Record::Carrier(ref carrier)) => self.time_mode = carrier.time_mode(),
+                Ok(Record::Leg(ref mut leg))     => leg.set_time_mode(self.time_mode),
+                _                                => {},
+            }
+
+            return Some(record.map_err(|kind| ReadError { record: self.count, kind }))
+        }
+
+        None
+    }
+}
@@ -0,0 +1,440 @@
+//! Applying ASM and SSM schedule changes to a schedule snapshot
+//!
+//! Ad hoc schedule messages (ASM) change a flight on a single date, standard
+//! schedule messages (SSM) over a period and days of the week. Legs partly
+//! covered by a change are split, so the parts outside the change keep their
+//! original data. Split parts keep the itinerary variation of the leg.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::date;
+//! use iata::ssim::*;
+//!
+//! let date = |d| date::from_ymd(2017, 11, d).unwrap();
+//!
+//! let mut leg = Leg::new("SU", 100, 1, 1);
+//! leg.set_period(Some(date(1)), Some(date(30)));
+//! leg.set_aircraft_type("320");
+//!
+//! let mut schedule = Schedule::from_legs(vec![leg]);
+//! let flight = FlightId::new("SU", 100);
+//!
+//! let changes = schedule.apply(&Message::asm(flight, date(15), Action::Equipment("321".into()))).unwrap();
+//!
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(schedule.legs().len(), 3);
+//! assert_eq!(schedule.legs()[2].aircraft_type(), "321");
+//! ```
+
+use std::fmt;
+use std::error;
+use std::cmp;
+
+use date;
+use super::{Days, Leg, Record, ScheduledTime, TimeMode};
+
+/// Flight designator, like `SU100` or `SU100A`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlightId {
+    pub airline: String,
+    pub number: u32,
+    pub suffix: Option<char>,
+}
+
+impl FlightId {
+    pub fn new(airline: &str, number: u32) -> FlightId {
+        FlightId {
+            airline: airline.trim().to_uppercase(),
+            number,
+            suffix: None,
+        }
+    }
+
+    /// Parses a designator like `AF4567`, `AF 4567` or `SU100A`
+    ///
+    /// The airline takes 2 characters, or 3 when the third is a letter.
+    pub fn parse(src: &str) -> Option<FlightId> {
+        let src = src.trim();
+
+        if !src.is_ascii() || src.len() < 3 {
+            return None
+        }
+
+        let airline_len = if src.as_bytes()[2].is_ascii_alphabetic() { 3 } else { 2 };
+        let (airline, rest) = src.split_at(airline_len);
+        let rest = rest.trim_start();
+
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+
+        if !(1 ..= 4).contains(&digits) || !airline.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None
+        }
+
+        let id = FlightId::new(airline, rest[.. digits].parse().ok()?);
+
+        match rest[digits ..].chars().collect::<Vec<_>>()[..] {
+            []  => Some(id),
+            [c] if c.is_ascii_alphabetic() => Some(id.with_suffix(c.to_ascii_uppercase())),
+            _   => None,
+        }
+    }
+
+    pub fn with_suffix(mut self, suffix: char) -> FlightId {
+        self.suffix = Some(suffix);
+        self
+    }
+
+    fn matches(&self, leg: &Leg) -> bool {
+        leg.airline() == self.airline && leg.flight_number() == self.number && leg.suffix() == self.suffix
+    }
+}
+
+impl fmt::Display for FlightId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.airline, self.number)?;
+
+        if let Some(suffix) = self.suffix {
+            write!(f, "{}", suffix)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Dates a change applies to, `None` bounds are open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub from: Option<date::Date>,
+    pub to: Option<date::Date>,
+    pub days: Days,
+}
+
+impl Period {
+    pub fn new(from: Option<date::Date>, to: Option<date::Date>, days: Days) -> Period {
+        Period { from, to, days }
+    }
+
+    /// Single date, on whatever day of the week it is
+    pub fn date(date: date::Date) -> Period {
+        Period::new(Some(date), Some(date), Days::all())
+    }
+
+    fn of(leg: &Leg) -> Period {
+        Period::new(leg.period_from(), leg.period_to(), leg.days())
+    }
+
+    /// Whether the flight operates at least once in the period
+    fn operates(&self) -> bool {
+        if self.days.is_empty() {
+            return false
+        }
+
+        match (self.from, self.to) {
+            (Some(from), Some(to)) => {
+                (0 .. 7)
+                    .map(|i| date::add_days(&from, i))
+                    .take_while(|d| *d <= to)
+                    .any(|d| self.days.contains(date::weekday(&d)))
+            },
+            _ => true,
+        }
+    }
+
+    /// Splits the period into the part overlapping `other` and the parts outside of it
+    fn split(&self, other: &Period) -> Option<(Period, Vec<Period>)> {
+        let from = match (self.from, other.from) {
+            (Some(a), Some(b)) => Some(cmp::max(a, b)),
+            (a, b)             => a.or(b),
+        };
+
+        let to = match (self.to, other.to) {
+            (Some(a), Some(b)) => Some(cmp::min(a, b)),
+            (a, b)             => a.or(b),
+        };
+
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return None
+            }
+        }
+
+        let overlap = Period::new(from, to, self.days.intersection(other.days));
+
+        if !overlap.operates() {
+            return None
+        }
+
+        let mut rest = vec![Period::new(from, to, self.days.difference(other.days))];
+
+        if from != self.from {
+            rest.push(Period::new(self.from, from.map(|d| date::add_days(&d, -1)), self.days));
+        }
+
+        if to != self.to {
+            rest.push(Period::new(to.map(|d| date::add_days(&d, 1)), self.to, self.days));
+        }
+
+        rest.retain(Period::operates);
+
+        Some((overlap, rest))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MessageKind {
+    /// Ad hoc schedule message, changes a single date
+    Asm,
+    /// Standard schedule message, changes a period
+    Ssm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Action {
+    /// Adds a flight, the legs get the period of the message
+    New(Vec<Leg>),
+    /// Cancels the flight
+    Cancel,
+    /// Changes the aircraft type
+    Equipment(String),
+    /// Changes the `HHMM` times of the leg departing from the station, given in the time mode of the message
+    Time {
+        station: String,
+        departure: Option<String>,
+        arrival: Option<String>,
+    },
+}
+
+/// Parsed ASM or SSM for a single flight
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub kind: MessageKind,
+    /// UTC unless the message carries the `LT` indicator
+    pub time_mode: TimeMode,
+    pub flight: FlightId,
+    pub period: Period,
+    pub action: Action,
+}
+
+impl Message {
+    pub fn asm(flight: FlightId, date: date::Date, action: Action) -> Message {
+        Message {
+            kind: MessageKind::Asm,
+            time_mode: TimeMode::Utc,
+            flight,
+            period: Period::date(date),
+            action,
+        }
+    }
+
+    pub fn ssm(flight: FlightId, period: Period, action: Action) -> Message {
+        Message {
+            kind: MessageKind::Ssm,
+            time_mode: TimeMode::Utc,
+            flight,
+            period,
+            action,
+        }
+    }
+
+    pub fn with_time_mode(mut self, mode: TimeMode) -> Message {
+        self.time_mode = mode;
+        self
+    }
+}
+
+/// Converts an `HHMM` time of the message to the time mode of the leg, `None` if it's malformed
+fn convert(time: &str, from: TimeMode, to: TimeMode, utc_variation: &str) -> Option<String> {
+    ScheduledTime::parse(time, from, utc_variation).map(|t| t.to_mode(to).hhmm())
+}
+
+/// Change made to the schedule, legs are restricted to the period they changed in
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Change {
+    Added(Leg),
+    Removed(Leg),
+    Updated { before: Leg, after: Leg },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ApplyError {
+    /// No leg of the flight operates in the period of the message
+    UnknownFlight(FlightId),
+    /// New flight already operates in the period of the message
+    Duplicate(FlightId),
+    /// New flight without legs, or with legs of another flight
+    InvalidLegs(FlightId),
+    /// Time isn't `HHMM`, or the leg lacks the UTC variation to convert it
+    InvalidTime(String),
+}
+
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ApplyError::UnknownFlight(ref flight) => write!(f, "flight {} doesn't operate in the period", flight),
+            ApplyError::Duplicate(ref flight)     => write!(f, "flight {} already operates in the period", flight),
+            ApplyError::InvalidLegs(ref flight)   => write!(f, "new flight {} must have legs of its own", flight),
+            ApplyError::InvalidTime(ref time)     => write!(f, "time {:?} can't be applied to the leg", time),
+        }
+    }
+}
+
+impl error::Error for ApplyError {}
+
+/// Changes made by a stream of messages and the messages that couldn't be applied
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeLog {
+    pub changes: Vec<Change>,
+    /// Position of the message in the stream and why it was rejected
+    pub rejected: Vec<(usize, ApplyError)>,
+}
+
+/// Flight legs of a schedule
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schedule {
+    legs: Vec<Leg>,
+}
+
+fn restrict(leg: &Leg, period: &Period) -> Leg {
+    let mut leg = leg.clone();
+    leg.set_period(period.from, period.to);
+    leg.set_days(period.days);
+    leg
+}
+
+impl Schedule {
+    pub fn from_legs(legs: Vec<Leg>) -> Schedule {
+        Schedule { legs }
+    }
+
+    /// Takes the legs out of SSIM records, like the output of `SsimReader`
+    pub fn from_records<I: IntoIterator<Item = Record>>(records: I) -> Schedule {
+        let legs = records.into_iter()
+            .filter_map(|record| match record {
+                Record::Leg(leg) => Some(leg),
+                _                => None,
+            })
+            .collect();
+
+        Schedule::from_legs(legs)
+    }
+
+    pub fn legs(&self) -> &[Leg] {
+        &self.legs
+    }
+
+    pub fn into_legs(self) -> Vec<Leg> {
+        self.legs
+    }
+
+    /// Applies the message, the schedule is left unchanged when it fails
+    pub fn apply(&mut self, message: &Message) -> Result<Vec<Change>, ApplyError> {
+        let flight = &message.flight;
+
+        if let Action::New(ref legs) = message.action {
+            if legs.is_empty() || !legs.iter().all(|leg| flight.matches(leg)) {
+                return Err(ApplyError::InvalidLegs(flight.clone()))
+            }
+
+            let exists = self.legs.iter()
+                .any(|leg| flight.matches(leg) && Period::of(leg).split(&message.period).is_some());
+
+            if exists {
+                return Err(ApplyError::Duplicate(flight.clone()))
+            }
+
+            let added: Vec<_> = legs.iter().map(|leg| restrict(leg, &message.period)).collect();
+            self.legs.extend(added.iter().cloned());
+
+            return Ok(added.into_iter().map(Change::Added).collect())
+        }
+
+        let mut legs = Vec::with_capacity(self.legs.len());
+        let mut changes = Vec::new();
+        let mut matched = false;
+
+        for leg in &self.legs {
+            let split = if flight.matches(leg) { Period::of(leg).split(&message.period) } else { None };
+
+            let (overlap, rest) = match split {
+                Some(split) => split,
+                None        => {
+                    legs.push(leg.clone());
+                    continue
+                },
+            };
+
+            matched = true;
+
+            let before = restrict(leg, &overlap);
+            let mut after = before.clone();
+
+            match message.action {
+                Action::Cancel => {},
+                Action::Equipment(ref aircraft_type) => after.set_aircraft_type(aircraft_type),
+                Action::Time { ref station, ref departure, ref arrival } => {
+                    if leg.departure() == station.as_str() {
+                        let (from, to) = (message.time_mode, leg.time_mode());
+
+                        let departure = departure.as_ref()
+                            .map(|t| convert(t, from, to, leg.departure_utc_variation()).ok_or(ApplyError::InvalidTime(t.clone())))
+                            .transpose()?;
+                        let arrival = arrival.as_ref()
+                            .map(|t| convert(t, from, to, leg.arrival_utc_variation()).ok_or(ApplyError::InvalidTime(t.clone())))
+                            .transpose()?;
+
+                        let departure = departure.as_ref().map_or(leg.departure_time(), |s| s.as_str());
+                        let arrival = arrival.as_ref().map_or(leg.arrival_time(), |s| s.as_str());
+
+                        after.set_departure(leg.departure(), departure, leg.departure_utc_variation());
+                        after.set_arrival(leg.arrival(), arrival, leg.arrival_utc_variation());
+                    }
+                },
+                Action::New(_) => unreachable!(),
+            }
+
+            let cancel = message.action == Action::Cancel;
+
+            // Leave legs the change doesn't touch in one piece
+            if !cancel && before == after {
+                legs.push(leg.clone());
+                continue
+            }
+
+            legs.extend(rest.iter().map(|period| restrict(leg, period)));
+
+            if cancel {
+                changes.push(Change::Removed(before));
+            } else {
+                legs.push(after.clone());
+                changes.push(Change::Updated { before, after });
+            }
+        }
+
+        if !matched {
+            return Err(ApplyError::UnknownFlight(flight.clone()))
+        }
+
+        self.legs = legs;
+        Ok(changes)
+    }
+
+    /// Applies messages in order, carrying on past the ones that fail
+    pub fn apply_all<'a, I>(&mut self, messages: I) -> ChangeLog
+        where I: IntoIterator<Item = &'a Message>
+    {
+        let mut log = ChangeLog::default();
+
+        for (i, message) in messages.into_iter().enumerate() {
+            match self.apply(message) {
+                Ok(changes) => log.changes.extend(changes),
+                Err(e)      => log.rejected.push((i, e)),
+            }
+        }
+
+        log
+    }
+}
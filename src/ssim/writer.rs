@@ -0,0 +1,163 @@
+//! Writer of SSIM data sets
+//!
+//! The writer takes care of the framing: the header record, record serial
+//! numbers, trailers with their serial check reference and the zero records
+//! filling physical blocks of five records.
+
+use std::io::{self, Write};
+
+use date;
+use super::{Carrier, Header, Leg, Record, SegmentData, Trailer, TimeMode, RECORD_LEN};
+
+/// Records in a physical block
+const BLOCK: u64 = 5;
+
+/// Width of the serial number ending every record
+const SERIAL_LEN: usize = 6;
+
+/// Writes records one at a time, numbering them
+///
+/// ```
+/// # extern crate iata;
+/// use iata::ssim::{Carrier, Leg, SsimReader, SsimWriter};
+///
+/// let mut writer = SsimWriter::new(Vec::new());
+///
+/// writer.begin_carrier(&Carrier::new("SU")).unwrap();
+/// let mut leg = Leg::new("SU", 100, 1, 1);
+/// leg.set_departure("SVO", "0800", "+0300");
+/// leg.set_arrival("LED", "0930", "+0300");
+/// writer.write_leg(&leg).unwrap();
+///
+/// let data = writer.finish().unwrap();
+/// let records = SsimReader::from_reader(&data[..]).collect::<Result<Vec<_>, _>>().unwrap();
+///
+/// assert_eq!(records.len(), 4);
+/// assert_eq!(records[2].serial(), 3);
+/// ```
+pub struct SsimWriter<W: Write> {
+    inner: W,
+    separator: &'static str,
+    dataset_serial: u32,
+    serial: u32,
+    written: u64,
+    carrier: Option<(String, Option<date::Date>, TimeMode)>,
+}
+
+impl<W: Write> SsimWriter<W> {
+    /// Writer separating records by line breaks
+    pub fn new(inner: W) -> SsimWriter<W> {
+        SsimWriter {
+            inner,
+            separator: "\n",
+            dataset_serial: 1,
+            serial: 0,
+            written: 0,
+            carrier: None,
+        }
+    }
+
+    /// Writes records back to back, as on tape and in some legacy exchanges
+    pub fn without_line_breaks(mut self) -> SsimWriter<W> {
+        self.separator = "";
+        self
+    }
+
+    /// Serial number of the data set given in the header record
+    pub fn with_dataset_serial(mut self, serial: u32) -> SsimWriter<W> {
+        self.dataset_serial = serial;
+        self
+    }
+
+    /// Serial number of the last record written
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    fn emit(&mut self, data: &str) -> io::Result<()> {
+        self.serial = (self.serial + 1) % 1_000_000;
+        self.written += 1;
+
+        let body = &data[.. RECORD_LEN - SERIAL_LEN];
+        write!(self.inner, "{}{:06}{}", body, self.serial, self.separator)
+    }
+
+    /// Fills the current block with zero records
+    fn pad(&mut self) -> io::Result<()> {
+        while !self.written.is_multiple_of(BLOCK) {
+            write!(self.inner, "{}{}", "0".repeat(RECORD_LEN), self.separator)?;
+            self.written += 1;
+        }
+        Ok(())
+    }
+
+    /// Opens the schedule of a carrier, closing the previous one
+    pub fn begin_carrier(&mut self, carrier: &Carrier) -> io::Result<()> {
+        if self.written == 0 {
+            let header = Header::new(self.dataset_serial);
+            self.emit(&header.data)?;
+            self.pad()?;
+        }
+
+        self.end_carrier()?;
+        self.emit(&carrier.data)?;
+        self.carrier = Some((carrier.airline().into(), carrier.release_date(), carrier.time_mode()));
+
+        Ok(())
+    }
+
+    /// Closes the schedule of the current carrier with a trailer, if one is open
+    pub fn end_carrier(&mut self) -> io::Result<()> {
+        if let Some((airline, release_date, _)) = self.carrier.take() {
+            let trailer = Trailer::new(&airline, release_date, self.serial);
+            self.emit(&trailer.data)?;
+            self.pad()?;
+        }
+
+        Ok(())
+    }
+
+    fn check_carrier(&self) -> io::Result<TimeMode> {
+        match self.carrier {
+            Some((_, _, mode)) => Ok(mode),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "no carrier record precedes the leg")),
+        }
+    }
+
+    /// Writes the leg, converting its times to the time mode of the carrier
+    pub fn write_leg(&mut self, leg: &Leg) -> io::Result<()> {
+        let mode = self.check_carrier()?;
+
+        if leg.time_mode() == mode {
+            return self.emit(&leg.data)
+        }
+
+        let mut leg = leg.clone();
+        leg.convert_times(mode);
+        self.emit(&leg.data)
+    }
+
+    pub fn write_segment_data(&mut self, segment: &SegmentData) -> io::Result<()> {
+        self.check_carrier()?;
+        self.emit(&segment.data)
+    }
+
+    /// Writes a record as read from another data set
+    ///
+    /// Header and trailer records are skipped, the writer generates its own.
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        match *record {
+            Record::Carrier(ref carrier)     => self.begin_carrier(carrier),
+            Record::Leg(ref leg)             => self.write_leg(leg),
+            Record::SegmentData(ref segment) => self.write_segment_data(segment),
+            Record::Header(_) | Record::Trailer(_) => Ok(()),
+        }
+    }
+
+    /// Closes the open carrier and returns the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.end_carrier()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
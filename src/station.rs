@@ -0,0 +1,182 @@
+//! Airport metadata with time zones and coordinates
+//!
+//! ```
+//! # extern crate iata;
+//! # extern crate chrono;
+//! use chrono::{NaiveDate, NaiveTime, Timelike};
+//! use iata::station::StationTable;
+//!
+//! let stations = StationTable::builtin();
+//! let jfk = stations.get("JFK").unwrap();
+//!
+//! let date = NaiveDate::from_ymd_opt(2017, 7, 1).unwrap();
+//! let utc = jfk.local_to_utc(date, NaiveTime::from_hms_opt(18, 30, 0).unwrap()).unwrap();
+//! assert_eq!(utc.hour(), 22);
+//! ```
+
+use std::fmt;
+use std::error;
+use std::collections::HashMap;
+
+use chrono::prelude::*;
+use chrono::{Duration, LocalResult};
+use chrono_tz::Tz;
+
+use bcbp::Segment;
+
+/// Mean Earth radius used for great-circle distances
+const EARTH_RADIUS_KM: f64 = 6371.0;
+/// Taxi, climb and descent allowance of the block time estimate
+const BLOCK_OVERHEAD_MINUTES: f64 = 30.0;
+/// Average ground speed of the block time estimate
+const CRUISE_SPEED_KMH: f64 = 800.0;
+
+const BUILTIN: [(&str, &str, &str, &str, f64, f64); 22] = [
+    ("AMS", "NL", "Amsterdam Schiphol", "Europe/Amsterdam", 52.3105, 4.7683),
+    ("ATL", "US", "Atlanta Hartsfield-Jackson", "America/New_York", 33.6407, -84.4277),
+    ("CDG", "FR", "Paris Charles de Gaulle", "Europe/Paris", 49.0097, 2.5479),
+    ("CPH", "DK", "Copenhagen Kastrup", "Europe/Copenhagen", 55.6180, 12.6508),
+    ("DOH", "QA", "Doha Hamad", "Asia/Qatar", 25.2731, 51.6081),
+    ("DXB", "AE", "Dubai", "Asia/Dubai", 25.2532, 55.3657),
+    ("FCO", "IT", "Rome Fiumicino", "Europe/Rome", 41.8003, 12.2389),
+    ("FRA", "DE", "Frankfurt", "Europe/Berlin", 50.0379, 8.5622),
+    ("HEL", "FI", "Helsinki Vantaa", "Europe/Helsinki", 60.3172, 24.9633),
+    ("HND", "JP", "Tokyo Haneda", "Asia/Tokyo", 35.5494, 139.7798),
+    ("IST", "TR", "Istanbul", "Europe/Istanbul", 41.2753, 28.7519),
+    ("JFK", "US", "New York John F. Kennedy", "America/New_York", 40.6413, -73.7781),
+    ("LAX", "US", "Los Angeles", "America/Los_Angeles", 33.9416, -118.4085),
+    ("LED", "RU", "Saint Petersburg Pulkovo", "Europe/Moscow", 59.8003, 30.2625),
+    ("LHR", "GB", "London Heathrow", "Europe/London", 51.4700, -0.4543),
+    ("MAD", "ES", "Madrid Barajas", "Europe/Madrid", 40.4983, -3.5676),
+    ("MUC", "DE", "Munich", "Europe/Berlin", 48.3537, 11.7750),
+    ("ORD", "US", "Chicago O'Hare", "America/Chicago", 41.9742, -87.9073),
+    ("SIN", "SG", "Singapore Changi", "Asia/Singapore", 1.3644, 103.9915),
+    ("SVO", "RU", "Moscow Sheremetyevo", "Europe/Moscow", 55.9726, 37.4146),
+    ("SYD", "AU", "Sydney Kingsford Smith", "Australia/Sydney", -33.9399, 151.1753),
+    ("ZRH", "CH", "Zurich", "Europe/Zurich", 47.4582, 8.5555),
+];
+
+/// Local time skipped by a daylight saving transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonexistentTime {
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+}
+
+impl fmt::Display for NonexistentTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} does not exist in the local time zone", self.date, self.time)
+    }
+}
+
+impl error::Error for NonexistentTime {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Station {
+    pub code: String,
+    /// ISO 3166 alpha-2 code of the country
+    pub country: String,
+    pub name: String,
+    pub timezone: Tz,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Station {
+    /// Converts local wall clock time of the station to UTC
+    ///
+    /// Times repeated when clocks go back resolve to the earlier instant,
+    /// times skipped when clocks go forward are an error.
+    pub fn local_to_utc(&self, date: NaiveDate, time: NaiveTime) -> Result<DateTime<Utc>, NonexistentTime> {
+        match self.timezone.from_local_datetime(&date.and_time(time)) {
+            LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => Ok(t.with_timezone(&Utc)),
+            LocalResult::None => Err(NonexistentTime { date, time }),
+        }
+    }
+
+    pub fn utc_to_local(&self, utc: DateTime<Utc>) -> NaiveDateTime {
+        utc.with_timezone(&self.timezone).naive_local()
+    }
+}
+
+/// Great-circle distance between two stations
+pub fn distance_km(a: &Station, b: &Station) -> f64 {
+    let (lat_a, lat_b) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+/// Rough gate to gate time of a flight, a fixed allowance plus the distance at cruise speed
+pub fn estimate_block_time(distance_km: f64) -> Duration {
+    let minutes = BLOCK_OVERHEAD_MINUTES + distance_km / CRUISE_SPEED_KMH * 60.0;
+    Duration::minutes(minutes.round() as i64)
+}
+
+/// Stations by IATA airport code
+#[derive(Debug, Clone, Default)]
+pub struct StationTable {
+    stations: HashMap<String, Station>,
+}
+
+impl StationTable {
+    pub fn new() -> StationTable {
+        StationTable::default()
+    }
+
+    /// Table with a few major airports, extend it with `insert()`
+    pub fn builtin() -> StationTable {
+        let mut ret = StationTable::new();
+
+        for &(code, country, name, tz, latitude, longitude) in BUILTIN.iter() {
+            ret.insert(Station {
+                code: code.into(),
+                country: country.into(),
+                name: name.into(),
+                timezone: tz.parse().expect("builtin time zones are valid"),
+                latitude,
+                longitude,
+            });
+        }
+
+        ret
+    }
+
+    pub fn insert(&mut self, station: Station) {
+        self.stations.insert(station.code.trim().to_uppercase(), station);
+    }
+
+    pub fn get(&self, code: &str) -> Option<&Station> {
+        self.stations.get(&code.trim().to_uppercase())
+    }
+
+    /// Country of the airport, a lookup for `CityPair::is_domestic()` and `Segment::is_international()`
+    pub fn country(&self, code: &str) -> Option<&str> {
+        self.get(code).map(|s| s.country.as_ref())
+    }
+
+    /// Distance of a leg, `None` if either airport is unknown
+    pub fn segment_distance_km(&self, segment: &Segment) -> Option<f64> {
+        Some(distance_km(self.get(segment.src_airport())?, self.get(segment.dst_airport())?))
+    }
+
+    pub fn segment_block_time(&self, segment: &Segment) -> Option<Duration> {
+        self.segment_distance_km(segment).map(estimate_block_time)
+    }
+
+    /// Stations in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Station> + '_ {
+        self.stations.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty()
+    }
+}
@@ -0,0 +1,269 @@
+//! Ticket taxes for settlement files, like the tax breakdown of BSP/RET records
+//!
+//! Amounts are kept in minor units of their currency. Decimal values with
+//! more digits than the currency has are rounded half away from zero, the
+//! rounding the IATA Clearing House applies to settlement amounts.
+
+use std::fmt;
+use std::error;
+use std::collections::BTreeMap;
+
+/// Currencies with other than 2 decimal places, ISO 4217
+const DECIMALS: &[(&str, u32)] = &[
+    ("BHD", 3), ("IQD", 3), ("JOD", 3), ("KWD", 3), ("LYD", 3), ("OMR", 3), ("TND", 3),
+    ("BIF", 0), ("CLP", 0), ("DJF", 0), ("GNF", 0), ("ISK", 0), ("JPY", 0), ("KMF", 0),
+    ("KRW", 0), ("PYG", 0), ("RWF", 0), ("UGX", 0), ("VND", 0), ("VUV", 0), ("XAF", 0),
+    ("XOF", 0), ("XPF", 0),
+];
+
+fn code<const N: usize>(src: &str, valid: fn(&u8) -> bool) -> Option<[u8; N]> {
+    let src = src.trim().to_ascii_uppercase();
+
+    if src.len() != N || !src.as_bytes().iter().all(valid) {
+        return None
+    }
+
+    let mut code = [0; N];
+    code.copy_from_slice(src.as_bytes());
+    Some(code)
+}
+
+/// ISO 4217 alphabetic currency code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub fn parse(src: &str) -> Option<Currency> {
+        code(src, u8::is_ascii_uppercase).map(Currency)
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or("")
+    }
+
+    /// Number of decimal places of the minor unit
+    pub fn decimals(&self) -> u32 {
+        DECIMALS.iter()
+            .find(|&&(c, _)| c == self.as_str())
+            .map_or(2, |&(_, d)| d)
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Two character tax code, like `US` or `YQ`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaxCode([u8; 2]);
+
+impl TaxCode {
+    pub fn parse(src: &str) -> Option<TaxCode> {
+        code(src, |b| b.is_ascii_uppercase() || b.is_ascii_digit()).map(TaxCode)
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or("")
+    }
+}
+
+impl fmt::Display for TaxCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// ISO 3166 alpha-2 country code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Country([u8; 2]);
+
+impl Country {
+    pub fn parse(src: &str) -> Option<Country> {
+        code(src, u8::is_ascii_uppercase).map(Country)
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or("")
+    }
+}
+
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Amount {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Amount {
+    pub fn new(minor: i64, currency: Currency) -> Amount {
+        Amount { minor, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Amount {
+        Amount::new(0, currency)
+    }
+
+    /// Parses a decimal value like `-12.345`, rounding it to the decimals of the currency
+    pub fn parse(src: &str, currency: Currency) -> Option<Amount> {
+        let src = src.trim();
+        let (negative, digits) = match src.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None       => (false, src),
+        };
+
+        let (int, frac) = match digits.find('.') {
+            Some(i) => (&digits[..i], &digits[i + 1..]),
+            None    => (digits, ""),
+        };
+
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if int.is_empty() || !is_digits(int) || !is_digits(frac) {
+            return None
+        }
+
+        let decimals = currency.decimals() as usize;
+        let mut minor: i64 = int.parse().ok()?;
+
+        for i in 0 .. decimals {
+            let digit = frac.as_bytes().get(i).map_or(0, |b| i64::from(b - b'0'));
+            minor = minor.checked_mul(10)?.checked_add(digit)?;
+        }
+
+        if frac.as_bytes().get(decimals).is_some_and(|&b| b >= b'5') {
+            minor = minor.checked_add(1)?;
+        }
+
+        Some(Amount::new(if negative { -minor } else { minor }, currency))
+    }
+
+    /// Amount in minor units, like cents
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Sum of the amounts, failing when currencies differ
+    pub fn checked_add(&self, other: &Amount) -> Result<Amount, TaxError> {
+        if self.currency != other.currency {
+            return Err(TaxError::CurrencyMismatch(self.currency, other.currency))
+        }
+
+        self.minor.checked_add(other.minor)
+            .map(|minor| Amount::new(minor, self.currency))
+            .ok_or(TaxError::Overflow)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimals = self.currency.decimals();
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let minor = self.minor.unsigned_abs();
+        let scale = 10u64.pow(decimals);
+
+        if decimals == 0 {
+            write!(f, "{}{} {}", sign, minor, self.currency)
+        } else {
+            write!(f, "{}{}.{:0width$} {}", sign, minor / scale, minor % scale, self.currency, width = decimals as usize)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaxError {
+    /// Amounts in different currencies can't be summed
+    CurrencyMismatch(Currency, Currency),
+    Overflow,
+}
+
+impl fmt::Display for TaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TaxError::CurrencyMismatch(a, b) => write!(f, "can't sum amounts in {} and {}", a, b),
+            TaxError::Overflow               => f.write_str("amount overflows"),
+        }
+    }
+}
+
+impl error::Error for TaxError {}
+
+/// Single tax, optionally with the country levying it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tax {
+    pub code: TaxCode,
+    pub country: Option<Country>,
+    pub amount: Amount,
+}
+
+/// Taxes of a ticket or a settlement record
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaxBreakdown {
+    taxes: Vec<Tax>,
+}
+
+impl TaxBreakdown {
+    pub fn new() -> TaxBreakdown {
+        TaxBreakdown::default()
+    }
+
+    pub fn push(&mut self, tax: Tax) {
+        self.taxes.push(tax);
+    }
+
+    pub fn taxes(&self) -> &[Tax] {
+        &self.taxes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.taxes.is_empty()
+    }
+
+    /// Sum of all taxes, `None` when there are none
+    pub fn total(&self) -> Result<Option<Amount>, TaxError> {
+        let mut taxes = self.taxes.iter();
+
+        let first = match taxes.next() {
+            Some(tax) => tax.amount,
+            None      => return Ok(None),
+        };
+
+        taxes.try_fold(first, |sum, tax| sum.checked_add(&tax.amount)).map(Some)
+    }
+
+    /// Sums per tax code, like several `XF` facility charges of a journey
+    pub fn by_code(&self) -> Result<BTreeMap<TaxCode, Amount>, TaxError> {
+        group(self.taxes.iter().map(|t| (t.code, t.amount)))
+    }
+
+    /// Sums per levying country, taxes without a country are left out
+    pub fn by_country(&self) -> Result<BTreeMap<Country, Amount>, TaxError> {
+        group(self.taxes.iter().filter_map(|t| t.country.map(|c| (c, t.amount))))
+    }
+}
+
+fn group<K: Ord, I>(entries: I) -> Result<BTreeMap<K, Amount>, TaxError>
+    where I: Iterator<Item = (K, Amount)>
+{
+    let mut groups = BTreeMap::new();
+
+    for (key, amount) in entries {
+        let sum = match groups.get(&key) {
+            Some(sum) => amount.checked_add(sum)?,
+            None      => amount,
+        };
+        groups.insert(key, sum);
+    }
+
+    Ok(groups)
+}
@@ -0,0 +1,122 @@
+//! Reader and runner for BCBP test case files
+//!
+//! The file lists one case per line as tab separated `id`, expected outcome
+//! and barcode data, the layout of the case listings in the IATA BCBP test
+//! kit. The expected outcome is `VALID` or `INVALID`, case insensitive.
+//! Blank lines and lines starting with `#` are skipped. The barcode is the
+//! rest of the line after the second tab, spaces included.
+//!
+//! ```
+//! # extern crate iata;
+//! use iata::bcbp::ParseOptions;
+//! use iata::testkit;
+//!
+//! let file = "# id\texpect\tdata\n\
+//!             M-001\tVALID\tM1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000\n\
+//!             M-002\tINVALID\tX1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000\n";
+//!
+//! let cases = testkit::read_cases(file.as_bytes()).unwrap();
+//! let stats = testkit::run(&cases, ParseOptions::strict());
+//!
+//! assert_eq!(stats.passed, 2);
+//! ```
+
+use std::io::{self, BufRead};
+
+use bcbp::{BCBP, Error, ParseOptions};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub id: String,
+    pub expect_valid: bool,
+    pub data: String,
+}
+
+/// Reads all cases, failing with `InvalidData` on a malformed line
+pub fn read_cases<R: BufRead>(reader: R) -> io::Result<Vec<TestCase>> {
+    let mut ret = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue
+        }
+
+        let mut fields = line.splitn(3, '\t');
+        let id = fields.next().unwrap_or("").trim();
+        let expect = fields.next().unwrap_or("").trim();
+        let data = fields.next();
+
+        let expect_valid = match (expect.to_uppercase().as_ref(), data) {
+            ("VALID", Some(_))   => true,
+            ("INVALID", Some(_)) => false,
+            _ => {
+                let msg = format!("line {}: expected `id<TAB>VALID|INVALID<TAB>data`", i + 1);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+            },
+        };
+
+        ret.push(TestCase {
+            id: id.into(),
+            expect_valid,
+            data: data.unwrap_or("").into(),
+        });
+    }
+
+    Ok(ret)
+}
+
+/// Case whose parse result differs from the expected one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub id: String,
+    pub expect_valid: bool,
+    /// Parser error, `None` when an invalid case was accepted
+    pub error: Option<Error>,
+}
+
+/// Aggregate results of a run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub total: usize,
+    pub passed: usize,
+    pub failures: Vec<Failure>,
+}
+
+impl Stats {
+    pub fn failed(&self) -> usize {
+        self.failures.len()
+    }
+
+    /// Share of cases with the expected outcome, 1.0 for an empty run
+    pub fn pass_rate(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0
+        }
+        self.passed as f64 / self.total as f64
+    }
+}
+
+/// Parses every case and compares the outcome with the expected one
+pub fn run<'a, I>(cases: I, options: ParseOptions) -> Stats
+    where I: IntoIterator<Item = &'a TestCase>
+{
+    let mut stats = Stats::default();
+
+    for case in cases {
+        stats.total += 1;
+
+        match (BCBP::from_with(&case.data, options.clone()), case.expect_valid) {
+            (Ok(_), true) | (Err(_), false) => stats.passed += 1,
+            (result, expect_valid) => stats.failures.push(Failure {
+                id: case.id.clone(),
+                expect_valid,
+                error: result.err().map(|e| e.error),
+            }),
+        }
+    }
+
+    stats
+}
@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate iata;
+
+use iata::airline_use::{self, FixedLayout};
+use iata::bcbp::{BCBP, ConditionalExtension, ExtensionRegistry};
+
+const PASS: &str = "M1JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09         ";
+
+#[test]
+fn fixed_layout() {
+    let layout = FixedLayout::new("*", &[("marker", 3), ("code", 5), ("rest", 20)]);
+
+    let fields = layout.decode("*30600000K09").unwrap();
+    assert!(fields["marker"] == "306" && fields["code"] == "00000");
+    assert!(!fields.contains_key("rest"));
+    assert!(layout.decode("30600000K09").is_none());
+
+    let mut registry = ExtensionRegistry::new();
+    register_airline_use!(registry, "sk", "*", { "marker" => 3, "code" => 5, });
+
+    let bcbp = BCBP::from(PASS).unwrap();
+    assert!(registry.is_registered("SK"));
+    assert!(registry.decode(&bcbp.segments()[0]).unwrap()["code"] == "00000");
+}
+
+#[test]
+fn well_known() {
+    let registry = airline_use::well_known();
+    assert!(registry.is_registered("SK") == cfg!(feature = "airline-sk"));
+
+    #[cfg(feature = "airline-sk")]
+    {
+        let bcbp = BCBP::from(PASS).unwrap();
+        assert!(registry.decode(&bcbp.segments()[0]).unwrap()["marker"] == "306");
+    }
+}
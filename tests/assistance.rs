@@ -0,0 +1,31 @@
+extern crate iata;
+
+use iata::assistance::*;
+use iata::bcbp::BCBP;
+
+#[test]
+fn sources() {
+    let pnl = AssistanceNeeds::from_pnl_line("1SMITH/JOHNMR-A2 .L/ABCDEF .R/WCHS HK1 .R/VGML HK1 .R/WCBW HK1");
+    assert!(pnl.iter().collect::<Vec<_>>() == [Assistance::WheelchairSteps, Assistance::PoweredWheelchair]);
+
+    let ssr = AssistanceNeeds::from_ssr_codes(vec!["blnd", "SVAN", "ESAN", "CHML"]);
+    assert!(ssr.to_string() == "BLND SVAN");
+    assert!(!ssr.needs_wheelchair());
+
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             WCHR/MAAS  ").unwrap();
+    let pass = AssistanceNeeds::from_bcbp(&bcbp);
+    assert!(pass.contains(Assistance::WheelchairRamp) && pass.contains(Assistance::MeetAndAssist));
+
+    let plain = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    assert!(AssistanceNeeds::from_segment(&plain.segments()[0]).is_empty());
+
+    let mut all = pass.clone();
+    all.merge(&pnl);
+    all.insert(Assistance::Oxygen);
+    assert!(all.to_string() == "WCHR WCHS WCBD MAAS OXYG");
+    assert!(all.needs_wheelchair());
+
+    assert!(AssistanceNeeds::new().is_empty());
+    assert!(Assistance::parse("WCLB") == Some(Assistance::PoweredWheelchair));
+    assert!(Assistance::parse("XBAG").is_none());
+}
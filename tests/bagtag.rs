@@ -0,0 +1,46 @@
+extern crate iata;
+
+use iata::bagtag::*;
+
+#[test]
+fn license_plate() {
+    let tag = LicensePlate::parse("0555123456").unwrap();
+
+    assert!(tag.category() == TagCategory::Interline);
+    assert!(tag.airline() == 555);
+    assert!(tag.serial() == 123456);
+    assert!(tag.to_string() == "0555123456");
+
+    assert!(LicensePlate::parse("2555123456").unwrap().category() == TagCategory::Rush);
+    assert!(LicensePlate::parse("7555123456").unwrap().category() == TagCategory::Other(7));
+    assert!(LicensePlate::parse("055512345").is_none());
+    assert!(LicensePlate::parse("05551234X6").is_none());
+}
+
+#[test]
+fn range_allocation() {
+    let mut range = TagRange::new(TagCategory::Interline, 220, 999_998, 999_999).unwrap();
+
+    assert!(range.remaining() == 2);
+    assert!(range.allocate().unwrap().to_string() == "0220999998");
+    assert!(range.allocate().unwrap().to_string() == "0220999999");
+    assert!(range.allocate().is_none());
+    assert!(range.remaining() == 0);
+
+    assert!(TagRange::new(TagCategory::Interline, 220, 0, 1_000_000).is_none());
+}
+
+#[test]
+fn tag_sequence() {
+    let tags = TagSequence::parse("0555123456002").unwrap();
+
+    assert!(tags.first().to_string() == "0555123456");
+    assert!(tags.count() == 3);
+    assert!(tags.to_string() == "0555123456002");
+
+    let plates: Vec<String> = tags.expand().iter().map(|p| p.to_string()).collect();
+    assert!(plates == ["0555123456", "0555123457", "0555123458"]);
+
+    assert!(TagSequence::parse("0555999999005").unwrap().expand().len() == 1);
+    assert!(TagSequence::parse("             ").is_none());
+}
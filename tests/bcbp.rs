@@ -1,34 +1,47 @@
 extern crate iata;
 
 use iata::bcbp::*;
+use iata::clock::FixedClock;
+use iata::date::{self, DateError};
+use iata::pax_status::PaxStatus;
+use iata::redact::RedactionRules;
 
 #[test]
 fn errors() {
     match BCBP::from("") {
-        Ok(_)  => assert!(false),
+        Ok(_)  => panic!(),
         Err(e) => assert!(e == Error::DataLength),
     }
 
     match BCBP::from("X1BRUNER/ROMAN MR     EJNUFFX MUCSVOSU 2327 231L013A0052 100") {
-        Ok(_)  => assert!(false),
+        Ok(_)  => panic!(),
         Err(e) => assert!(e == Error::FormatCode),
     }
 
     match BCBP::from("M0BRUNER/ROMAN MR     EJNUFFX MUCSVOSU 2327 231L013A0052 100") {
-        Ok(_)  => assert!(false),
+        Ok(_)  => panic!(),
         Err(e) => assert!(e == Error::SegmentsCount)
     }
 
     match BCBP::from("MABRUNER/ROMAN MR     EJNUFFX MUCSVOSU 2327 231L013A0052 100") {
-        Ok(_)  => assert!(false),
+        Ok(_)  => panic!(),
         Err(e) => assert!(e == Error::SegmentsCount)
     }
 
     match BCBP::from("M1BRUNER/ROMAN MR     EJNUFFX MUCSVOSU 2327 231L013A0052 1FF") {
-        Ok(_)  => assert!(false),
+        Ok(_)  => panic!(),
         Err(e) => assert!(e == Error::CoditionalDataSize)
     }
 
+    match BCBP::from("M1BRUNER/ROMAN MR     #JNUFFX MUCSVOSU 2327 231L013A0052 100") {
+        Ok(_)  => panic!(),
+        Err(e) => assert!(e == Error::ElectronicTicketIndicator)
+    }
+
+    assert!(BCBP::from_with("M1BRUNER/ROMAN MR     #JNUFFX MUCSVOSU 2327 231L013A0052 100", ParseOptions::lenient()).is_ok());
+
+    assert!(Error::FormatCode.to_string() == "format code must be 'M'");
+
         println!("{:?}", BCBP::from("M1BRUNER/ROMAN MR     EJNUFFX MUCSVOSU 2327 231L013A0052 100"));
         // assert!(false);
 
@@ -48,22 +61,72 @@ fn mandatory1() {
     assert!(bcbp.name()        == "JOHN/SMITH JORDAN");
     assert!(bcbp.name_last()   == "JOHN");
     assert!(bcbp.name_first()  == "SMITH JORDAN");
-    assert!(bcbp.ticket_flag()  == 'E');
-    assert!(bcbp.segments[0].pnr() == "ABCDEF");
-    assert!(bcbp.segments[0].src_airport()  == "JFK");
-    assert!(bcbp.segments[0].dst_airport()  == "SVO");
-    assert!(bcbp.segments[0].airline()      == "SU");
-    assert!(bcbp.segments[0].flight_code()  == "1234A");
-    assert!(bcbp.segments[0].flight_day()   == 1);
-    assert!(bcbp.segments[0].flight_date(2017) == NaiveDate::from_ymd(2017, 1, 1));
-    assert!(bcbp.segments[0].flight_day_aligned()   == "001");
-    assert!(bcbp.segments[0].compartment()  == 'Y');
-    assert!(bcbp.segments[0].seat()         == "1Z");
-    assert!(bcbp.segments[0].seat_aligned() == "001Z");
-    assert!(bcbp.segments[0].sequence()         == 7);
-    assert!(bcbp.segments[0].sequence_aligned() == "0007");
-    assert!(bcbp.segments[0].pax_status()   == "0");
-    assert!(bcbp.build().unwrap() == src);
+    assert!(bcbp.electronic_ticket_indicator() == TicketIndicator::Eticket);
+    assert!(bcbp.segments()[0].pnr() == "ABCDEF");
+    assert!(bcbp.segments()[0].src_airport()  == "JFK");
+    assert!(bcbp.segments()[0].dst_airport()  == "SVO");
+    assert!(bcbp.segments()[0].airline()      == "SU");
+    assert!(bcbp.segments()[0].flight_code()  == "1234A");
+    assert!(bcbp.segments()[0].flight_day()   == 1);
+    assert!(bcbp.segments()[0].try_flight_date(2017) == Ok(date::from_ymd(2017, 1, 1).unwrap()));
+    assert!(bcbp.segments()[0].try_flight_date_current_year_with(&FixedClock(date::from_ymd(2016, 12, 31).unwrap())) == Ok(date::from_ymd(2016, 1, 1).unwrap()));
+    assert!(bcbp.segments()[0].flight_day_aligned()   == "001");
+    assert!(bcbp.segments()[0].compartment()  == 'Y');
+    assert!(bcbp.segments()[0].seat()         == "1Z");
+    assert!(bcbp.segments()[0].seat_aligned() == "001Z");
+    assert!(bcbp.segments()[0].sequence()         == 7);
+    assert!(bcbp.segments()[0].sequence_aligned() == "0007");
+    assert!(bcbp.segments()[0].pax_status()   == "0");
+    assert!(bcbp.segments()[0].passenger_status() == Some(PaxStatus::NotCheckedIn));
+    assert!(bcbp.segments()[0].passenger_status().unwrap().needs_document_check());
+    assert!(bcbp.segments()[0].metadata().is_none());
+    assert!(bcbp.conditional_version().is_none());
+    assert!(bcbp.passenger_description().is_none());
+    assert!(bcbp.document_type().is_none());
+    assert!(bcbp.security().is_none());
+    assert!(bcbp.summary_with(&FixedClock(date::from_ymd(2017, 5, 1).unwrap())) == "JOHN/SMITH JORDAN SU1234A JFK→SVO 01JAN 1Z SEQ 7");
+    assert!(bcbp.expect_build() == src);
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn segment_metadata() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+    let mut bcbp = BCBP::from(src).unwrap();
+
+    bcbp.segments_mut()[0].set_metadata(Some(SegmentMetadata {
+        terminal: Some("4".into()),
+        gate: Some("B22".into()),
+        boarding_time: NaiveTime::from_hms_opt(13, 40, 0),
+        status: None,
+    }));
+
+    assert!(bcbp.segments()[0].metadata().unwrap().gate == Some("B22".into()));
+    assert!(bcbp.expect_build() == src);
+}
+
+#[test]
+fn name_field() {
+    use iata::flight::FlightKey;
+
+    let bcbp = BCBP::from("M1SMITH/JÖRG ÅKE      EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+
+    assert!(bcbp.name_first() == "JÖRG ÅKE");
+    assert!(bcbp.name() == "SMITH/J?RG ?KE");
+    assert!(!bcbp.name_truncated());
+
+    let key = FlightKey::new("SU", 100, None, date::from_ymd(2017, 1, 1).unwrap(), "JFK");
+    let mut template = PassTemplate::new(key, "SVO");
+
+    let long = template.issue("WOLFESCHLEGELSTEINHAUSEN/HUBERT BLAINE", "ABCDEF", "1A", 1).unwrap();
+    assert!(long.name() == "WOLFESCHLEGELSTEINHA");
+    assert!(long.name_truncated());
+
+    // Cut within non ASCII characters, which are replaced before the cut
+    let umlauts = template.issue("LUDENSCHEIDT/BJÖRN ÅSMUND", "ABCDEF", "2A", 2).unwrap();
+    assert!(umlauts.name() == "LUDENSCHEIDT/BJ?RN ?");
+    assert!(umlauts.name().len() == 20);
+    assert!(umlauts.name_truncated());
 }
 
 #[test]
@@ -76,37 +139,38 @@ fn mandatory4() {
     let bcbp = tmp.unwrap();
 
     assert!(bcbp.name()       == "VERYLONGESTLASTNAMED");
+    assert!(!bcbp.name_truncated());
     assert!(bcbp.name_last()  == "VERYLONGESTLASTNAMED");
     assert!(bcbp.name_first() == "");
-    assert!(bcbp.ticket_flag() == 'E');
-    assert!(bcbp.segments[0].pnr()  == "ABCDEF");
-    assert!(bcbp.segments[0].src_airport()  == "JFK");
-    assert!(bcbp.segments[0].dst_airport()  == "SVO");
-    assert!(bcbp.segments[0].airline()      == "SU");
-    assert!(bcbp.segments[0].flight_code()  == "1234");
-    assert!(bcbp.segments[0].flight_day()   == 207);
-    assert!(bcbp.segments[1].pnr()  == "ABCDEF");
-    assert!(bcbp.segments[1].src_airport()  == "SVO");
-    assert!(bcbp.segments[1].dst_airport()  == "LED");
-    assert!(bcbp.segments[1].airline()      == "SU");
-    assert!(bcbp.segments[1].flight_code()  == "5678");
-    assert!(bcbp.segments[1].flight_day()   == 210);
-    assert!(bcbp.segments[2].pnr()  == "ABCDEF");
-    assert!(bcbp.segments[2].src_airport()  == "LED");
-    assert!(bcbp.segments[2].dst_airport()  == "SVO");
-    assert!(bcbp.segments[2].airline()      == "SU");
-    assert!(bcbp.segments[2].flight_code()  == "9876");
-    assert!(bcbp.segments[2].flight_day()   == 215);
-    assert!(bcbp.segments[3].pnr()  == "ABCDEF");
-    assert!(bcbp.segments[3].src_airport()  == "SVO");
-    assert!(bcbp.segments[3].dst_airport()  == "JFK");
-    assert!(bcbp.segments[3].airline()      == "SU");
-    assert!(bcbp.segments[3].flight_code()  == "1357");
-    assert!(bcbp.segments[3].flight_day()   == 215);
-
-    println!("BLD{:?}\nSRC{:?}", bcbp.build().unwrap(), src);
-
-    assert!(bcbp.build().unwrap() == src);
+    assert!(bcbp.electronic_ticket_indicator() == TicketIndicator::Eticket);
+    assert!(bcbp.segments()[0].pnr()  == "ABCDEF");
+    assert!(bcbp.segments()[0].src_airport()  == "JFK");
+    assert!(bcbp.segments()[0].dst_airport()  == "SVO");
+    assert!(bcbp.segments()[0].airline()      == "SU");
+    assert!(bcbp.segments()[0].flight_code()  == "1234");
+    assert!(bcbp.segments()[0].flight_day()   == 207);
+    assert!(bcbp.segments()[1].pnr()  == "ABCDEF");
+    assert!(bcbp.segments()[1].src_airport()  == "SVO");
+    assert!(bcbp.segments()[1].dst_airport()  == "LED");
+    assert!(bcbp.segments()[1].airline()      == "SU");
+    assert!(bcbp.segments()[1].flight_code()  == "5678");
+    assert!(bcbp.segments()[1].flight_day()   == 210);
+    assert!(bcbp.segments()[2].pnr()  == "ABCDEF");
+    assert!(bcbp.segments()[2].src_airport()  == "LED");
+    assert!(bcbp.segments()[2].dst_airport()  == "SVO");
+    assert!(bcbp.segments()[2].airline()      == "SU");
+    assert!(bcbp.segments()[2].flight_code()  == "9876");
+    assert!(bcbp.segments()[2].flight_day()   == 215);
+    assert!(bcbp.segments()[3].pnr()  == "ABCDEF");
+    assert!(bcbp.segments()[3].src_airport()  == "SVO");
+    assert!(bcbp.segments()[3].dst_airport()  == "JFK");
+    assert!(bcbp.segments()[3].airline()      == "SU");
+    assert!(bcbp.segments()[3].flight_code()  == "1357");
+    assert!(bcbp.segments()[3].flight_day()   == 215);
+
+    println!("BLD{:?}\nSRC{:?}", bcbp.expect_build(), src);
+
+    assert!(bcbp.expect_build() == src);
 }
 
 #[test]
@@ -125,24 +189,985 @@ fn conditional3() {
     assert!(bcbp.name()       == "JOHN/SMITH");
     assert!(bcbp.name_last()  == "JOHN");
     assert!(bcbp.name_first() == "SMITH");
-    assert!(bcbp.ticket_flag() == 'E');
-    assert!(bcbp.segments[0].pnr()  == "ABCDEF");
-    assert!(bcbp.segments[0].src_airport()  == "JFK");
-    assert!(bcbp.segments[0].dst_airport()  == "SVO");
-    assert!(bcbp.segments[0].airline()      == "SK");
-    assert!(bcbp.segments[0].flight_code()  == "1234");
-    assert!(bcbp.segments[0].flight_day()   == 123);
-    assert!(bcbp.segments[1].pnr()  == "ABCDEF");
-    assert!(bcbp.segments[1].src_airport()  == "SVO");
-    assert!(bcbp.segments[1].dst_airport()  == "FRA");
-    assert!(bcbp.segments[1].airline()      == "SU");
-    assert!(bcbp.segments[1].flight_code()  == "5678");
-    assert!(bcbp.segments[1].flight_day()   == 135);
-    assert!(bcbp.segments[2].pnr()  == "ABCDEF");
-    assert!(bcbp.segments[2].src_airport()  == "FRA");
-    assert!(bcbp.segments[2].dst_airport()  == "JFK");
-    assert!(bcbp.segments[2].airline()      == "SU");
-    assert!(bcbp.segments[2].flight_code()  == "9876");
-    assert!(bcbp.segments[2].flight_day()   == 231);
+    assert!(bcbp.electronic_ticket_indicator() == TicketIndicator::Eticket);
+    assert!(bcbp.segments_count() == 3);
+    assert!(bcbp.conditional_version() == Some('5'));
+    assert!(bcbp.passenger_description() == Some(PassengerDescription::Adult));
+    assert!(bcbp.document_type() == Some('B'));
+    assert!(bcbp.checkin_source() == Some(Source::AirportAgent));
+    assert!(bcbp.boarding_pass_source().is_none());
+    assert!(bcbp.segments()[0].pnr()  == "ABCDEF");
+    assert!(bcbp.segments()[0].src_airport()  == "JFK");
+    assert!(bcbp.segments()[0].dst_airport()  == "SVO");
+    assert!(bcbp.segments()[0].airline()      == "SK");
+    assert!(bcbp.segments()[0].flight_code()  == "1234");
+    assert!(bcbp.segments()[0].flight_day()   == 123);
+    assert!(bcbp.segments()[0].compartment()  == 'M');
+    assert!(bcbp.segments()[0].seat()         == "14C");
+    assert!(bcbp.segments()[0].sequence()     == 50);
+    assert!(bcbp.segments()[0].pax_status()   == "3");
+    assert!(bcbp.segments()[1].pnr()  == "ABCDEF");
+    assert!(bcbp.segments()[1].src_airport()  == "SVO");
+    assert!(bcbp.segments()[1].dst_airport()  == "FRA");
+    assert!(bcbp.segments()[1].airline()      == "SU");
+    assert!(bcbp.segments()[1].flight_code()  == "5678");
+    assert!(bcbp.segments()[1].flight_day()   == 135);
+    assert!(bcbp.segments()[2].pnr()  == "ABCDEF");
+    assert!(bcbp.segments()[2].src_airport()  == "FRA");
+    assert!(bcbp.segments()[2].dst_airport()  == "JFK");
+    assert!(bcbp.segments()[2].airline()      == "SU");
+    assert!(bcbp.segments()[2].flight_code()  == "9876");
+    assert!(bcbp.segments()[2].flight_day()   == 231);
+}
+
+
+#[test]
+fn airline_extension() {
+    let src = "M3JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09         ABCDEF SVOFRASU 5678 135Y013A0012 3372A55559467513990 SU SU 12345678             09         ABCDEF FRAJFKSU 9876 231Y022F0052 3372A55559467513990 SU SU 12345678             09         ";
+    let bcbp = BCBP::from(src).unwrap();
+
+    assert!(bcbp.segments()[0].airline_data() == Some("*30600000K09         "));
+    assert!(bcbp.segments()[1].airline_data() == Some("09         "));
+    assert!(bcbp.segments()[1].frequent_flyer_airline() == Some("SU"));
+    assert!(bcbp.segments()[1].frequent_flyer_number() == Some("12345678"));
+
+    let mut registry = ExtensionRegistry::new();
+    registry.register("SK", |data: &str| {
+        if !data.starts_with('*') {
+            return None
+        }
+        let mut fields = ::std::collections::BTreeMap::new();
+        fields.insert("marker".to_string(), data[1..4].to_string());
+        Some(fields)
+    });
+
+    assert!(registry.is_registered("sk"));
+    assert!(registry.decode(&bcbp.segments()[0]).unwrap()["marker"] == "306");
+    assert!(registry.decode(&bcbp.segments()[1]).is_none());
+}
+
+#[test]
+fn security_legacy() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000^10AGIWVC5EH7j";
+    let mut legacy = String::from(src);
+    legacy.truncate(60);
+    legacy.push_str("^GIWVC5EH7jnt684");
+
+    match BCBP::from(&legacy) {
+        Ok(_)  => panic!(),
+        Err(e) => assert!(e == Error::InvalidSizeField(29, *b"IW")),
+    }
+
+    let bcbp = BCBP::from_with(&legacy, ParseOptions::lenient()).unwrap();
+
+    assert!(bcbp.security() == Some(&SecuritySection::Legacy("GIWVC5EH7jnt684".into())));
+    assert!(bcbp.expect_build() == legacy);
+
+    let bcbp = BCBP::from(src).unwrap();
+
+    match bcbp.security() {
+        Some(&SecuritySection::Standard { kind, ref data }) => {
+            assert!(kind == '1');
+            assert!(data == "GIWVC5EH7j");
+        },
+        _ => panic!(),
+    }
+    assert!(bcbp.expect_build() == src);
+}
+
+#[test]
+fn security_data() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+
+    let bcbp = BCBP::from(&format!("{}^10AGIWVC5EH7j", src)).unwrap();
+    assert!(bcbp.security_data_type() == Some('1'));
+    assert!(bcbp.security_data() == Some("GIWVC5EH7j"));
+
+    let plain = BCBP::from(src).unwrap();
+    assert!(plain.security_data_type().is_none() && plain.security_data().is_none());
+
+    for &(security, ref error) in [("^", Error::SecurityHeader), ("^1", Error::SecurityHeader), ("^10", Error::SecurityHeader),
+                               ("^10AGIWVC", Error::SecurityDataSize), ("^1ZZGIWVC", Error::InvalidSizeField(29, *b"ZZ"))].iter() {
+        let data = format!("{}{}", src, security);
+        assert!(BCBP::from(&data).unwrap_err() == *error);
+        assert!(validate_only(data.as_bytes()).err() == Some(error.clone()));
+    }
+
+    let legacy = BCBP::from_with(&format!("{}^1", src), ParseOptions::lenient()).unwrap();
+    assert!(legacy.security_data_type().is_none() && legacy.security_data() == Some("1"));
+}
+
+#[test]
+fn security_build() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+    let mut bcbp = BCBP::from(src).unwrap();
+
+    bcbp.set_security_data('1', "GIWVC5EH7j");
+    assert!(bcbp.expect_build() == format!("{}^10AGIWVC5EH7j", src));
+
+    bcbp.set_security_data('1', &"X".repeat(256));
+    assert!(bcbp.try_build() == Err(BuildError::SecurityDataSize(256)));
+
+    bcbp.set_security(None);
+    assert!(bcbp.expect_build() == src);
+}
+
+#[test]
+fn spec_layout() {
+    use iata::spec;
+
+    assert!(spec::fixed_len(spec::MANDATORY) == 23);
+    assert!(spec::fixed_len(spec::LEG) == 37);
+    assert!(spec::item(254).unwrap().since == 6);
+    assert!(spec::item(4).unwrap().is_variable());
+}
+
+#[test]
+fn charsets() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFK5VOSU 1234A001Y001Z0007 000";
+
+    match BCBP::from(src) {
+        Ok(_)  => panic!(),
+        Err(e) => assert!(e == Error::InvalidCharset(38)),
+    }
+
+    let bcbp = BCBP::from_with(src, ParseOptions::lenient()).unwrap();
+
+    assert!(bcbp.segments()[0].dst_airport() == "5VO");
+    assert!(bcbp.warnings() == [Warning::Charset { item: 38, value: "5VO".into() }]);
+    assert!(BCBP::from(src.to_lowercase().as_ref()).is_err());
+    assert!(BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap().warnings().is_empty());
 }
 
+#[test]
+fn repeated_charsets() {
+    let src = "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2AABC59467513#90 SU SU 12345678             09         ";
+
+    let e = BCBP::from_with(src, ParseOptions::strict()).unwrap_err();
+    assert!(e == Error::InvalidCharset(142) && e.offset == 90 && e.raw == "ABC");
+
+    let e = BCBP::from_with(&src.replace("2AABC", "2A555"), ParseOptions::strict()).unwrap_err();
+    assert!(e == Error::InvalidCharset(143) && e.offset == 93 && e.raw == "**********");
+
+    let bcbp = BCBP::from_with(src, ParseOptions::lenient()).unwrap();
+    assert!(bcbp.warnings() == [
+        Warning::Charset { item: 142, value: "ABC".into() },
+        Warning::Charset { item: 143, value: "59467513#9".into() },
+    ]);
+    assert!(bcbp.quality_score() == 70);
+    assert!(bcbp.segments()[0].conditional().airline_code() == Some("ABC"));
+}
+
+#[test]
+fn snippet_masking() {
+    let src = "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU S$ 12345678             09         ";
+
+    // Document and frequent flyer numbers around the failing item are masked like `redact()` does
+    let e = BCBP::from_with(src, ParseOptions::strict().with_snippets(true)).unwrap_err();
+    assert!(e == Error::InvalidCharset(20) && e.offset == 108 && e.raw == "S$ ");
+    assert!(e.snippet.as_ref().map(|s| s.as_ref()) == Some("*******0 SU S$ ******** "));
+
+    let e = BCBP::from_with(&src.replace("2A555", "2A5$5").replace("S$", "SU"), ParseOptions::strict().with_snippets(true)).unwrap_err();
+    assert!(e == Error::InvalidCharset(142) && e.raw == "5$5");
+    assert!(e.snippet.as_ref().map(|s| s.as_ref()) == Some("          2A5$5*********"));
+}
+
+#[test]
+fn conditional_boundaries() {
+    let mandatory = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 0";
+
+    let bcbp = BCBP::from(&format!("{}00", mandatory)).unwrap();
+    assert!(bcbp.conditional_version().is_none());
+
+    let bcbp = BCBP::from(&format!("{}01>", mandatory)).unwrap();
+    assert!(bcbp.conditional_version().is_none());
+
+    let bcbp = BCBP::from(&format!("{}02>6", mandatory)).unwrap();
+    assert!(bcbp.conditional_version() == Some('6'));
+
+    match BCBP::from(&format!("{}03>60", mandatory)) {
+        Ok(_)  => panic!(),
+        Err(e) => assert!(e == Error::DataLength),
+    }
+
+    let bcbp = BCBP::from(&format!("{}04>600", mandatory)).unwrap();
+    assert!(bcbp.conditional_version() == Some('6'));
+    assert!(bcbp.passenger_description().is_none());
+
+    let bcbp = BCBP::from(&format!("{}06>60000", mandatory)).unwrap();
+    assert!(bcbp.conditional_version() == Some('6'));
+    assert!(bcbp.segments()[0].airline_data().is_none());
+
+    let bcbp = BCBP::from(&format!("{}07>600001", mandatory)).unwrap();
+    assert!(bcbp.passenger_description().is_none());
+    assert!(bcbp.segments()[0].airline_data() == Some("1"));
+
+    let bcbp = BCBP::from(&format!("{}05>6011", mandatory)).unwrap();
+    assert!(bcbp.passenger_description() == Some(PassengerDescription::Male));
+
+    // Short sections are written back as they were parsed
+    for conditional in ["00", "01>", "02>6", "04>600", "06>60000", "07>600001", "05>6011"].iter() {
+        let src = format!("{}{}", mandatory, conditional);
+        assert!(BCBP::from(&src).unwrap().expect_build() == src, "{}", src);
+    }
+
+    // and keep their version once changed
+    let moved = BCBP::from(&format!("{}02>6", mandatory)).unwrap().with_new_seat("2A").unwrap().expect_build();
+    assert!(moved.ends_with("002A0007 006>60000"));
+    assert!(BCBP::from(&moved).unwrap().conditional_version() == Some('6'));
+}
+
+#[test]
+fn boarding_index() {
+    use iata::bcbp::index::BoardingIndex;
+
+    let first  = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let repeat = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let other  = BCBP::from("M1DOE/JANE            EABCDEF JFKSVOSU 1234A001Y001A0008 000").unwrap();
+
+    let mut index = BoardingIndex::new();
+
+    assert!(!index.is_boarded(&first.segments()[0]));
+    assert!(index.insert(first));
+    assert!(index.is_boarded(&repeat.segments()[0]));
+    assert!(!index.insert(repeat));
+    assert!(index.insert(other));
+    assert!(index.len() == 2);
+    assert!(index.by_pnr("ABCDEF").len() == 2);
+    assert!(index.by_pnr("XXXXXX").is_empty());
+}
+
+#[test]
+#[cfg(not(feature = "full_debug"))]
+fn debug_redacted() {
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let debug = format!("{:?}", bcbp);
+
+    assert!(!debug.contains("SMITH"));
+    assert!(!debug.contains("ABCDEF"));
+    assert!(debug.contains("JFK"));
+
+    let mut bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ").unwrap();
+    bcbp.segments_mut()[0].add_item(236, "87654321").unwrap();
+    let debug = format!("{:?}", bcbp);
+
+    for private in ["12345678", "87654321", "5946751399"].iter() {
+        assert!(!debug.contains(private), "{}", private);
+    }
+    assert!(debug.contains("236: <redacted>"));
+}
+
+#[test]
+fn redact_items() {
+    let mut bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ").unwrap();
+    bcbp.segments_mut()[0].add_item(236, "87654321").unwrap();
+
+    let mut kept = bcbp.clone();
+    kept.redact(&RedactionRules { document_number: false, ..RedactionRules::none() });
+    assert!(kept.segments()[0].conditional().document_number() == Some("5946751399"));
+
+    bcbp.redact(&RedactionRules::all());
+    let s = &bcbp.segments()[0];
+
+    assert!(s.conditional().document_number() == Some("0000000000"));
+    assert!(s.frequent_flyer_number() == Some("XXXXXXXX"));
+    assert!(s.item(236) == Some("XXXXXXXX"));
+
+    let built = bcbp.expect_build();
+    for private in ["SMITH", "ABCDEF", "12345678", "87654321", "5946751399"].iter() {
+        assert!(!built.contains(private), "{}", private);
+    }
+    assert!(BCBP::from(&built).is_ok());
+}
+
+#[test]
+fn flight_date_leap_years() {
+    let pass = |day: &str| BCBP::from(&format!("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A{}Y001Z0007 000", day)).unwrap();
+
+    let last = pass("365");
+    assert!(last.segments()[0].try_flight_date(2017) == Ok(date::from_ymd(2017, 12, 31).unwrap()));
+    assert!(last.segments()[0].try_flight_date(2016) == Ok(date::from_ymd(2016, 12, 30).unwrap()));
+
+    let leap = pass("366");
+    assert!(leap.segments()[0].try_flight_date(2016) == Ok(date::from_ymd(2016, 12, 31).unwrap()));
+    assert!(leap.segments()[0].try_flight_date(2000) == Ok(date::from_ymd(2000, 12, 31).unwrap()));
+    assert!(leap.segments()[0].try_flight_date(2017) == Err(DateError::InvalidDay { year: 2017, day: 366 }));
+    assert!(leap.segments()[0].try_flight_date(1900) == Err(DateError::InvalidDay { year: 1900, day: 366 }));
+    assert!(leap.segments()[0].flight_date_opt(2017).is_none());
+
+    assert!(pass("367").segments()[0].try_flight_date(2016).is_err());
+    assert!(pass("   ").segments()[0].try_flight_date(2016) == Err(DateError::Missing));
+}
+
+#[test]
+fn encoded() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+
+    let json = "\"M1JOHN\\/SMITH JORDAN\\u0020\\u0020 EABCDEF JFKSVOSU 1234A001Y001Z0007 000\\r\\n\"";
+    assert!(BCBP::from_encoded(json).unwrap().expect_build() == src);
+
+    let query = "pass=M1JOHN%2FSMITH+JORDAN+++EABCDEF+JFKSVOSU+1234A001Y001Z0007+000";
+    assert!(BCBP::from_encoded(&format!("wallet://add?{}", query)).unwrap().expect_build() == src);
+
+    let url_safe = "TTFKT0hOL1NNSVRIIEpPUkRBTiAgIEVBQkNERUYgSkZLU1ZPU1UgMTIzNEEwMDFZMDAxWjAwMDcgMDAw";
+    assert!(BCBP::from_encoded(url_safe).unwrap().expect_build() == src);
+
+    assert!(BCBP::from_encoded(src).unwrap().expect_build() == src);
+    assert!(BCBP::from_encoded("aGVsbG8=").is_err());
+}
+
+#[test]
+fn reservation_match() {
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+
+    let mut reservation = ReservationSummary {
+        pnr: "abcdef".into(),
+        name_last: "John".into(),
+        name_first: "Smith  Jordan".into(),
+        airline: "SU".into(),
+        flight_code: "01234A".into(),
+        date: date::from_ymd(2017, 1, 1).unwrap(),
+    };
+
+    assert!(bcbp.matches_reservation(&reservation) == ReservationMatch::Exact);
+
+    reservation.pnr = "XYZXYZ".into();
+    reservation.date = date::from_ymd(2017, 1, 2).unwrap();
+    assert!(bcbp.matches_reservation(&reservation) == ReservationMatch::Mismatch(vec![ReservationField::Pnr, ReservationField::Date]));
+
+    let long = BCBP::from("M1WOLFESCHLEGEL/HUBERTEABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let reservation = ReservationSummary {
+        pnr: "ABCDEF".into(),
+        name_last: "WOLFESCHLEGEL".into(),
+        name_first: "HUBERT BLAINE".into(),
+        airline: "SU".into(),
+        flight_code: "1234A".into(),
+        date: date::from_ymd(2017, 1, 1).unwrap(),
+    };
+
+    assert!(long.matches_reservation(&reservation) == ReservationMatch::NameTruncated);
+    assert!(bcbp.matches_reservation(&reservation) == ReservationMatch::Mismatch(vec![ReservationField::Name]));
+}
+
+#[test]
+fn trailing_data() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+
+    assert!(BCBP::from(&format!("{}XYZ", src)).unwrap_err() == Error::TrailingData);
+
+    let bcbp = BCBP::from_with(&format!("{}XYZ", src), ParseOptions::lenient()).unwrap();
+    assert!(bcbp.trailing() == Some("XYZ"));
+    assert!(bcbp.warnings() == [Warning::TrailingData]);
+    assert!(bcbp.expect_build() == format!("{}XYZ", src));
+
+    let bcbp = BCBP::from(&format!("]Q3{}", src)).unwrap();
+    assert!(bcbp.symbology() == Some("]Q3"));
+    assert!(bcbp.trailing().is_none());
+    assert!(bcbp.expect_build() == src);
+}
+
+#[test]
+fn undersized_conditional() {
+    // Trailing blanks dropped by the scanner, the sizes announce the whole sections
+    let src = "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK 05551234560012A55559467513990 SU SU 12345678             09";
+
+    assert!(BCBP::from(src).unwrap_err() == Error::CoditionalDataSize);
+
+    let bcbp = BCBP::from_with(src, ParseOptions::lenient()).unwrap();
+    assert!(bcbp.warnings() == [Warning::Undersized { item: 6, size: 0x53, len: 74 }]);
+    assert!(bcbp.issuer_airline() == Some("SK"));
+    assert!(bcbp.segments()[0].frequent_flyer_number() == Some("12345678"));
+    assert!(bcbp.quality_score() < 100);
+}
+
+#[test]
+fn segments_count_limits() {
+    let mut bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let leg = bcbp.segments()[0].clone();
+
+    for _ in 1 .. 9 {
+        bcbp.add_segment(leg.clone()).unwrap();
+    }
+
+    assert!(bcbp.segments_count() == 9);
+    assert!(bcbp.add_segment(leg).unwrap_err() == Error::SegmentsCount);
+    assert!(bcbp.expect_build().starts_with("M9"));
+
+    while bcbp.remove_segment(0).is_some() {}
+
+    assert!(bcbp.segments().is_empty());
+    assert!(bcbp.try_build() == Err(BuildError::SegmentsCount(0)));
+}
+
+#[test]
+fn build_errors() {
+    use iata::flight::FlightKey;
+
+    let date = date::from_ymd(2017, 2, 1).unwrap();
+    let mut template = PassTemplate::new(FlightKey::new("SU", 1234, None, date, "JFK"), "SVO");
+
+    let bcbp = template.issue("SMITH/JOHN", "ABCDEFGH", "", 0).unwrap();
+    assert!(bcbp.try_build() == Err(BuildError::FieldTooLong(7, "ABCDEFGH".into())));
+
+    let bcbp = template.issue("SMITH/JOHN", "AB-123", "", 0).unwrap();
+    let error = bcbp.try_build().unwrap_err();
+    assert!(error == BuildError::InvalidCharset(7, "AB-123".into()));
+    assert!(error.to_string() == "\"AB-123\" contains characters outside of the charset of item 7 (Operating carrier PNR Code)");
+
+    let mut template = PassTemplate::new(FlightKey::new("SU", 123456, None, date, "JFK"), "SVO");
+    let bcbp = template.issue("SMITH/JOHN", "ABCDEF", "", 0).unwrap();
+    assert!(bcbp.try_build() == Err(BuildError::FieldTooLong(43, "123456 ".into())));
+
+    let mut template = PassTemplate::new(FlightKey::new("SU", 1234, None, date, "JFK"), " ");
+    let bcbp = template.issue("SMITH/JOHN", "ABCDEF", "", 0).unwrap();
+    assert!(bcbp.try_build() == Err(BuildError::MissingMandatory(38)));
+}
+
+#[test]
+fn same_flight() {
+    use iata::bcbp::index::BoardingIndex;
+
+    let leg = |data: &str| BCBP::from(&format!("M1JOHN/SMITH          E{}", data)).unwrap().segments()[0].clone();
+
+    let a = leg("ABCDEF JFKSVOSU 0010 001Y001Z0007 000");
+    let b = leg("GHIJKL JFKSVOSU 10   001C002A0008 100");
+
+    assert!(a.same_flight(&b) && b.same_flight(&a));
+    assert!(!a.same_flight(&leg("ABCDEF JFKSVOSU 0010A001Y001Z0007 000")));
+    assert!(!a.same_flight(&leg("ABCDEF JFKSVOSU 0010 002Y001Z0007 000")));
+    assert!(!a.same_flight(&leg("ABCDEF JFKLEDSU 0010 001Y001Z0007 000")));
+    assert!(!a.same_flight(&leg("ABCDEF JFKSVOAF 0010 001Y001Z0007 000")));
+
+    // Boarding the same passenger twice is caught however the flight number is written
+    let mut index = BoardingIndex::new();
+    assert!(index.insert(BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSU 0010 001Y001Z0007 000").unwrap()));
+    assert!(!index.insert(BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSU 10   001Y001Z0007 000").unwrap()));
+}
+
+#[test]
+fn issue_date() {
+    let date = IssueDate::parse("6235").unwrap();
+
+    assert!(date.year_digit() == 6);
+    assert!(date.day_of_year() == 235);
+    assert!(date.to_string() == "6235");
+    assert!(date.resolve(2016) == Ok(date::from_ymd(2016, 8, 22).unwrap()));
+    assert!(date.resolve(2025) == Ok(date::from_ymd(2016, 8, 22).unwrap()));
+    assert!(date.resolve(2026) == Ok(date::from_ymd(2026, 8, 23).unwrap()));
+    assert!(date.resolve(2015) == Ok(date::from_ymd(2006, 8, 23).unwrap()));
+
+    assert!(IssueDate::parse("7366").unwrap().resolve(2020) == Err(DateError::InvalidDay { year: 2017, day: 366 }));
+    assert!(IssueDate::parse("    ").is_none());
+
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ").unwrap();
+    assert!(bcbp.issue_date() == IssueDate::parse("0276"));
+}
+
+#[test]
+fn bag_tags() {
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK 05551234560012A55559467513990 SU SU 12345678             09         ").unwrap();
+
+    assert!(bcbp.bag_tags().len() == 1);
+    assert!(bcbp.bag_tags()[0].expand().len() == 2);
+}
+
+#[test]
+fn quality_score() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+
+    assert!(BCBP::from(src).unwrap().quality_score() == 100);
+
+    let bcbp = BCBP::from_with(&src.replace("ABCDEF ", " abcdef"), ParseOptions::lenient()).unwrap();
+    assert!(bcbp.warnings() == [Warning::Padding { item: 7, value: " abcdef".into() }]);
+    assert!(bcbp.quality_score() == 95);
+
+    let bcbp = BCBP::from_with(&format!("{}XYZ", src.replace("JFKSVO", "JFK5VO")), ParseOptions::lenient()).unwrap();
+    assert!(bcbp.quality_score() == 65);
+}
+
+#[test]
+fn error_context() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 0X0";
+
+    let e = BCBP::from_with(src, ParseOptions::strict()).unwrap_err();
+    assert!(e.error == Error::InvalidSizeField(6, *b"X0"));
+    assert!(e.offset == 58);
+    assert!(e.snippet.is_none());
+
+    let e = BCBP::from_with(src, ParseOptions::strict().with_snippets(true)).unwrap_err();
+    assert!(e.snippet.as_ref().map(|s| s.as_ref()) == Some("1Y001Z0007 0X0"));
+
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JF1SVOSU 1234A001Y001Z0007 000";
+    let e = BCBP::from_with(src, ParseOptions::strict().with_snippets(true)).unwrap_err();
+    assert!(e.error == Error::InvalidCharset(26));
+    assert!(e.offset == 30);
+    assert!(e.snippet.as_ref().map(|s| s.as_ref()) == Some("*   E****** JF1SVOSU 123"));
+}
+
+#[test]
+fn invalid_size_field() {
+    let src = "M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 0";
+
+    for &(conditional, item, value) in [("Y0", 6, *b"Y0"), ("04>6Z0", 10, *b"Z0"), ("06>600?!", 17, *b"?!")].iter() {
+        let data = format!("{}{}", src, conditional);
+        let error = Error::InvalidSizeField(item, value);
+
+        assert!(BCBP::from(&data).unwrap_err() == error);
+        assert!(validate_only(data.as_bytes()).err() == Some(error));
+    }
+}
+
+#[test]
+fn error_item() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 0X0";
+    let e = BCBP::from_with(src, ParseOptions::strict()).unwrap_err();
+
+    assert!(e.item == Some(6) && e.raw == "X0");
+    assert!(e.item_name() == Some("Field size of variable size field"));
+    assert!(e.to_string() == "size item 6 (Field size of variable size field) must be 2 hex digits, found \"X0\" at offset 58");
+
+    // Offsets point at the start of the item, private items are masked
+    let e = BCBP::from_with(&src.replace("JOHN/", "J0HN/"), ParseOptions::strict()).unwrap_err();
+    assert!(e.error == Error::Name && e.item == Some(11));
+    assert!(e.offset == 2 && e.raw == "********** ******   ");
+
+    let e = BCBP::from_with(&src.replace("M1", "X1"), ParseOptions::strict()).unwrap_err();
+    assert!(e.item == Some(1) && e.offset == 0 && e.raw == "X");
+
+    // Bytes and characters differ after non ASCII data
+    let e = BCBP::from_with(&src.replace("JORDAN ", "JÖRDAN ").replace("JFKSVO", "JF1SVO").replace("0X0", "000"), ParseOptions::strict()).unwrap_err();
+    assert!(e.item == Some(26) && e.raw == "JF1");
+    assert!(e.offset == 30 && e.byte_offset == 31);
+
+    // Plain parsing locates conditional errors as well
+    let e = BCBP::from(&src.replace("0X0", "002X6")).unwrap_err();
+    assert!(e == Error::CoditionalData);
+    assert!(e.offset == 60 && e.raw == "X6");
+
+    // Unique items are located on their own, not at the start of their section
+    let src = "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 02X6BSK              2A55559467513990 SU SU 12345678             09         ";
+    let e = BCBP::from_with(src, ParseOptions::strict()).unwrap_err();
+    assert!(e == Error::InvalidCharset(22) && e.item == Some(22));
+    assert!(e.offset == 67 && e.raw == "02X6");
+
+    let e = BCBP::from_with(&src.replace("02X6BSK", "0276B$K"), ParseOptions::strict()).unwrap_err();
+    assert!(e == Error::InvalidCharset(21) && e.offset == 72 && e.raw == "$K ");
+}
+
+#[test]
+fn ff_tier() {
+    let src = "M3JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09 *G      ABCDEF SVOFRASU 5678 135Y013A0012 3372A55559467513990 SU KL 12345678             09 *X      ABCDEF FRAJFKSU 9876 231Y022F0052 3372A55559467513990 SU SU 12345678             09 *G      ";
+    let bcbp = BCBP::from(src).unwrap();
+
+    assert!(bcbp.segments()[0].ff_tier() == Some(FfTier::StarGold));
+    assert!(bcbp.segments()[1].ff_tier() == Some(FfTier::SkyTeamElitePlus));
+    assert!(bcbp.segments()[1].ff_tier().unwrap().alliance() == Alliance::SkyTeam);
+    // Program outside of the alliance tables
+    assert!(bcbp.segments()[2].ff_tier().is_none());
+
+    assert!(FfTier::decode(Alliance::StarAlliance, "*G") == Some(FfTier::StarGold));
+    assert!(FfTier::decode(Alliance::StarAlliance, "*GOLD").is_none());
+    assert!(Alliance::of("lh ") == Some(Alliance::StarAlliance));
+
+    let registry = ExtensionRegistry::builtin();
+    assert!(registry.is_registered("KL"));
+    assert!(registry.decode(&bcbp.segments()[0]).unwrap()["ff_tier"] == "Star Alliance Gold");
+    assert!(registry.decode(&bcbp.segments()[1]).is_none());
+}
+
+#[test]
+#[allow(deprecated)]
+fn fallible_names() {
+    let mut bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A366Y001Z0007 000").unwrap();
+    let clock = FixedClock(date::from_ymd(2017, 6, 1).unwrap());
+
+    assert!(bcbp.build() == Ok(bcbp.expect_build()));
+    assert!(bcbp.segments()[0].flight_date(2017) == Err(DateError::InvalidDay { year: 2017, day: 366 }));
+    assert!(bcbp.segments()[0].flight_date_current_year_with(&clock).is_err());
+
+    bcbp.remove_segment(0);
+    assert!(bcbp.build() == Err("pass must have 1 to 9 legs, got 0".into()));
+}
+
+#[test]
+#[should_panic(expected = "pass must have 1 to 9 legs")]
+fn build_panics() {
+    let mut bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    bcbp.remove_segment(0);
+    bcbp.expect_build();
+}
+
+#[test]
+#[should_panic(expected = "year 2017 has no day 366")]
+fn flight_date_panics() {
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A366Y001Z0007 000").unwrap();
+    assert!(bcbp.segments()[0].expect_flight_date(2016) == date::from_ymd(2016, 12, 31).unwrap());
+    bcbp.segments()[0].expect_flight_date(2017);
+}
+
+#[test]
+fn capacity() {
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ").unwrap();
+    let airline_data = bcbp.segments()[0].airline_data().unwrap().len();
+
+    assert!(bcbp.encoded_len_for_version(0) == 60);
+    assert!(bcbp.encoded_len_for_version(5) == 60 + 54 + 43 + airline_data);
+    assert!(bcbp.encoded_len_for_version(6) == bcbp.encoded_len_for_version(5) + 1);
+
+    assert!(bcbp.check_capacity(6, 1850).is_none());
+
+    let warning = bcbp.check_capacity(6, 160).unwrap();
+    assert!(warning == CapacityWarning { version: 6, len: 158 + airline_data, capacity: 160 });
+    assert!(warning.to_string() == format!("pass takes {} characters with conditional version 6, the symbol holds 160", 158 + airline_data));
+}
+
+#[test]
+fn template() {
+    use iata::flight::FlightKey;
+
+    let key = FlightKey::new("SU", 1234, Some('A'), date::from_ymd(2017, 2, 1).unwrap(), "JFK");
+    let mut template = PassTemplate::new(key.clone(), "SVO").with_pax_status(PaxStatus::NotCheckedIn);
+
+    let bcbp = template.issue("john/smith jordan", "abcdef", "001Z", 7).unwrap();
+    assert!(bcbp.expect_build() == "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A032Y001Z0007 000");
+    assert!(BCBP::from(&bcbp.expect_build()).is_ok());
+
+    let results: Vec<_> = template.issue_all(vec![
+        ("DOE/JANE", "GHIJKL", "1Z", 8),
+        ("DOE/JANE", "GHIJKL", "2A", 7),
+        ("12/JANE", "GHIJKL", "2A", 8),
+        ("DOE/JANE", "GHIJKL", "2A", 8),
+        ("DOE/JOHN", "GHIJKL", "", 0),
+        ("DOE/JILL", "GHIJKL", "", 0),
+    ]).map(|r| r.err()).collect();
+
+    assert!(results == [
+        Some(TemplateError::DuplicateSeat("1Z".into())),
+        Some(TemplateError::DuplicateSequence(7)),
+        Some(TemplateError::Name),
+        None,
+        None,
+        None,
+    ]);
+    assert!(template.seats_issued() == 2);
+    assert!(template.flight() == &key);
+}
+
+#[test]
+fn sequence_allocation() {
+    use iata::flight::FlightKey;
+
+    let date = date::from_ymd(2017, 2, 1).unwrap();
+    let su100 = FlightKey::new("SU", 100, None, date, "JFK");
+    let su102 = FlightKey::new("SU", 102, None, date, "JFK");
+
+    let mut allocator = MemoryAllocator::new();
+    assert!(allocator.allocate(&su100) == Ok(1));
+    assert!(allocator.allocate(&su100) == Ok(2));
+    assert!(allocator.allocate(&su102) == Ok(1));
+    assert!(allocator.last(&su100) == Some(2));
+
+    allocator.resume(su102.clone(), MAX_SEQUENCE);
+    assert!(allocator.allocate(&su102) == Err(SequenceError::Exhausted));
+
+    let mut template = PassTemplate::new(su100.clone(), "SVO");
+
+    let bcbp = template.issue_next(&mut allocator, "JOHN/SMITH", "ABCDEF", "1A").unwrap();
+    assert!(bcbp.segments()[0].sequence() == 3);
+
+    assert!(template.issue_next(&mut allocator, "JANE/DOE", "ABCDEF", "1A").err() == Some(TemplateError::DuplicateSeat("1A".into())));
+    assert!(template.issue_next(&mut allocator, "JANE/DOE", "ABCDEF", "1B").unwrap().segments()[0].sequence() == 4);
+
+    let mut exhausted = PassTemplate::new(su102, "SVO");
+    assert!(exhausted.issue_next(&mut allocator, "JANE/DOE", "ABCDEF", "1B").err() == Some(TemplateError::Sequence(SequenceError::Exhausted)));
+}
+
+#[test]
+fn offsets() {
+    use iata::spec;
+
+    let src = "M3JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09         ABCDEF SVOFRASU 5678 135Y013A0012 3372A55559467513990 SU SU 12345678             09         ABCDEF FRAJFKSU 9876 231Y022F0052 3372A55559467513990 SU SU 12345678             09         ";
+    let bcbp = BCBP::from(src).unwrap();
+
+    assert!(spec::mandatory_offset(5, 100, 11) == Some(2));
+    assert!(spec::mandatory_offset(0, 0, 8).is_none());
+
+    let mut conditional = 0;
+    for (leg, segment) in bcbp.segments().iter().enumerate() {
+        let from = spec::mandatory_offset(leg, conditional, 26).unwrap();
+        assert!(&src[from .. from + 3] == segment.src_airport());
+
+        let carrier = spec::mandatory_offset(leg, conditional, 42).unwrap();
+        assert!(src[carrier .. carrier + 3].trim() == segment.airline());
+
+        let size = spec::mandatory_offset(leg, conditional, 6).unwrap();
+        conditional += usize::from_str_radix(&src[size .. size + 2], 16).unwrap();
+    }
+
+    assert!(spec::leg_offset(3, conditional) == src.len());
+}
+
+#[test]
+fn reissue() {
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000^10AGIWVC5EH7J").unwrap();
+
+    let moved = bcbp.with_new_seat("012c").unwrap();
+    assert!(moved.expect_build() == "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y012C0007 000");
+    assert!(bcbp.segments()[0].seat() == "1Z");
+
+    let resequenced = moved.with_new_sequence(12345).unwrap();
+    assert!(resequenced.expect_build() == "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y012C12345000");
+    assert!(BCBP::from(&resequenced.expect_build()).unwrap().segments()[0].sequence() == 12345);
+
+    assert!(bcbp.with_new_seat("1234A").err() == Some(BuildError::Seat("1234A".into())));
+    assert!(bcbp.with_new_seat("A1").err() == Some(BuildError::Seat("A1".into())));
+    assert!(bcbp.with_new_sequence(100000).err() == Some(BuildError::Sequence(100000)));
+    assert!(BCBP::new().with_new_sequence(1).err() == Some(BuildError::SegmentsCount(0)));
+}
+
+#[test]
+fn reissue_conditional() {
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 354>5180O 0276BSK 05551234560012A55559467513990 SK SK 12345678         09  *30600000K09^10AGIWVC5EH7J").unwrap();
+
+    let moved = BCBP::from(&bcbp.with_new_seat("14D").unwrap().expect_build()).unwrap();
+    let s = &moved.segments()[0];
+
+    assert!(s.seat() == "14D");
+    assert!(s.frequent_flyer_number() == Some("12345678"));
+    assert!(s.conditional() == bcbp.segments()[0].conditional());
+    assert!(s.airline_data() == Some("*30600000K09"));
+    assert!(moved.conditional_unique() == bcbp.conditional_unique());
+    assert!(moved.security().is_none());
+}
+
+#[test]
+fn round_trip() {
+    let samples = [
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ^10AGIWVC5EH7J",
+        "M3JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09         ABCDEF SVOFRASU 5678 135Y013A0012 3372A55559467513990 SU SU 12345678             09         ABCDEF FRAJFKSU 9876 231Y022F0052 3372A55559467513990 SU SU 12345678             09         ",
+        "M1JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 354>5180O 0276BSK 05551234560012A55559467513990 SK SK 12345678         09  *30600000K09^10AGIWVC5EH7J",
+    ];
+
+    for src in samples.iter() {
+        assert!(BCBP::from(src).unwrap().expect_build() == *src, "{}", src);
+    }
+}
+
+#[test]
+fn signed_data() {
+    let src = "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ^10AGIWVC5EH7J";
+    let bcbp = BCBP::from(src).unwrap();
+
+    // The signature covers the scanned data, not the items decoded from it
+    let mut changed = bcbp.clone();
+    changed.segments_mut()[0].add_item(118, "20K").unwrap();
+    assert!(changed.try_build() == Err(BuildError::SignedData));
+
+    let mut resigned = changed.clone();
+    resigned.set_security_data('1', "NEWSIGNATURE");
+    let built = resigned.expect_build();
+    assert!(built.contains("20K") && built.ends_with("^10CNEWSIGNATURE"));
+
+    changed.set_security(None);
+    assert!(!changed.expect_build().contains('^'));
+
+    let mut redacted = bcbp.clone();
+    redacted.redact(&RedactionRules { name: true, ..RedactionRules::none() });
+    assert!(redacted.security().is_none());
+}
+
+#[test]
+fn bag_tag_items() {
+    let src = format!("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 02B>6250O 0276BSK {:13}055512345600100", "");
+    let bcbp = BCBP::from(&src).unwrap();
+    let unique = bcbp.conditional_unique();
+
+    assert!(bcbp.bag_tags().len() == 1);
+    assert!(unique.bag_tag_numbers().is_none() && unique.second_non_consecutive_bag_tags().is_none());
+    assert!(unique.first_non_consecutive_bag_tags().unwrap().expand().len() == 2);
+
+    // A blank document type isn't a type
+    assert!(BCBP::from(&src.replace("0276BSK", "0276 SK")).unwrap().document_type().is_none());
+
+    // Written back as item 31, not as the first item of the section
+    let moved = bcbp.with_new_seat("2A").unwrap().expect_build();
+    assert!(moved.ends_with(&format!("02B>6250O 0276BSK {:13}055512345600100", "")));
+}
+
+#[test]
+fn add_item() {
+    let mut bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    bcbp.add_item(21, "SU").unwrap();
+    bcbp.segments_mut()[0].add_item(118, "20K").unwrap();
+
+    let built = bcbp.expect_build();
+    assert!(built == format!("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 03A>60B        SU 29{:38}20K", ""));
+    assert!(BCBP::from(&built).unwrap().conditional_version() == Some('6'));
+
+    bcbp.segments_mut()[0].remove_item(118);
+    bcbp.segments_mut()[0].add_item(236, "1234567890").unwrap();
+    let parsed = BCBP::from(&bcbp.expect_build()).unwrap();
+    assert!(parsed.segments()[0].frequent_flyer_number() == Some("1234567890"));
+
+    assert!(bcbp.add_item(118, "20K") == Err(BuildError::Item(118)));
+    assert!(bcbp.add_item(10, "00") == Err(BuildError::Item(10)));
+    assert!(bcbp.add_item(21, "SU12") == Err(BuildError::ItemValue(21, "SU12".into())));
+    assert!(bcbp.segments_mut()[0].add_item(4, "DATA") == Err(BuildError::Item(4)));
+
+    let (legacy, stripped) = bcbp.mandatory_only();
+    assert!(legacy.expect_build() == "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000");
+    assert!(stripped.items == vec![21] && stripped.leg_items == vec![(0, vec![236])]);
+}
+
+#[test]
+fn interline_issued() {
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ").unwrap();
+    assert!(bcbp.issuer_airline() == Some("SK"));
+    assert!(bcbp.is_interline_issued() == Some(true));
+
+    let mut own = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    assert!(own.issuer_airline().is_none() && own.is_interline_issued().is_none());
+
+    own.add_item(21, "SU").unwrap();
+    let own = BCBP::from(&own.expect_build()).unwrap();
+    assert!(own.issuer_airline() == Some("SU"));
+    assert!(own.is_interline_issued() == Some(false));
+}
+
+#[test]
+fn conditional_unique() {
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK 05551234560012A55559467513990 SU SU 12345678             09         ").unwrap();
+    let unique = bcbp.conditional_unique();
+
+    assert!(unique.version() == Some('5'));
+    assert!(unique.passenger_description() == Some(PassengerDescription::Adult));
+    assert!(unique.checkin_source() == Some(Source::AirportAgent));
+    assert!(unique.boarding_pass_source().is_none());
+    assert!(unique.issue_date() == IssueDate::parse("0276"));
+    assert!(unique.document_type() == Some('B'));
+    assert!(unique.issuer_airline() == Some("SK"));
+    assert!(unique.bag_tags().len() == 1 && unique.bag_tags() == bcbp.bag_tags());
+    assert!(!unique.is_empty());
+
+    let plain = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    assert!(plain.conditional_unique().is_empty());
+    assert!(bcbp.mandatory_only().0.conditional_unique().is_empty());
+}
+
+#[test]
+fn segment_conditional() {
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK 05551234560012A55559467513990 SU SU 12345678             09         ").unwrap();
+    let c = bcbp.segments()[0].conditional();
+
+    assert!(c.airline_code() == Some("555"));
+    assert!(c.document_number() == Some("5946751399"));
+    assert!(c.selectee() == Some('0'));
+    assert!(c.document_verification().is_none());
+    assert!(c.marketing_airline() == Some("SU"));
+    assert!(c.frequent_flyer_airline() == Some("SU"));
+    assert!(c.frequent_flyer_number() == Some("12345678"));
+    assert!(c.id_ad().is_none() && c.baggage_allowance().is_none());
+    assert!(c.fast_track().is_none());
+
+    let mut pass = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    assert!(pass.segments()[0].conditional().is_empty());
+
+    pass.segments_mut()[0].add_item(118, "2PC").unwrap();
+    pass.segments_mut()[0].add_item(254, "Y").unwrap();
+    let pass = BCBP::from(&pass.expect_build()).unwrap();
+    assert!(pass.segments()[0].conditional().baggage_allowance() == Some("2PC"));
+    assert!(pass.segments()[0].conditional().fast_track() == Some(true));
+}
+
+#[test]
+fn version_layout() {
+    let pass = |version: char| {
+        let src = format!("M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 030>{}002A5551234567890  SU SU 1234567890       20KY", version);
+        BCBP::from(&src).unwrap()
+    };
+
+    // Fast Track, item 254, is the only item the spec gates by version, it comes with version 6
+    let v5 = pass('5');
+    assert!(v5.version() == Some(Version::V5));
+    assert!(v5.segments()[0].conditional().baggage_allowance() == Some("20K"));
+    assert!(v5.segments()[0].conditional().fast_track().is_none());
+
+    let v6 = pass('6');
+    assert!(v6.version() == Some(Version::V6));
+    assert!(v6.segments()[0].conditional().baggage_allowance() == Some("20K"));
+    assert!(v6.segments()[0].conditional().fast_track() == Some(true));
+
+    // Once changed, a pass is written back in the layout of its version
+    let mut v5 = v5;
+    let mut v6 = v6;
+    v5.segments_mut()[0].add_item(118, "30K").unwrap();
+    v6.segments_mut()[0].add_item(118, "30K").unwrap();
+    assert!(v5.expect_build().ends_with("02F>500295551234567890  SU SU 1234567890       30K"));
+    assert!(v6.expect_build().ends_with("030>6002A5551234567890  SU SU 1234567890       30KY"));
+
+    assert!(Version::from('X') == Version::Other('X') && Version::Other('X').number().is_none());
+    assert!(pass('X').segments()[0].conditional().fast_track() == Some(true));
+}
+
+#[test]
+fn mandatory_only() {
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ^10AGIWVC5EH7J").unwrap();
+    let (legacy, stripped) = bcbp.mandatory_only();
+
+    assert!(legacy.expect_build() == "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 300");
+    assert!(legacy.conditional_version().is_none() && legacy.security().is_none());
+    assert!(legacy.segments()[0].frequent_flyer_number().is_none());
+    assert!(BCBP::from(&legacy.expect_build()).is_ok());
+
+    assert!(stripped.items == vec![9, 15, 12, 14, 22, 16, 21]);
+    assert!(stripped.leg_items == vec![(0, vec![142, 143, 18, 19, 20, 236, 4])]);
+    assert!(stripped.security && stripped.trailing == 0);
+    assert!(!stripped.is_empty());
+
+    let (_, nothing) = legacy.mandatory_only();
+    assert!(nothing.is_empty());
+    assert!(nothing.to_string() == "nothing");
+}
+
+#[test]
+fn validate_only_agrees() {
+    let valid = [
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "]Q3M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ^10AGIWVC5EH7J",
+        "M3JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09         ABCDEF SVOFRASU 5678 135Y013A0012 3372A55559467513990 SU SU 12345678             09         ABCDEF FRAJFKSU 9876 231Y022F0052 3372A55559467513990 SU SU 12345678             09         ",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 002>6",
+    ];
+
+    let invalid = [
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007",
+        "X1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M0JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M112/SMITH JORDAN     EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M1JOHN/SMITH JORDAN   #ABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A0X1Y001Z0007 000",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 0ZZ",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 010",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 02X6",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000^1",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000^10AGIWVC",
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000XYZ",
+    ];
+
+    for src in valid.iter() {
+        let summary = validate_only(src.as_bytes()).expect(src);
+        let bcbp = BCBP::from(src).unwrap();
+        let leg = &bcbp.segments()[0];
+
+        assert!(summary.segments_count() == bcbp.segments_count());
+        assert!(summary.src_airport() == leg.src_airport() && summary.dst_airport() == leg.dst_airport());
+        assert!(summary.airline() == leg.airline() && summary.flight_code() == leg.flight_code());
+        assert!(u32::from(summary.flight_day()) == leg.flight_day());
+        assert!(summary.seat() == leg.seat() && summary.sequence() == leg.sequence());
+    }
+
+    for src in invalid.iter() {
+        assert!(validate_only(src.as_bytes()).err() == BCBP::from(src).err().map(|e| e.error), "{}", src);
+    }
+}
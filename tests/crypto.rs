@@ -0,0 +1,92 @@
+#![cfg(feature = "crypto")]
+
+extern crate iata;
+
+use iata::bcbp::BCBP;
+
+#[test]
+fn hashed_identity() {
+    let src = "M3JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09         ABCDEF SVOFRASU 5678 135Y013A0012 3372A55559467513990 SU SU 12345678             09         ABCDEF FRAJFKSU 9876 231Y022F0052 3372A55559467513990 SU SU 12345678             09         ";
+    let bcbp = BCBP::from(src).unwrap();
+
+    // HMAC-SHA256 of "JOHN/SMITH\x1fABCDEF\x1fSU12345678"
+    let expected = "83f3cc1c2aec9a897de1d05cc1ae6a8e4719829819e7bc6b4be9205229d7dba8";
+
+    assert_eq!(bcbp.hashed_identity(b"secret"), expected);
+    assert_ne!(bcbp.hashed_identity(b"other"), expected);
+
+    let lower = BCBP::from(&src.replace("JOHN/SMITH", "john/smith")).unwrap();
+    assert_eq!(lower.hashed_identity(b"secret"), expected);
+}
+
+#[test]
+fn boarding_token() {
+    use iata::bcbp::token_window;
+
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let window = token_window(1483272000, 300);
+    assert_eq!(window, 4944240);
+
+    // HMAC-SHA256 of "SU1234A\x1f1\x1fJFK\x1f7\x1f4944240", first 50 bits in base32
+    let token = bcbp.boarding_token(b"secret", window).unwrap();
+    assert_eq!(token, "87PRY5Z9R8");
+
+    assert_eq!(bcbp.boarding_token(b"secret", window), Some(token.clone()));
+    assert_ne!(bcbp.boarding_token(b"other", window), Some(token.clone()));
+    assert_ne!(bcbp.boarding_token(b"secret", window + 1), Some(token.clone()));
+
+    assert!(bcbp.verify_boarding_token(b"secret", window, &token.to_lowercase()));
+    assert!(bcbp.verify_boarding_token(b"secret", window + 1, &token));
+    assert!(!bcbp.verify_boarding_token(b"secret", window + 2, &token));
+
+    // Bound to the sequence number, not the passenger name
+    let other_seq = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0008 000").unwrap();
+    assert!(!other_seq.verify_boarding_token(b"secret", window, &token));
+
+    let other_name = BCBP::from("M1DOE/JANE            EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    assert!(other_name.verify_boarding_token(b"secret", window, &token));
+}
+
+#[test]
+fn boarding_log() {
+    use iata::audit::{AuditError, BoardingLog};
+    use iata::scan::ScanEvent;
+
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let mut log = BoardingLog::new();
+    assert_eq!(log.head(), "0".repeat(64));
+
+    let first = log.append(&ScanEvent::new(bcbp.clone(), 1483272000, "GATE-B12-1")).unwrap().clone();
+    log.append(&ScanEvent::new(bcbp.clone(), 1483272060, "GATE-B12-1").with_location("JFK/B12")).unwrap();
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.entries()[1].prev_hash, first.hash);
+    assert!(log.verify().is_ok());
+
+    match log.append(&ScanEvent::new(bcbp.clone(), 1483272120, "GATE\tB12")) {
+        Err(AuditError::Field("device id")) => {},
+        other => panic!("{:?}", other),
+    }
+
+    let mut file = Vec::new();
+    log.write_to(&mut file).unwrap();
+    let text = String::from_utf8(file).unwrap();
+    assert_eq!(text.lines().count(), 2);
+    assert_eq!(BoardingLog::read_from(text.as_bytes()).unwrap(), log);
+
+    let edited = text.replace("1483272060", "1483272059");
+    match BoardingLog::read_from(edited.as_bytes()) {
+        Err(AuditError::Hash { index: 1 }) => {},
+        other => panic!("{:?}", other),
+    }
+
+    let dropped: String = text.lines().skip(1).map(|l| format!("{}\n", l)).collect();
+    match BoardingLog::read_from(dropped.as_bytes()) {
+        Err(AuditError::Chain { index: 0 }) => {},
+        other => panic!("{:?}", other),
+    }
+
+    match BoardingLog::read_from(&b"garbage\n"[..]) {
+        Err(AuditError::Malformed { line: 1 }) => {},
+        other => panic!("{:?}", other),
+    }
+}
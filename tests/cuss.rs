@@ -0,0 +1,34 @@
+#![cfg(feature = "serde")]
+
+extern crate iata;
+extern crate serde_json;
+
+use iata::bagtag::LicensePlate;
+use iata::cuss::*;
+
+#[test]
+fn json_round_trip() {
+    let scan = CussEvent::scan("KIOSK-12", 1_500_000_000, "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000");
+    let tag = CussEvent::new("BAGDROP-3", 1_500_000_060, CussEventKind::TagPrinted(LicensePlate::parse("0555123456").unwrap()));
+
+    let json = serde_json::to_string(&scan).unwrap();
+    let back: CussEvent = serde_json::from_str(&json).unwrap();
+
+    match back.kind {
+        CussEventKind::PassScanned(bcbp) => assert_eq!(bcbp.name(), "JOHN/SMITH JORDAN"),
+        _ => panic!(),
+    }
+
+    let json = serde_json::to_string(&tag).unwrap();
+    let back: CussEvent = serde_json::from_str(&json).unwrap();
+
+    match back.kind {
+        CussEventKind::TagPrinted(plate) => assert_eq!(plate.to_string(), "0555123456"),
+        _ => panic!(),
+    }
+
+    match CussEvent::scan("KIOSK-12", 0, "garbage").kind {
+        CussEventKind::PassRejected { .. } => (),
+        _ => panic!(),
+    }
+}
@@ -0,0 +1,72 @@
+extern crate iata;
+
+use iata::date;
+use iata::datetime::*;
+
+#[test]
+fn ddmmm() {
+    let day = date::from_ymd(2024, 2, 29).unwrap();
+
+    assert!(format_ddmmm(&day) == "29FEB");
+    assert!(format_ddmmmyy(&day) == "29FEB24");
+    assert!(format_ddmmmyy(&date::from_ymd(2009, 1, 1).unwrap()) == "01JAN09");
+
+    assert!(parse_ddmmm("29FEB", 2024) == Some(day));
+    assert!(parse_ddmmm("29FEB", 2025).is_none());
+    assert!(parse_ddmmmyy("29feb24") == Some(day));
+
+    assert!(parse_month_day("12AUG") == Some((8, 12)));
+    assert!(parse_month_day("00AUG").is_none());
+    assert!(parse_month_day("32AUG").is_none());
+    assert!(parse_month_day("1AUG").is_none());
+    assert!(parse_month_day("+1AUG").is_none());
+    assert!(parse_month_day("12ABC").is_none());
+    assert!(parse_ddmmmyy("00XXX00").is_none());
+    assert!(parse_ddmmmyy("12AUG2").is_none());
+    assert!(month("dec") == Some(12));
+}
+
+#[test]
+fn wire_times() {
+    let time = WireTime::parse("0905").unwrap();
+    assert!((time.hour(), time.minute(), time.minutes()) == (9, 5, 545));
+    assert!(time.day_offset() == 0 && time.to_string() == "0905");
+
+    let midnight = WireTime::parse("2400").unwrap();
+    assert!(midnight.minutes() == 0 && midnight.day_offset() == 1);
+    assert!(midnight.hhmm() == "0000");
+    assert!(midnight != WireTime::parse("0000").unwrap());
+
+    assert!(WireTime::parse("2359").is_some());
+    assert!(WireTime::parse("2401").is_none());
+    assert!(WireTime::parse("1260").is_none());
+    assert!(WireTime::parse("930").is_none());
+    assert!(WireTime::parse("+930").is_none());
+    assert!(WireTime::new(24, 0).is_none());
+}
+
+#[test]
+fn day_offsets() {
+    let late = TimeWithOffset::parse("0130/1").unwrap();
+    assert!(late == TimeWithOffset::parse("0130+1").unwrap());
+    assert!(late.time() == WireTime::new(1, 30).unwrap() && late.day_offset() == 1);
+    assert!(late.minutes() == 24 * 60 + 90);
+    assert!(late.to_string() == "0130+1" && late.ssm() == "0130/1");
+
+    let early = TimeWithOffset::parse("2350/-1").unwrap();
+    assert!(early == TimeWithOffset::parse("2350-1").unwrap());
+    assert!(early.minutes() == -10 && early.to_string() == "2350-1" && early.ssm() == "2350/-1");
+
+    let midnight = TimeWithOffset::parse("2400").unwrap();
+    assert!(midnight == TimeWithOffset::parse("0000/1").unwrap());
+    assert!(TimeWithOffset::parse("2400/1").unwrap().day_offset() == 2);
+    assert!(TimeWithOffset::parse("1200").unwrap().to_string() == "1200");
+
+    let day = date::from_ymd(2024, 12, 31).unwrap();
+    assert!(late.date_from(&day) == date::from_ymd(2025, 1, 1).unwrap());
+    assert!(early.date_from(&day) == date::from_ymd(2024, 12, 30).unwrap());
+
+    for bad in ["0130/", "0130/12", "0130+-1", "0130--1", "0130/+1", "0130*1", "130/1", "2500/1"].iter() {
+        assert!(TimeWithOffset::parse(bad).is_none(), "{}", bad);
+    }
+}
@@ -0,0 +1,22 @@
+extern crate iata;
+
+use iata::bcbp::BCBP;
+use iata::diagnostics::{self, SELF_TEST_CHECKSUM, SELF_TEST_DATA};
+
+#[test]
+fn self_test_pass() {
+    let pass = diagnostics::self_test_pass();
+
+    assert!(pass.expect_build() == SELF_TEST_DATA);
+    assert!(pass.name() == "TEST/PRINTER");
+    assert!(pass.segments()[0].airline() == "XX");
+    assert!(diagnostics::is_self_test(&pass));
+    assert!(!diagnostics::is_self_test(&BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap()));
+
+    assert!(diagnostics::checksum(SELF_TEST_DATA) == SELF_TEST_CHECKSUM);
+    assert!(diagnostics::checksum("123456789") == 0xCBF4_3926);
+    assert!(diagnostics::checksum("") == 0);
+
+    assert!(diagnostics::verify_loopback(SELF_TEST_DATA));
+    assert!(!diagnostics::verify_loopback(&SELF_TEST_DATA[.. 59]));
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "emissions")]
+
+extern crate iata;
+
+use iata::bcbp::BCBP;
+use iata::emissions::*;
+use iata::station::StationTable;
+
+#[test]
+fn cabins() {
+    assert_eq!(Cabin::from_compartment('Y'), Cabin::Economy);
+    assert_eq!(Cabin::from_compartment('w'), Cabin::PremiumEconomy);
+    assert_eq!(Cabin::from_compartment('J'), Cabin::Business);
+    assert_eq!(Cabin::from_compartment('F'), Cabin::First);
+}
+
+#[test]
+fn per_passenger_co2() {
+    let model = EmissionsModel::default();
+
+    let economy = model.co2_kg(1000.0, Cabin::Economy);
+    assert!((economy - 72.96).abs() < 0.01);
+    assert!((model.co2_kg(1000.0, Cabin::Business) - economy * 4.0).abs() < 1e-9);
+
+    let empty = EmissionsModel { load_factor: 0.0, ..EmissionsModel::default() };
+    assert_eq!(empty.co2_kg(1000.0, Cabin::Economy), 0.0);
+
+    let stations = StationTable::builtin();
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let leg = &bcbp.segments()[0];
+    assert!((model.segment_co2_kg(&stations, leg).unwrap() - 545.8).abs() < 1.0);
+
+    let unknown = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKXXXSU 1234A001Y001Z0007 000").unwrap();
+    assert!(model.segment_co2_kg(&stations, &unknown.segments()[0]).is_none());
+}
@@ -0,0 +1,156 @@
+extern crate iata;
+
+use std::collections::HashSet;
+
+use iata::date;
+use iata::bcbp::BCBP;
+use iata::flight::{self, CityPair, FlightKey, MarketSegment};
+use iata::flight_status::MvtHeader;
+use iata::ssim::{Days, FlightId, Leg, SegmentData};
+
+#[test]
+fn flight_keys() {
+    let pass = |code: &str| BCBP::from(&format!("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU {}032Y001Z0007 000", code)).unwrap();
+
+    let key = FlightKey::from_segment(&pass("0100A").segments()[0], 2017).unwrap();
+    assert!(key.number == 100 && key.suffix == Some('A'));
+    assert!(key.date == date::from_ymd(2017, 2, 1).unwrap());
+    assert!(key.flight_id() == FlightId::new("SU", 100).with_suffix('A'));
+    assert!(key.to_string() == "SU100A/2017-02-01/JFK");
+
+    assert!(FlightKey::from_segment(&pass("10 A ").segments()[0], 2017).is_none());
+    assert!(FlightKey::from_segment(&pass("     ").segments()[0], 2017).is_none());
+
+    let mut leg = Leg::new("SU", 100, 1, 1);
+    leg.set_departure("JFK", "1300", "-0500");
+    leg.set_period(date::from_ymd(2017, 1, 1), date::from_ymd(2017, 3, 31));
+    leg.set_days(Days::parse("  3    "));
+
+    // February 1st 2017 is a Wednesday
+    let wednesday = date::from_ymd(2017, 2, 1).unwrap();
+    let plain = FlightKey::from_segment(&pass("0100 ").segments()[0], 2017).unwrap();
+    assert!(FlightKey::from_leg(&leg, wednesday) == Some(plain.clone()));
+    assert!(FlightKey::from_leg(&leg, date::add_days(&wednesday, 1)).is_none());
+    assert!(FlightKey::from_leg(&leg, date::from_ymd(2017, 4, 5).unwrap()).is_none());
+
+    let keys: HashSet<_> = vec![key, plain.clone(), plain].into_iter().collect();
+    assert!(keys.len() == 2);
+}
+
+#[test]
+fn flight_key_sources() {
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 0100 032Y001Z0007 000").unwrap();
+    let from_pass = FlightKey::from_segment(&bcbp.segments()[0], 2017).unwrap();
+
+    let mut leg = Leg::new("SU", 100, 1, 1);
+    leg.set_departure("JFK", "1300", "-0500");
+    let from_schedule = FlightKey::from_leg(&leg, date::from_ymd(2017, 2, 1).unwrap()).unwrap();
+
+    let header = MvtHeader::parse("SU100/01.VPBAA.JFK").unwrap();
+    assert!(header.registration == "VPBAA" && header.day == 1);
+    let from_mvt = FlightKey::from_mvt(&header, 2017, 2).unwrap();
+
+    assert!(from_pass == from_schedule && from_pass == from_mvt);
+    assert!(FlightKey::from_mvt(&MvtHeader::parse("SU100A/01.VPBAA.JFK").unwrap(), 2017, 2).unwrap().suffix == Some('A'));
+
+    assert!(FlightKey::from_mvt(&MvtHeader::parse("SU100/31.VPBAA.JFK").unwrap(), 2017, 2).is_none());
+    assert!(MvtHeader::parse("SU100/1.VPBAA.JFK").is_none());
+    assert!(MvtHeader::parse("SU100/01.VPBAA").is_none());
+}
+
+#[test]
+fn market_segments() {
+    let leg = |number, variation, sequence, from: &str, to: &str| {
+        let mut leg = Leg::new("SU", number, variation, sequence);
+        leg.set_departure(from, "1000", "+0300");
+        leg.set_arrival(to, "1200", "+0300");
+        leg
+    };
+
+    let legs = vec![
+        leg(100, 1, 3, "LED", "KGD"),
+        leg(100, 1, 1, "JFK", "SVO"),
+        leg(100, 1, 2, "SVO", "LED"),
+        leg(100, 2, 1, "JFK", "SVO"),
+        leg(200, 1, 1, "SVO", "AER"),
+        leg(200, 1, 2, "LED", "AER"),
+    ];
+
+    let segments = MarketSegment::derive(&legs);
+    let names: Vec<_> = segments.iter().map(|s| s.to_string()).collect();
+
+    assert!(names == vec![
+        "SU100 JFK-SVO", "SU100 JFK-LED", "SU100 JFK-KGD",
+        "SU100 SVO-LED", "SU100 SVO-KGD", "SU100 LED-KGD",
+        "SU100 JFK-SVO",
+        "SU200 SVO-AER", "SU200 LED-AER",
+    ]);
+
+    assert!(segments[2].legs_count() == 3 && segments[2].is_multi_leg());
+    assert!((segments[4].first_leg, segments[4].last_leg) == (2, 3));
+    assert!(segments[6].itinerary_variation == 2 && !segments[6].is_multi_leg());
+}
+
+#[test]
+fn city_pairs() {
+    let pair = CityPair::parse("jfk-SVO").unwrap();
+    assert!(pair == CityPair::new("JFK", "SVO"));
+    assert!(pair.to_string() == "JFK-SVO");
+    assert!(pair.reversed().to_string() == "SVO-JFK");
+    assert!(pair != pair.reversed() && pair.same_airports(&pair.reversed()));
+    assert!(CityPair::parse("JFK").is_none() && CityPair::parse("JFK-SVO-LED").is_none() && CityPair::parse("-SVO").is_none());
+
+    let country = |airport: &str| match airport {
+        "SVO" | "LED" => Some("RU"),
+        "JFK"         => Some("US"),
+        _             => None,
+    };
+    assert!(CityPair::new("SVO", "LED").is_domestic(country) == Some(true));
+    assert!(pair.is_domestic(country) == Some(false));
+    assert!(CityPair::new("SVO", "XXX").is_domestic(country).is_none());
+
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 0100 032Y001Z0007 000").unwrap();
+    assert!(bcbp.segments()[0].city_pair() == pair);
+
+    let mut leg = Leg::new("SU", 100, 1, 1);
+    leg.set_departure("JFK", "1300", "-0500");
+    leg.set_arrival("SVO", "0600", "+0300");
+    assert!(leg.city_pair() == pair);
+    assert!(MarketSegment::derive(&[leg])[0].city_pair() == pair);
+}
+
+#[test]
+fn marketing_flights() {
+    let mut bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 0100 032Y001Z0007 000").unwrap();
+    bcbp.segments_mut()[0].add_item(19, "KL").unwrap();
+    let bcbp = BCBP::from(&bcbp.expect_build()).unwrap();
+    let segment = &bcbp.segments()[0];
+    assert!(segment.marketing_airline() == Some("KL"));
+
+    let mut leg = Leg::new("SU", 100, 1, 1);
+    leg.set_departure("JFK", "1300", "-0500");
+    leg.set_arrival("SVO", "0600", "+0300");
+
+    let mut other = Leg::new("SU", 100, 1, 2);
+    other.set_departure("SVO", "0800", "+0300");
+    other.set_arrival("LED", "0930", "+0300");
+
+    let data = vec![
+        SegmentData::new(&leg, 10, "AF 4567/KL 9876/SU 100"),
+        SegmentData::new(&leg, 10, "AF4567/DL30A"),
+        SegmentData::new(&leg, 2, "AF"),
+        SegmentData::new(&other, 10, "AY 777"),
+        SegmentData::new(&Leg::new("SU", 200, 1, 1), 10, "AF 1"),
+    ];
+
+    let flights: Vec<String> = flight::marketing_flights(segment, &data).iter().map(|id| id.to_string()).collect();
+    assert!(flights == ["KL9876", "AF4567", "DL30A"]);
+
+    assert!(data[0].codeshare_flights().len() == 3 && data[2].codeshare_flights().is_empty());
+    assert!(FlightId::parse("U2 1234") == Some(FlightId::new("U2", 1234)));
+    assert!(FlightId::parse("AFR12") == Some(FlightId::new("AFR", 12)));
+    assert!(FlightId::parse("AF").is_none() && FlightId::parse("AF12345").is_none() && FlightId::parse("AF12AB").is_none());
+
+    let plain = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 0100 032Y001Z0007 000").unwrap();
+    assert!(flight::marketing_flights(&plain.segments()[0], &data[3 ..]).is_empty());
+}
@@ -0,0 +1,58 @@
+extern crate iata;
+
+use iata::date;
+use iata::datetime::TimeWithOffset;
+use iata::flight::FlightKey;
+use iata::flight_status::*;
+
+fn time(src: &str) -> TimeWithOffset {
+    TimeWithOffset::parse(src).unwrap()
+}
+
+#[test]
+fn transitions() {
+    use iata::flight_status::FlightStatus::*;
+
+    assert!(Scheduled.can_transition_to(Arrived));
+    assert!(!Scheduled.can_transition_to(Scheduled));
+    assert!(Delayed.can_transition_to(Delayed));
+    assert!(!Departed.can_transition_to(Boarding));
+    assert!(!Airborne.can_transition_to(Cancelled));
+    assert!(Cancelled.can_transition_to(Scheduled) && !Cancelled.can_transition_to(Boarding));
+    assert!(Arrived.is_final() && Diverted.is_operating());
+    assert!(Diverted.to_string() == "diverted");
+}
+
+#[test]
+fn tracker() {
+    let day = date::from_ymd(2017, 1, 1).unwrap();
+    let su100 = FlightKey::new("SU", 100, None, day, "JFK");
+    let su102 = FlightKey::new("SU", 102, None, day, "JFK");
+
+    let mut tracker = StatusTracker::new();
+    tracker.apply(&su100, FlightEvent::Delayed(time("2330"))).unwrap();
+    tracker.apply(&su100, FlightEvent::Delayed(time("0015/1"))).unwrap();
+    tracker.apply(&su100, FlightEvent::OffBlock(time("0020/1"))).unwrap();
+    tracker.apply(&su100, FlightEvent::Diverted("led ".into())).unwrap();
+
+    let state = tracker.apply(&su100, FlightEvent::Arrived(time("0950/1"))).unwrap().clone();
+    assert!(state.status() == FlightStatus::Arrived);
+    assert!(state.estimated_departure() == Some(time("0015/1")));
+    assert!(state.off_block().unwrap().date_from(&day) == date::from_ymd(2017, 1, 2).unwrap());
+    assert!(state.airborne().is_none() && state.diverted_to() == Some("LED"));
+
+    let err = tracker.apply(&su100, FlightEvent::Airborne(time("1000/1"))).unwrap_err();
+    assert!(err == StatusError { flight: su100.clone(), from: FlightStatus::Arrived, to: FlightStatus::Airborne });
+    assert!(err.to_string() == "flight SU100/2017-01-01/JFK can't move from arrived to airborne");
+    assert!(tracker.get(&su100).unwrap().on_block() == Some(time("0950/1")));
+
+    assert!(tracker.apply(&su102, FlightEvent::Reinstated).is_err());
+    assert!(tracker.get(&su102).is_none());
+    tracker.apply(&su102, FlightEvent::Cancelled).unwrap();
+    tracker.apply(&su102, FlightEvent::Reinstated).unwrap();
+    assert!(tracker.get(&su102) == Some(&FlightState::new()));
+
+    assert!(tracker.len() == 2);
+    assert!(tracker.with_status(FlightStatus::Arrived).collect::<Vec<_>>() == [&su100]);
+    assert!(tracker.remove(&su100).is_some() && tracker.len() == 1);
+}
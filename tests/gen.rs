@@ -0,0 +1,22 @@
+#![cfg(feature = "gen")]
+
+extern crate iata;
+
+use iata::bcbp::*;
+use iata::gen::Generator;
+
+#[test]
+fn generated_passes_parse() {
+    for src in Generator::new(1).take(500) {
+        let bcbp = BCBP::from(&src).unwrap_or_else(|e| panic!("{:?}: {}", src, e));
+        assert!(!bcbp.segments().is_empty());
+    }
+}
+
+#[test]
+fn deterministic() {
+    let a: Vec<String> = Generator::new(7).take(5).collect();
+    let b: Vec<String> = Generator::new(7).take(5).collect();
+
+    assert_eq!(a, b);
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "geojson")]
+
+extern crate iata;
+extern crate serde_json;
+
+use serde_json::Value;
+
+use iata::bcbp::BCBP;
+use iata::clock::FixedClock;
+use iata::date;
+use iata::geojson;
+use iata::station::StationTable;
+
+#[test]
+fn route() {
+    let pass = BCBP::from("M3JOHN/SMITH          EABCDEF JFKSVOSU 1234A182Y001Z0007 000ABCDEF SVOXXXSU 0010 183Y002A0008 000ABCDEF SVOCDGSU 2454 000Y002A0009 000").unwrap();
+    let clock = FixedClock(date::from_ordinal(2017, 150).unwrap());
+
+    let route: Value = serde_json::from_str(&geojson::route_with(&pass, &StationTable::builtin(), &clock)).unwrap();
+    assert!(route["type"] == "FeatureCollection");
+
+    // The leg to an airport missing from the table is left out
+    let features = route["features"].as_array().unwrap();
+    assert!(features.len() == 2);
+
+    let jfk = &features[0];
+    assert!(jfk["geometry"]["type"] == "LineString");
+    assert!(jfk["geometry"]["coordinates"][0] == serde_json::json!([-73.7781, 40.6413]));
+    assert!(jfk["geometry"]["coordinates"][1] == serde_json::json!([37.4146, 55.9726]));
+    assert!(jfk["properties"]["flight"] == "SU1234A");
+    assert!(jfk["properties"]["carrier"] == "SU");
+    assert!(jfk["properties"]["from"] == "JFK" && jfk["properties"]["to"] == "SVO");
+    assert!(jfk["properties"]["date"] == "2017-07-01");
+
+    assert!(features[1]["properties"]["to"] == "CDG");
+    assert!(features[1]["properties"]["date"].is_null());
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "ics")]
+
+extern crate chrono;
+extern crate iata;
+
+use chrono::NaiveTime;
+
+use iata::bcbp::BCBP;
+use iata::clock::FixedClock;
+use iata::date;
+use iata::ics;
+use iata::station::StationTable;
+
+fn clock() -> FixedClock {
+    FixedClock(date::from_ordinal(2017, 150).unwrap())
+}
+
+#[test]
+fn calendar() {
+    let pass = BCBP::from("M2JOHN/SMITH          EABCDEF JFKSVOSU 1234A182Y001Z0007 000ABCDEF XXXSVOSU 0010 183Y002A0008 000").unwrap();
+    let calendar = ics::calendar_with(&pass, &StationTable::builtin(), &clock());
+
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    assert!(calendar.matches("BEGIN:VEVENT").count() == 2);
+
+    assert!(calendar.contains("UID:SU1234A-20170701-JFK-7\r\n"));
+    assert!(calendar.contains("DTSTAMP:20170530T000000Z\r\n"));
+    assert!(calendar.contains("DTSTART;VALUE=DATE:20170701\r\nDTEND;VALUE=DATE:20170702\r\n"));
+    assert!(calendar.contains("LOCATION:New York John F. Kennedy (JFK)\r\n"));
+    assert!(calendar.contains("DESCRIPTION:Booking ABCDEF\\, seat 1Z\\, sequence 7\r\n"));
+
+    // Airports missing from the table are located by code
+    assert!(calendar.contains("SUMMARY:SU0010 XXX→SVO\r\n"));
+    assert!(calendar.contains("LOCATION:XXX\r\n"));
+}
+
+#[test]
+fn departure_time() {
+    let pass = BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A182Y001Z0007 000").unwrap();
+    let stations = StationTable::builtin();
+    let departure = NaiveTime::from_hms_opt(18, 30, 0);
+
+    let event = ics::event(&pass.segments()[0], &stations, &clock(), departure).unwrap();
+    assert!(event.contains("DTSTART:20170701T223000Z\r\n"));
+    assert!(event.contains("DTEND:2017"));
+    assert!(!event.contains("VALUE=DATE"));
+
+    // Without a flight day there is no date to put the event on
+    let undated = BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A000Y001Z0007 000").unwrap();
+    assert!(ics::event(&undated.segments()[0], &stations, &clock(), departure).is_none());
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "instrument")]
+
+extern crate iata;
+
+use std::sync::Mutex;
+
+use iata::bcbp::*;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The counter is process wide, so tests counting allocations run one at a time
+static COUNTING: Mutex<()> = Mutex::new(());
+
+#[test]
+fn instrumented_parse() {
+    let _counting = COUNTING.lock().unwrap();
+    let src = "]Q3M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ^10AGIWVC5EH7JXYZ";
+    let (bcbp, stats) = BCBP::from_instrumented(src, ParseOptions::lenient());
+
+    assert!(bcbp.unwrap().trailing() == Some("XYZ"));
+    assert!(stats.allocations > 0);
+    assert!(stats.sections == SectionSizes {
+        symbology: 3,
+        mandatory: 23,
+        legs: 37,
+        unique: 28,
+        repeated: 44,
+        airline_data: 11,
+        security: 14,
+        trailing: 3,
+    });
+    assert!(stats.sections.total() == src.len());
+
+    let (bcbp, stats) = BCBP::from_instrumented("M1JOHN", ParseOptions::default());
+    assert!(bcbp.is_err());
+    assert!(stats.sections == SectionSizes::default());
+}
+
+#[test]
+fn validate_only_allocations() {
+    let inputs: [&[u8]; 4] = [
+        b"M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ^10AGIWVC5EH7J",
+        b"M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 0X0",
+        b"M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 006>6Z0",
+        b"M1JOHN",
+    ];
+
+    let _counting = COUNTING.lock().unwrap();
+
+    for input in inputs.iter() {
+        let before = CountingAllocator::allocations();
+        let result = validate_only(input);
+        let allocations = CountingAllocator::allocations() - before;
+
+        assert!(allocations == 0, "{:?} allocated {} times", result, allocations);
+    }
+}
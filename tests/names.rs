@@ -0,0 +1,37 @@
+extern crate iata;
+
+use iata::names::*;
+
+#[test]
+fn scores() {
+    assert!(match_score("smith /  john", "SMITH / JOHN") == 100);
+    assert!(match_score("SMITH/JOHN", "JONES/JOHN") == 0);
+    assert!(match_score("SMITH/JOHN", "SMITH/JANE") == 50);
+
+    assert!(match_score("SMITH/JOHN MR", "SMITH/JOHN") == 90);
+    assert!(match_score("Ångström/Björn", "ANGSTROM/BJORN") == 90);
+    assert!(match_score("STRASSE/JOSÉ", "STRAßE/JOSE") == 90);
+    assert!(match_score("O'NEIL-BROWN/ANN", "ONEIL BROWN/ANN") == 90);
+
+    assert!(match_score("SMITH/JOHN ALBERT", "SMITH/J A") == 80);
+    assert!(match_score("SMITH/JOHN", "SMITH/J") == 80);
+    assert!(match_score("VERYLONGLASTNAMEFOR/JOHN", "VERYLONGLASTNAMEFOR/") == 80);
+    assert!(match_score("SMITH/J", "SMITH") == 50);
+}
+
+#[test]
+fn tolerances() {
+    let strict = Tolerance::strict();
+
+    assert!(match_score_with("smith/john", "SMITH/JOHN", &strict) == 100);
+    assert!(match_score_with("SMITH/JOHN MR", "SMITH/JOHN", &strict) == 50);
+    assert!(match_score_with("MÜLLER/ANNA", "MUELLER/ANNA", &strict) == 0);
+    assert!(match_score_with("SMITH/JOHN", "SMITH/J", &strict) == 50);
+
+    let titles = Tolerance { titles: true, ..Tolerance::strict() };
+    assert!(match_score_with("SMITH/JOHN MR", "SMITH/JOHN", &titles) == 90);
+    assert!(match_score_with("MÜLLER/ANNA MRS", "MUELLER/ANNA", &titles) == 0);
+
+    assert!(is_field_truncation("VERYLONGLASTNAMEFOR/JOHN", "VERYLONGLASTNAMEFOR/"));
+    assert!(!is_field_truncation("SMITH/JOHN", "SMITH/JO"));
+}
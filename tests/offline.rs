@@ -0,0 +1,50 @@
+#![cfg(feature = "offline")]
+
+extern crate iata;
+
+use std::env;
+use std::fs;
+
+use iata::offline::*;
+use iata::station::StationTable;
+
+#[test]
+fn snapshot_files() {
+    let mut data = ReferenceData::builtin();
+    data.airlines.insert("ZZ", "ZZZ");
+    data.mct.insert("SVO".into(), ConnectTime { domestic: 50, international: 90 });
+    data.keys.insert("boarding-token".into(), vec![0, 1, 0xfe, 0xff]);
+
+    let path = env::temp_dir().join(format!("iata-reference-{}.gz", std::process::id()));
+    data.export(&path).unwrap();
+    let loaded = ReferenceData::import(&path);
+    let first = fs::read(&path).unwrap();
+    data.export(&path).unwrap();
+    let second = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let loaded = loaded.unwrap();
+    assert!(first == second);
+    assert!(loaded.stations.len() == StationTable::builtin().len());
+    assert!(loaded.stations.get("JFK") == data.stations.get("JFK"));
+    assert!(loaded.airlines.icao("ZZ") == Some("ZZZ") && loaded.airlines.iata("AFL") == Some("SU"));
+    assert!(loaded.min_connect_time("svo", false) == Some(50));
+    assert!(loaded.min_connect_time("JFK", false).is_none());
+    assert!(loaded.keys == data.keys);
+}
+
+#[test]
+fn bad_snapshots() {
+    match ReferenceData::read_from(&b"not gzip"[..]) {
+        Err(SnapshotError::Io(_)) => {},
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+
+    let mut data = ReferenceData::new();
+    data.keys.insert("tab\tname".into(), vec![1]);
+
+    match data.write_to(Vec::new()) {
+        Err(SnapshotError::Field(ref f)) if f == "tab\tname" => {},
+        other => panic!("{:?}", other),
+    }
+}
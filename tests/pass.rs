@@ -0,0 +1,53 @@
+extern crate iata;
+
+use iata::bcbp::{BCBP, BuildError};
+use iata::pass::{Pass, PayloadError, PayloadFormat};
+
+const SRC: &str = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+
+#[test]
+fn payload_formats() {
+    let pass = Pass::new(BCBP::from(SRC).unwrap());
+
+    for &format in [PayloadFormat::Text, PayloadFormat::Pdf417, PayloadFormat::Aztec].iter() {
+        assert!(pass.payload(format).unwrap() == SRC);
+    }
+
+    let wallet = pass.payload(PayloadFormat::WalletJson).unwrap();
+    assert!(wallet == format!("{{\"format\":\"PKBarcodeFormatPDF417\",\"message\":\"{}\",\"messageEncoding\":\"iso-8859-1\",\"altText\":\"JOHN/SMITH JORDAN\"}}", SRC));
+}
+
+#[test]
+fn payload_signature() {
+    let bcbp = BCBP::from(&format!("{}^10COLDSIGNATURE", SRC)).unwrap();
+    assert!(Pass::new(bcbp.clone()).payload(PayloadFormat::Text).unwrap().ends_with("^10COLDSIGNATURE"));
+
+    let pass = Pass::new(bcbp).with_signature('1', "GIWVC5EH7J");
+    assert!(pass.payload(PayloadFormat::Text).unwrap() == format!("{}^10AGIWVC5EH7J", SRC));
+    assert!(pass.payload(PayloadFormat::Aztec).unwrap().ends_with("^10AGIWVC5EH7J"));
+    assert!(pass.payload(PayloadFormat::WalletJson).unwrap().contains("^10AGIWVC5EH7J"));
+
+    // The wrapped pass keeps its own security section
+    assert!(pass.bcbp().security_data() == Some("OLDSIGNATURE"));
+}
+
+#[test]
+fn payload_capacity() {
+    let pass = Pass::new(BCBP::from(SRC).unwrap()).with_signature('1', "GIWVC5EH7J").with_capacity(64);
+    let overflow = PayloadError::Capacity { len: 74, capacity: 64 };
+
+    assert!(pass.payload(PayloadFormat::Pdf417).err() == Some(overflow.clone()));
+    assert!(pass.payload(PayloadFormat::Aztec).err() == Some(overflow.clone()));
+    assert!(pass.payload(PayloadFormat::WalletJson).err() == Some(overflow.clone()));
+    assert!(overflow.to_string() == "pass takes 74 characters, the symbol holds 64");
+
+    // Text isn't printed in a symbol
+    assert!(pass.payload(PayloadFormat::Text).is_ok());
+    assert!(pass.clone().with_capacity(74).payload(PayloadFormat::Pdf417).is_ok());
+}
+
+#[test]
+fn payload_build_error() {
+    let pass = Pass::new(BCBP::new());
+    assert!(pass.payload(PayloadFormat::Text).err() == Some(PayloadError::Build(BuildError::SegmentsCount(0))));
+}
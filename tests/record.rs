@@ -0,0 +1,33 @@
+extern crate iata;
+
+use iata::record::*;
+use iata::spec;
+
+#[test]
+fn spec_layout() {
+    let layout = Layout::from_items(spec::MANDATORY);
+    assert!(layout.len() == 23);
+
+    let record = layout.parse("M1JOHN/SMITH JORDAN   EABCDEF").unwrap();
+    assert!(record.get("Passenger Name") == Some("JOHN/SMITH JORDAN   "));
+    assert!(record.rest() == "ABCDEF");
+
+    let e = layout.slice("M1JOHN").unwrap_err();
+    assert!(e == RecordError { field: "Passenger Name", offset: 2, kind: RecordErrorKind::Missing });
+
+    let e = layout.parse("MXJOHN/SMITH JORDAN   E").unwrap_err();
+    assert!(e.kind == RecordErrorKind::Charset && e.offset == 1);
+}
+
+#[test]
+fn character_offsets() {
+    let layout = Layout::new(vec![
+        Field::new("name", 0, 3, spec::Charset::Any),
+        Field::new("code", 3, 2, spec::Charset::Alpha).optional(),
+    ]);
+
+    let record = layout.parse("ÅÄÖAB").unwrap();
+    assert!(record.get("name") == Some("ÅÄÖ"));
+    assert!(record.get("code") == Some("AB"));
+    assert!(layout.parse("ÅÄÖ").unwrap().get("code").is_none());
+}
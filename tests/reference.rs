@@ -0,0 +1,33 @@
+#![cfg(feature = "reference")]
+
+extern crate iata;
+
+use iata::reference::*;
+
+#[test]
+fn agrees_on_samples() {
+    let samples = [
+        "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M4VERYLONGESTLASTNAMEDEABCDEF JFKSVOSU 1234 207          000ABCDEF SVOLEDSU 5678 210          000ABCDEF LEDSVOSU 9876 215          000ABCDEF SVOJFKSU 1357 215          000",
+        "M3JOHN/SMITH          EABCDEF JFKSVOSK 1234 123M014C0050 35D>5180O 0276BSK              2A55559467513980 SK                         *30600000K09         ABCDEF SVOFRASU 5678 135Y013A0012 3372A55559467513990 SU SU 12345678             09         ABCDEF FRAJFKSU 9876 231Y022F0052 3372A55559467513990 SU SU 12345678             09         ",
+    ];
+
+    for src in samples.iter() {
+        assert!(decode(src).is_some());
+        assert!(compare(src).is_empty(), "{:?}", compare(src));
+    }
+}
+
+#[test]
+fn reports_divergence() {
+    let src = "M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 0ZZ";
+
+    assert!(decode(src).is_none());
+    assert!(compare(src).is_empty());
+
+    // Spaces in the last name are allowed by the guide, but not by the parser
+    let src = "M1VAN DYKE/JOHN       EABCDEF JFKSVOSU 1234A001Y001Z0007 000";
+
+    assert!(decode(src).is_some());
+    assert!(compare(src)[0].field == "result");
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "report")]
+
+extern crate iata;
+
+use iata::bcbp::BCBP;
+use iata::report::*;
+
+#[test]
+fn csv() {
+    let passes = [
+        BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap(),
+        BCBP::from("M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 00A>60000\"X,Y").unwrap(),
+    ];
+
+    let mut out = Vec::new();
+    write_csv(passes.iter(), &mut out).unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], CSV_COLUMNS.join(","));
+    assert_eq!(lines[1], "JOHN,SMITH JORDAN,E,,,,1,ABCDEF,JFK,SVO,SU,1234A,1,Y,1Z,7,0,");
+    assert_eq!(lines[2], "JOHN,SMITH,E,6,,,1,ABCDEF,JFK,SVO,SU,1234A,1,Y,1Z,7,0,\"\"\"X,Y\"");
+}
+
+#[test]
+fn corpus_stats() {
+    use iata::bcbp::ParseOptions;
+
+    let mut stats = CorpusStats::new();
+
+    stats.add("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000", ParseOptions::strict());
+    stats.add("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ", ParseOptions::strict());
+    stats.add("M1JOHN/SMITH", ParseOptions::strict());
+
+    assert!(stats.total == 3 && stats.parsed == 2 && stats.failed() == 1);
+    assert!(stats.versions[&None] == 1 && stats.versions[&Some('5')] == 1);
+    assert!(stats.airlines["SU"] == 2);
+    assert!(stats.items[&236] == 1 && !stats.items.contains_key(&25));
+    assert!(stats.errors.values().sum::<usize>() == 1);
+
+    let out = stats.to_string();
+    assert!(out.starts_with("passes: 3, parsed: 2, failed: 1\n"));
+    assert!(!out.contains("JOHN") && !out.contains("ABCDEF") && !out.contains("12345678"));
+}
+
+#[test]
+fn validate_corpus_report() {
+    let inputs = [
+        "M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 000",
+        "M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 008>5000000",
+        "M1JOHN/SMITH          EABCDEF JFKSVOSU 1234A001Y001Z0007 000TRAILING",
+        "garbage",
+    ];
+
+    let report = validate_corpus(inputs.iter());
+    assert!(report.inputs.len() == 4);
+
+    assert!(report.inputs[0].findings.is_empty());
+    assert!(report.inputs[0].airline.as_deref() == Some("SU"));
+    assert!(report.inputs[0].severity().is_none());
+
+    assert!(report.inputs[1].version == Some('5'));
+    assert!(report.inputs[1].is_valid());
+    assert!(report.inputs[1].severity() == Some(Severity::Warning));
+    assert!(report.inputs[1].warnings().any(|f| f.code == "outdated_version"));
+
+    assert!(!report.inputs[2].is_valid());
+    assert!(report.inputs[2].errors().any(|f| f.code == "trailing_data"));
+
+    assert!(report.inputs[3].airline.is_none());
+    assert!(report.inputs[3].errors().any(|f| f.code == "unreadable"));
+
+    assert!(report.valid() == 2);
+    assert!(report.count(Severity::Error) == 2);
+    assert!(report.count(Severity::Warning) == 1);
+
+    let mut out = Vec::new();
+    report.write_csv(&mut out).unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+
+    assert!(lines[0] == VALIDATION_CSV_COLUMNS.join(","));
+    assert!(lines.len() == 1 + report.inputs.iter().map(|i| i.findings.len()).sum::<usize>());
+    assert!(lines[1].starts_with("1,5,SU,warning,outdated_version,9,"));
+}
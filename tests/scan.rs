@@ -0,0 +1,33 @@
+extern crate iata;
+
+use iata::bcbp::{BCBP, SecuritySection};
+use iata::redact::RedactionRules;
+use iata::scan::ScanEvent;
+
+const PASS: &str = "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ^164GIWVC5EH7JNT684FVNJ91W2QA4DVN5J8K4F0L0GEQ3DF5TGBN8709HKT5D3DW3GBHFCVHMY7J5T6HFR41W2QA4DVN5J8K4F0L0GE";
+
+#[test]
+fn redaction() {
+    let event = ScanEvent::new(BCBP::from(PASS).unwrap(), 1483272000, "GATE-B12-1").with_location("FRA/B12");
+    assert!(event.location.as_ref().map(|l| l.as_ref()) == Some("FRA/B12"));
+
+    let kept = event.clone().redacted(&RedactionRules::none());
+    assert!(kept.bcbp.expect_build() == event.bcbp.expect_build());
+
+    let redacted = event.redacted(&RedactionRules { pnr: false, ..RedactionRules::all() });
+    let bcbp = &redacted.bcbp;
+
+    assert!(bcbp.name() == "XXXX/XXXXX");
+    assert!(bcbp.segments()[0].pnr() == "ABCDEF");
+    assert!(bcbp.segments()[0].frequent_flyer_number() == Some("XXXXXXXX"));
+    assert!(bcbp.segments()[0].airline_data().unwrap().chars().all(|c| c == 'X' || c == ' '));
+    assert!(bcbp.segments()[0].seat() == "13A");
+
+    match bcbp.security() {
+        Some(&SecuritySection::Standard { kind, ref data }) => assert!(kind == '1' && data.chars().all(|c| c == 'X')),
+        _ => panic!(),
+    }
+
+    assert!(BCBP::from(&bcbp.expect_build()).is_ok());
+    assert!(!format!("{:?}", redacted).contains("12345678"));
+}
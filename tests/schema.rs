@@ -0,0 +1,76 @@
+#![cfg(feature = "serde")]
+
+extern crate iata;
+extern crate serde_json;
+
+use iata::bcbp::BCBP;
+use iata::bcbp::schema::{PassV1, SCHEMA_V1};
+
+// Documents of the `bcbp/1` schema as published, must never change
+const MANDATORY_V1: &str = r#"{"schema":"bcbp/1","name_last":"JOHN","name_first":"SMITH JORDAN","ticket_indicator":"E","conditional_version":null,"passenger_description":null,"checkin_source":null,"boarding_pass_source":null,"boarding_pass_issue_day":null,"boarding_pass_issuer":null,"document_type":null,"legs":[{"pnr":"ABCDEF","from":"JFK","to":"SVO","carrier":"SU","flight":"1234A","flight_day":1,"compartment":"Y","seat":"1Z","sequence":7,"passenger_status":"0","frequent_flyer_airline":null,"frequent_flyer_number":null,"airline_data":null}],"security_type":null,"security_data":null}"#;
+
+const CONDITIONAL_V1: &str = r#"{"schema":"bcbp/1","name_last":"JOHN","name_first":"SMITH","ticket_indicator":"E","conditional_version":"5","passenger_description":"0","checkin_source":"O","boarding_pass_source":null,"boarding_pass_issue_day":276,"boarding_pass_issuer":"SK","document_type":"B","legs":[{"pnr":"ABCDEF","from":"SVO","to":"FRA","carrier":"SU","flight":"5678","flight_day":135,"compartment":"Y","seat":"13A","sequence":12,"passenger_status":"3","frequent_flyer_airline":"SU","frequent_flyer_number":"12345678","airline_data":"09         "}],"security_type":null,"security_data":null}"#;
+
+#[test]
+fn v1_serialization() {
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    assert_eq!(serde_json::to_string(&bcbp.to_schema_v1()).unwrap(), MANDATORY_V1);
+
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ").unwrap();
+    assert_eq!(serde_json::to_string(&bcbp.to_schema_v1()).unwrap(), CONDITIONAL_V1);
+}
+
+#[test]
+fn v1_compatibility() {
+    for doc in [MANDATORY_V1, CONDITIONAL_V1].iter() {
+        let pass: PassV1 = serde_json::from_str(doc).unwrap();
+        assert_eq!(pass.schema, SCHEMA_V1);
+    }
+
+    // Readers of bcbp/1 ignore fields added later
+    let extended = MANDATORY_V1.replacen("\"schema\":\"bcbp/1\",", "\"schema\":\"bcbp/1\",\"added_later\":true,", 1);
+    let pass: PassV1 = serde_json::from_str(&extended).unwrap();
+    assert_eq!(pass.legs[0].flight, "1234A");
+}
+
+#[test]
+fn v1_airline_fields() {
+    use iata::bcbp::ExtensionRegistry;
+    use iata::airline_use::FixedLayout;
+
+    let bcbp = BCBP::from("M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ").unwrap();
+
+    let mut registry = ExtensionRegistry::new();
+    registry.register(" su", FixedLayout::new("0", &[("class", 1)]));
+
+    let json = serde_json::to_string(&bcbp.to_schema_v1_with(&registry)).unwrap();
+    assert!(json.contains(r#""airline_data":"09         ","airline_fields":{"class":"9"}}"#));
+
+    // Legs without a decoder leave the field out, as plain `to_schema_v1()` does
+    let json = serde_json::to_string(&bcbp.to_schema_v1_with(&ExtensionRegistry::new())).unwrap();
+    assert_eq!(json, CONDITIONAL_V1);
+
+    let pass: PassV1 = serde_json::from_str(CONDITIONAL_V1).unwrap();
+    assert!(pass.legs[0].airline_fields.is_none());
+
+    // Carriers are matched regardless of case and padding on both sides
+    let mut value = serde_json::to_value(&bcbp).unwrap();
+    value["segments"][0]["airline"] = "su ".into();
+    let padded: BCBP = serde_json::from_value(value).unwrap();
+    assert!(registry.decode(&padded.segments()[0]).unwrap()["class"] == "9");
+}
+
+#[test]
+fn scan_event() {
+    use iata::scan::ScanEvent;
+
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let event = ScanEvent::new(bcbp, 1483272000, "GATE-B12-1").with_location("JFK/B12");
+
+    let json = serde_json::to_string(&event).unwrap();
+    assert!(json.contains("\"scanned_at\":1483272000") && json.contains("\"location\":\"JFK/B12\""));
+
+    let back: ScanEvent = serde_json::from_str(&json).unwrap();
+    assert!(back.bcbp.expect_build() == event.bcbp.expect_build());
+    assert!(back.device_id == "GATE-B12-1");
+}
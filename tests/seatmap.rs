@@ -0,0 +1,63 @@
+extern crate iata;
+
+use iata::seatmap::*;
+use iata::bcbp::{BCBP, PassTemplate, TemplateError};
+use iata::date;
+use iata::flight::FlightKey;
+
+#[test]
+fn seats() {
+    assert!(Seat::parse("012a") == Some(Seat::new(12, 'A')));
+    assert!(Seat::parse(" 1C ") == Some(Seat::new(1, 'C')));
+    assert!(Seat::parse("").is_none());
+    assert!(Seat::parse("000A").is_none());
+    assert!(Seat::parse("1234A").is_none());
+    assert!(Seat::parse("12").is_none());
+    assert!(Seat::new(7, 'k').to_string() == "7K");
+}
+
+#[test]
+fn seat_map() {
+    let map = SeatMap::new(1, 30, "abcdef")
+        .with_missing_row(13)
+        .with_blocked(Seat::new(1, 'A'))
+        .with_blocked(Seat::new(40, 'A'));
+
+    assert!(map.capacity() == 29 * 6 - 1);
+    assert!(map.contains(Seat::new(1, 'A')) && map.is_blocked(Seat::new(1, 'A')));
+    assert!(map.check("30F") == Ok(Seat::new(30, 'F')));
+    assert!(map.check("31A") == Err(SeatError::NotOnAircraft(Seat::new(31, 'A'))));
+    assert!(map.check("12G") == Err(SeatError::NotOnAircraft(Seat::new(12, 'G'))));
+    assert!(map.check("XX") == Err(SeatError::Malformed("XX".into())));
+
+    let mut types = AircraftTypes::new();
+    types.register("320", map.clone());
+    assert!(types.is_registered(" 320") && !types.is_registered("321"));
+
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y013A0007 000").unwrap();
+    assert!(bcbp.segments()[0].check_seat(&map) == Err(SeatError::NotOnAircraft(Seat::new(13, 'A'))));
+
+    let blank = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y    0007 000").unwrap();
+    assert!(blank.segments()[0].check_seat(&map) == Ok(None));
+}
+
+#[test]
+fn template_seat_map() {
+    let key = FlightKey::new("SU", 100, None, date::from_ymd(2017, 1, 1).unwrap(), "JFK");
+    let map = SeatMap::new(1, 2, "AB").with_blocked(Seat::new(2, 'B'));
+    let mut template = PassTemplate::new(key, "SVO").with_seat_map(map);
+
+    let results: Vec<_> = template.issue_all(vec![
+        ("DOE/JANE", "GHIJKL", "1A", 1),
+        ("DOE/JOHN", "GHIJKL", "1C", 2),
+        ("DOE/JILL", "GHIJKL", "2B", 3),
+        ("DOE/JACK", "GHIJKL", "", 4),
+    ]).map(|r| r.err()).collect();
+
+    assert!(results == [
+        None,
+        Some(TemplateError::Seat(SeatError::NotOnAircraft(Seat::new(1, 'C')))),
+        Some(TemplateError::Seat(SeatError::Blocked(Seat::new(2, 'B')))),
+        None,
+    ]);
+}
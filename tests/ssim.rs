@@ -0,0 +1,338 @@
+extern crate iata;
+
+use iata::date;
+use iata::ssim::*;
+
+fn record(body: &str, serial: u32) -> String {
+    format!("{:<194}{:06}", body, serial)
+}
+
+fn data_set(separator: &str) -> String {
+    let records = [
+        record(&format!("{:<40}1{:>151}", "1AIRLINE STANDARD SCHEDULE DATA SET", "001"), 1),
+        "0".repeat(200),
+        record("2LSU  0008W17 01NOV1731MAR1820OCT17Aeroflot winter schedule      20OCT17C", 2),
+        record("3 SU 01000101J29OCT1724MAR181234567 SVO08000800+0300D LED09300930+0300  320FJCYBMHQKLV", 3),
+        record("3 SU 01000201J29OCT1700XXX00 2 4 6  LED11001100+0300  SVO12301230+0300D 321Y", 4),
+        record("4 SU 01000101J              AB010SVOLEDAF 1234", 5),
+        record(&format!("{:<187}{:06}E", "5 SU 20OCT17", 5), 6),
+    ];
+
+    records.iter().map(|r| format!("{}{}", r, separator)).collect()
+}
+
+#[test]
+fn read_records() {
+    let date = |y, m, d| date::from_ymd(y, m, d).unwrap();
+
+    for separator in ["\n", "\r\n", ""].iter() {
+        let data = data_set(separator);
+        let records = SsimReader::from_reader(data.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(records.len() == 6);
+        assert!(records.iter().map(Record::serial).collect::<Vec<_>>() == vec![1, 2, 3, 4, 5, 6]);
+
+        match records[0] {
+            Record::Header(ref h) => {
+                assert!(h.title() == "AIRLINE STANDARD SCHEDULE DATA SET");
+                assert!(h.seasons() == 1);
+                assert!(h.dataset_serial() == 1);
+            },
+            _ => panic!(),
+        }
+
+        match records[1] {
+            Record::Carrier(ref c) => {
+                assert!(c.time_mode() == TimeMode::Local);
+                assert!(c.airline() == "SU");
+                assert!(c.season() == "W17");
+                assert!(c.valid_from() == Some(date(2017, 11, 1)));
+                assert!(c.valid_to() == Some(date(2018, 3, 31)));
+                assert!(c.status() == Some('C'));
+            },
+            _ => panic!(),
+        }
+
+        match records[2] {
+            Record::Leg(ref l) => {
+                assert!(l.airline() == "SU" && l.flight_number() == 100 && l.suffix().is_none());
+                assert!(l.itinerary_variation() == 1 && l.leg_sequence() == 1);
+                assert!(l.service_type() == Some('J'));
+                assert!(l.period_from() == Some(date(2017, 10, 29)));
+                assert!(l.period_to() == Some(date(2018, 3, 24)));
+                assert!(l.days().to_string() == "1234567");
+                assert!((l.departure(), l.departure_time(), l.departure_utc_variation()) == ("SVO", "0800", "+0300"));
+                assert!((l.arrival(), l.arrival_time()) == ("LED", "0930"));
+                assert!(l.aircraft_type() == "320");
+                assert!(l.prbd() == "FJCYBMHQKLV");
+            },
+            _ => panic!(),
+        }
+
+        match records[3] {
+            Record::Leg(ref l) => {
+                assert!(l.period_to().is_none());
+                assert!(l.days().contains(2) && !l.days().contains(1));
+                assert!(l.days().to_string() == " 2 4 6 ");
+            },
+            _ => panic!(),
+        }
+
+        match records[4] {
+            Record::SegmentData(ref s) => {
+                assert!(s.dei() == 10);
+                assert!((s.board_point(), s.off_point()) == ("SVO", "LED"));
+                assert!(s.value() == "AF 1234");
+            },
+            _ => panic!(),
+        }
+
+        match records[5] {
+            Record::Trailer(ref t) => {
+                assert!(t.release_date() == Some(date(2017, 10, 20)));
+                assert!(t.serial_check() == 5);
+                assert!(t.continuation() == Some('E'));
+            },
+            _ => panic!(),
+        }
+    }
+}
+
+#[test]
+fn skip_and_report() {
+    let mut data = data_set("\n");
+    data.push_str(&format!("{}\n", record("3 SU 01XX0101J29OCT1724MAR181234567 SVO08000800+0300D LED09300930+0300  320", 7)));
+    data.push_str(&format!("{}\n", record("9", 8)));
+    data.push_str("3 SU short\n");
+    data.push_str("3 SU Ä\n");
+    data.push_str(&format!("{}\n", record("3 SU 02000101J29OCT1724MAR181234567 SVO08000800+0300D LED09300930+0300  320", 10)));
+
+    let mut reader = SsimReader::from_reader(data.as_bytes());
+    let results: Vec<_> = reader.by_ref().collect();
+
+    assert!(results.len() == 11);
+    assert!(reader.records_read() == 11);
+
+    let errors: Vec<_> = results.iter().filter_map(|r| r.as_ref().err()).collect();
+    assert!(errors.iter().map(|e| e.record).collect::<Vec<_>>() == vec![7, 8, 9, 10]);
+
+    match errors[0].kind {
+        ReadErrorKind::Record(ref e) => assert!(e.field == "Flight Number" && e.offset == 7),
+        _ => panic!(),
+    }
+    assert!(matches!(errors[1].kind, ReadErrorKind::RecordType('9')));
+    assert!(matches!(errors[2].kind, ReadErrorKind::Record(_)));
+    assert!(matches!(errors[3].kind, ReadErrorKind::Encoding));
+    assert!(errors[0].to_string().starts_with("record 7: field 'Flight Number'"));
+
+    match results[10] {
+        Ok(Record::Leg(ref l)) => assert!(l.flight_number() == 200),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn write_data_set() {
+    let data = data_set("\n");
+    let mut writer = SsimWriter::new(Vec::new()).with_dataset_serial(7);
+
+    assert!(writer.write_leg(&Leg::new("SU", 100, 1, 1)).is_err());
+
+    for record in SsimReader::from_reader(data.as_bytes()) {
+        writer.write(&record.unwrap()).unwrap();
+    }
+
+    let mut carrier = Carrier::new("AF");
+    carrier.set_season("W17");
+    carrier.set_validity(date::from_ymd(2017, 10, 29), None);
+    writer.begin_carrier(&carrier).unwrap();
+
+    let mut leg = Leg::new("AF", 1145, 1, 1);
+    leg.set_service_type('J');
+    leg.set_days(Days::parse("1 3 5"));
+    leg.set_departure("CDG", "0945", "+0100");
+    leg.set_arrival("SVO", "1500", "+0300");
+    leg.set_aircraft_type("320");
+    writer.write_leg(&leg).unwrap();
+    assert!(writer.serial() == 8);
+
+    let written = String::from_utf8(writer.finish().unwrap()).unwrap();
+    let lines: Vec<_> = written.lines().collect();
+
+    // Header block, SU block of 5 records, AF block of 3 records and padding
+    assert!(lines.len() == 15);
+    assert!(lines.iter().all(|l| l.len() == RECORD_LEN));
+    assert!(lines[1 .. 5].iter().chain(lines[13 ..].iter()).all(|l| l.bytes().all(|b| b == b'0')));
+
+    let records = SsimReader::from_reader(written.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+    let original = SsimReader::from_reader(data.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert!(records.iter().map(Record::serial).collect::<Vec<_>>() == (1 ..= 9).collect::<Vec<_>>());
+    assert!(records[1 .. 5] == original[1 .. 5]);
+
+    match records[0] {
+        Record::Header(ref h) => assert!(h.dataset_serial() == 7),
+        _ => panic!(),
+    }
+
+    match records[5] {
+        Record::Trailer(ref t) => {
+            assert!(t.airline() == "SU" && t.serial_check() == 5 && t.continuation() == Some('E'));
+            assert!(t.release_date() == Some(date::from_ymd(2017, 10, 20).unwrap()));
+        },
+        _ => panic!(),
+    }
+
+    match records[7] {
+        Record::Leg(ref l) => {
+            assert!(l.flight_number() == 1145 && l.days().to_string() == "1 3 5  ");
+            assert!((l.departure(), l.arrival_time(), l.aircraft_type()) == ("CDG", "1500", "320"));
+            assert!(l.period_from().is_none());
+        },
+        _ => panic!(),
+    }
+
+    match records[8] {
+        Record::Trailer(ref t) => assert!(t.airline() == "AF" && t.serial_check() == 8),
+        _ => panic!(),
+    }
+
+    let mut writer = SsimWriter::new(Vec::new()).without_line_breaks();
+    writer.begin_carrier(&carrier).unwrap();
+    assert!(writer.finish().unwrap().len() == 10 * RECORD_LEN);
+}
+
+#[test]
+fn apply_messages() {
+    let date = |m, d| date::from_ymd(2017, m, d);
+
+    let data = data_set("\n");
+    let records = SsimReader::from_reader(data.as_bytes()).map(Result::unwrap);
+    let mut schedule = Schedule::from_records(records);
+
+    assert!(schedule.legs().len() == 2);
+
+    let su100 = FlightId::new("SU", 100);
+    let november = Period::new(date(11, 1), date(11, 30), Days::parse("1 3"));
+
+    // Cancel Mondays and Wednesdays of November, leg 1 runs daily, leg 2 never on these days
+    let changes = schedule.apply(&Message::ssm(su100.clone(), november, Action::Cancel)).unwrap();
+
+    assert!(changes.len() == 1);
+    match changes[0] {
+        Change::Removed(ref leg) => {
+            assert!(leg.departure() == "SVO");
+            assert!(leg.days().to_string() == "1 3    ");
+            assert!((leg.period_from(), leg.period_to()) == (date(11, 1), date(11, 30)));
+        },
+        _ => panic!(),
+    }
+
+    let svo: Vec<_> = schedule.legs().iter().filter(|l| l.departure() == "SVO").collect();
+    let mut periods: Vec<_> = svo.iter().map(|l| (l.period_from(), l.period_to(), l.days().to_string())).collect();
+    periods.sort();
+
+    assert!(periods == vec![
+        (date(10, 29), date(10, 31), "1234567".to_string()),
+        (date(11, 1), date(11, 30), " 2 4567".to_string()),
+        (date(12, 1), date::from_ymd(2018, 3, 24), "1234567".to_string()),
+    ]);
+
+    // Leg 2 is open ended, only its LED departure is retimed
+    let time = Action::Time { station: "LED".into(), departure: Some("1115".into()), arrival: None };
+    let message = Message::asm(su100.clone(), date(11, 7).unwrap(), time).with_time_mode(TimeMode::Local);
+    let changes = schedule.apply(&message).unwrap();
+
+    match changes[..] {
+        [Change::Updated { ref before, ref after }] => {
+            assert!(before.departure_time() == "1100" && after.departure_time() == "1115");
+            assert!(after.arrival_time() == "1230");
+            assert!(after.period_from() == date(11, 7) && after.period_to() == date(11, 7));
+        },
+        _ => panic!(),
+    }
+    assert!(schedule.legs().len() == 6);
+
+    // Monday November 6th is cancelled on the SVO leg and the LED leg never operates on Mondays
+    let equipment = Action::Equipment("321".into());
+    let e = schedule.apply(&Message::asm(su100.clone(), date(11, 6).unwrap(), equipment)).unwrap_err();
+    assert!(e == ApplyError::UnknownFlight(su100.clone()));
+    assert!(schedule.legs().len() == 6);
+
+    let af = FlightId::new("AF", 1145);
+    let mut leg = Leg::new("AF", 1145, 1, 1);
+    leg.set_departure("CDG", "0945", "+0100");
+
+    let messages = vec![
+        Message::ssm(af.clone(), Period::new(date(11, 1), None, Days::all()), Action::New(vec![leg.clone()])),
+        Message::ssm(af.clone(), Period::new(date(12, 1), None, Days::all()), Action::New(vec![leg.clone()])),
+        Message::ssm(af.clone(), november, Action::New(vec![Leg::new("AF", 1, 1, 1)])),
+        Message::asm(af.clone(), date(11, 10).unwrap(), Action::Equipment("319".into())),
+    ];
+
+    let log = schedule.apply_all(&messages);
+
+    assert!(log.rejected == vec![(1, ApplyError::Duplicate(af.clone())), (2, ApplyError::InvalidLegs(af.clone()))]);
+    assert!(log.changes.len() == 2);
+    match log.changes[0] {
+        Change::Added(ref leg) => assert!(leg.period_from() == date(11, 1) && leg.period_to().is_none()),
+        _ => panic!(),
+    }
+    assert!(schedule.legs().iter().filter(|l| l.airline() == "AF").count() == 3);
+}
+
+#[test]
+fn time_modes() {
+    let evening = ScheduledTime::parse("2230", TimeMode::Local, "-0500").unwrap();
+    assert!(evening.to_utc().hhmm() == "0330" && evening.to_utc().day_offset() == 1);
+    assert!(evening.to_utc().to_string() == "0330Z+1");
+    assert!(evening.to_utc().to_local() == evening);
+    assert!(evening.to_string() == "2230L");
+    assert!(ScheduledTime::parse("2460", TimeMode::Utc, "+0000").is_none());
+    assert!(ScheduledTime::parse("2400", TimeMode::Utc, "+0000").unwrap().to_string() == "0000Z+1");
+    assert!(parse_utc_variation("-0530") == Some(-330));
+    assert!(parse_utc_variation("0530").is_none());
+
+    let data = data_set("\n");
+    let legs: Vec<_> = SsimReader::from_reader(data.as_bytes())
+        .filter_map(|r| match r.unwrap() {
+            Record::Leg(leg) => Some(leg),
+            _ => None,
+        })
+        .collect();
+
+    // The carrier record gives local times
+    assert!(legs[0].time_mode() == TimeMode::Local);
+    assert!(legs[0].scheduled_departure().unwrap().to_utc().hhmm() == "0500");
+
+    let mut writer = SsimWriter::new(Vec::new());
+    writer.begin_carrier(&Carrier::new("SU")).unwrap();
+    writer.write_leg(&legs[0]).unwrap();
+
+    let written = writer.finish().unwrap();
+    let utc = SsimReader::from_reader(&written[..])
+        .filter_map(|r| match r.unwrap() {
+            Record::Leg(leg) => Some(leg),
+            _ => None,
+        })
+        .next()
+        .unwrap();
+
+    assert!(utc.time_mode() == TimeMode::Utc);
+    assert!((utc.departure_time(), utc.arrival_time()) == ("0500", "0630"));
+
+    // UTC messages are converted to the local times of the schedule
+    let mut schedule = Schedule::from_legs(legs);
+    let time = Action::Time { station: "SVO".into(), departure: Some("0600".into()), arrival: Some("0xxx".into()) };
+    let message = Message::asm(FlightId::new("SU", 100), date::from_ymd(2017, 11, 7).unwrap(), time);
+    assert!(schedule.apply(&message) == Err(ApplyError::InvalidTime("0xxx".into())));
+
+    let time = Action::Time { station: "SVO".into(), departure: Some("0600".into()), arrival: None };
+    let message = Message { action: time, ..message };
+    match schedule.apply(&message).unwrap()[..] {
+        [Change::Updated { ref after, .. }] => assert!(after.departure_time() == "0900"),
+        _ => panic!(),
+    }
+}
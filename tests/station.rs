@@ -0,0 +1,96 @@
+#![cfg(feature = "station")]
+
+extern crate chrono;
+extern crate iata;
+
+use chrono::{Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use iata::bcbp::{BCBP, DocCheckReason};
+use iata::station::*;
+
+#[test]
+fn dst_transitions() {
+    let stations = StationTable::builtin();
+    let lhr = stations.get("lhr").unwrap();
+
+    let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+    // Clocks went forward at 01:00 on March 26th 2017
+    let spring = NaiveDate::from_ymd_opt(2017, 3, 26).unwrap();
+    assert!(lhr.local_to_utc(spring, time(1, 30)) == Err(NonexistentTime { date: spring, time: time(1, 30) }));
+    assert!(lhr.local_to_utc(spring, time(2, 30)) == Ok(Utc.with_ymd_and_hms(2017, 3, 26, 1, 30, 0).unwrap()));
+
+    // And back at 02:00 on October 29th, 01:30 happened twice
+    let autumn = NaiveDate::from_ymd_opt(2017, 10, 29).unwrap();
+    assert!(lhr.local_to_utc(autumn, time(1, 30)) == Ok(Utc.with_ymd_and_hms(2017, 10, 29, 0, 30, 0).unwrap()));
+
+    let svo = stations.get("SVO").unwrap();
+    let utc = Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap();
+    assert!(svo.utc_to_local(utc) == NaiveDate::from_ymd_opt(2017, 1, 1).unwrap().and_time(time(15, 0)));
+}
+
+#[test]
+fn distances() {
+    let stations = StationTable::builtin();
+
+    let lhr_cdg = distance_km(stations.get("LHR").unwrap(), stations.get("CDG").unwrap());
+    assert!((lhr_cdg - 347.0).abs() < 1.0);
+
+    let bcbp = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    let leg = &bcbp.segments()[0];
+
+    assert!((stations.segment_distance_km(leg).unwrap() - 7481.0).abs() < 1.0);
+    assert!(stations.segment_block_time(leg) == Some(Duration::minutes(591)));
+
+    let unknown = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKXXXSU 1234A001Y001Z0007 000").unwrap();
+    assert!(stations.segment_distance_km(&unknown.segments()[0]).is_none());
+}
+
+#[test]
+fn route_types() {
+    use iata::flight::{CityPair, RouteType};
+
+    let stations = StationTable::builtin();
+    let country = |code: &str| stations.country(code);
+
+    assert!(stations.country("svo") == Some("RU"));
+    assert!(CityPair::new("SVO", "LED").is_domestic(country) == Some(true));
+
+    let bcbp = BCBP::from("M2JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000DEFGHI SVOLEDSU 0010 002Y002A0008 000").unwrap();
+    assert!(bcbp.segments()[0].is_international(country) == Some(true));
+    assert!(bcbp.segments()[1].is_international(country) == Some(false));
+    assert!(bcbp.route_type(country) == Some(RouteType::Mixed));
+    assert!(bcbp.route_type(country).unwrap().is_international());
+
+    let domestic = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF SVOLEDSU 0010 001Y001Z0007 000").unwrap();
+    assert!(domestic.route_type(country) == Some(RouteType::Domestic));
+
+    let unknown = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKXXXSU 1234A001Y001Z0007 000").unwrap();
+    assert!(unknown.route_type(country).is_none());
+}
+
+#[test]
+fn doc_checks() {
+    let stations = StationTable::builtin();
+    let country = |code: &str| stations.country(code);
+
+    let checked_in = "M1JOHN/SMITH          EABCDEF SVOFRASU 5678 135Y013A0012 353>5180O 0276BSK              2A55559467513990 SU SU 12345678             09         ";
+    assert!(BCBP::from(checked_in).unwrap().requires_doc_check(country).is_none());
+
+    let receipt = BCBP::from(&checked_in.replace("0276BSK", "0276ISK")).unwrap();
+    assert!(receipt.requires_doc_check(country) == Some(DocCheckReason::ItineraryReceipt));
+
+    let bcbp = BCBP::from("M2JOHN/SMITH JORDAN   EABCDEF SVOLEDSU 0010 001Y001Z0007 000DEFGHI LEDJFKSU 0020 002Y002A0008 700").unwrap();
+    let reason = bcbp.requires_doc_check(country).unwrap();
+    assert!(reason == DocCheckReason::Standby { leg: 1 });
+    assert!(reason.leg() == Some(1));
+    assert!(reason.to_string() == "standby on international leg 2");
+
+    let domestic = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF SVOLEDSU 0010 001Y001Z0007 000").unwrap();
+    assert!(domestic.requires_doc_check(country).is_none());
+
+    let international = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000").unwrap();
+    assert!(international.requires_doc_check(country) == Some(DocCheckReason::NotCheckedIn { leg: 0 }));
+
+    let unknown = BCBP::from("M1JOHN/SMITH JORDAN   EABCDEF JFKXXXSU 1234A001Y001Z0007 100").unwrap();
+    assert!(unknown.requires_doc_check(country) == Some(DocCheckReason::UnknownRoute { leg: 0 }));
+}
@@ -0,0 +1,58 @@
+extern crate iata;
+
+use iata::tax::*;
+
+#[test]
+fn amounts() {
+    let eur = Currency::parse("eur").unwrap();
+    let jpy = Currency::parse("JPY").unwrap();
+    let kwd = Currency::parse("KWD").unwrap();
+
+    assert!(Currency::parse("EU").is_none());
+    assert!(eur.decimals() == 2 && jpy.decimals() == 0 && kwd.decimals() == 3);
+
+    assert!(Amount::parse("12.345", eur).unwrap().minor() == 1235);
+    assert!(Amount::parse("12.344", eur).unwrap().minor() == 1234);
+    assert!(Amount::parse("-12.345", eur).unwrap().minor() == -1235);
+    assert!(Amount::parse("7", eur).unwrap().minor() == 700);
+    assert!(Amount::parse("1500.5", jpy).unwrap().minor() == 1501);
+    assert!(Amount::parse("1.2", kwd).unwrap().to_string() == "1.200 KWD");
+    assert!(Amount::parse("-0.05", eur).unwrap().to_string() == "-0.05 EUR");
+    assert!(Amount::parse("1.2.3", eur).is_none());
+    assert!(Amount::parse(".5", eur).is_none());
+
+    let sum = Amount::new(100, eur).checked_add(&Amount::new(1, jpy));
+    assert!(sum == Err(TaxError::CurrencyMismatch(eur, jpy)));
+}
+
+#[test]
+fn breakdown() {
+    let usd = Currency::parse("USD").unwrap();
+    let tax = |code: &str, country: Option<&str>, value: &str| Tax {
+        code: TaxCode::parse(code).unwrap(),
+        country: country.map(|c| Country::parse(c).unwrap()),
+        amount: Amount::parse(value, usd).unwrap(),
+    };
+
+    let mut taxes = TaxBreakdown::new();
+    assert!(taxes.total() == Ok(None));
+
+    taxes.push(tax("US", Some("US"), "18.60"));
+    taxes.push(tax("XF", Some("US"), "4.50"));
+    taxes.push(tax("XF", Some("US"), "4.50"));
+    taxes.push(tax("YQ", None, "120.00"));
+    taxes.push(tax("GB", Some("GB"), "102.00"));
+
+    assert!(taxes.total() == Ok(Some(Amount::new(24960, usd))));
+
+    let by_code = taxes.by_code().unwrap();
+    assert!(by_code[&TaxCode::parse("XF").unwrap()] == Amount::new(900, usd));
+    assert!(by_code.len() == 4);
+
+    let by_country = taxes.by_country().unwrap();
+    assert!(by_country[&Country::parse("US").unwrap()] == Amount::new(2760, usd));
+    assert!(by_country.len() == 2);
+
+    taxes.push(Tax { amount: Amount::new(100, Currency::parse("EUR").unwrap()), ..taxes.taxes()[0] });
+    assert!(taxes.total().is_err());
+}
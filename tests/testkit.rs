@@ -0,0 +1,34 @@
+#![cfg(feature = "testkit")]
+
+extern crate iata;
+
+use iata::bcbp::{Error, ParseOptions};
+use iata::testkit::*;
+
+#[test]
+fn corpus() {
+    let file = "# id\texpect\tdata\r\n\
+                M-001\tVALID\tM1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000\r\n\
+                \n\
+                M-002\tinvalid\tX1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000\n\
+                M-003\tVALID\tM1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 00\n\
+                M-004\tINVALID\tM1JOHN/SMITH JORDAN   EABCDEF JFKSVOSU 1234A001Y001Z0007 000\n";
+
+    let cases = read_cases(file.as_bytes()).unwrap();
+
+    assert_eq!(cases.len(), 4);
+    assert_eq!(cases[0].id, "M-001");
+    assert!(!cases[1].expect_valid);
+    assert!(cases[2].data.ends_with("0007 00"));
+
+    let stats = run(&cases, ParseOptions::strict());
+
+    assert_eq!(stats.total, 4);
+    assert_eq!(stats.passed, 2);
+    assert_eq!(stats.failed(), 2);
+    assert_eq!(stats.pass_rate(), 0.5);
+    assert_eq!(stats.failures[0], Failure { id: "M-003".into(), expect_valid: true, error: Some(Error::DataLength) });
+    assert_eq!(stats.failures[1].error, None);
+
+    assert!(read_cases("M-001 VALID M1".as_bytes()).is_err());
+}
@@ -0,0 +1,40 @@
+extern crate iata;
+
+use iata::bcbp::BCBP;
+
+/// Shared with other BCBP implementations, keep the file free of crate specifics
+const VECTORS: &str = include_str!("vectors/bcbp.tsv");
+
+#[test]
+fn shared_vectors() {
+    let mut checked = 0;
+
+    for line in VECTORS.lines().filter(|l| !l.starts_with('#') && !l.is_empty()) {
+        let fields: Vec<&str> = line.split('\t').collect();
+        assert!(fields.len() == 13, "malformed vector {:?}", line);
+
+        let bcbp = BCBP::from(fields[0]).unwrap_or_else(|e| panic!("{:?}: {}", fields[0], e));
+        let leg: usize = fields[1].parse().unwrap();
+        let s = &bcbp.segments()[leg];
+
+        let actual = [
+            bcbp.name(),
+            s.pnr().to_string(),
+            s.src_airport().to_string(),
+            s.dst_airport().to_string(),
+            s.airline().to_string(),
+            s.flight_code().to_string(),
+            s.flight_day().to_string(),
+            s.compartment().to_string(),
+            s.seat().to_string(),
+            s.sequence().to_string(),
+            s.pax_status().to_string(),
+        ];
+
+        assert!(actual[..] == fields[2 ..], "{:?} leg {}: {:?}", fields[0], leg, actual);
+
+        checked += 1;
+    }
+
+    assert!(checked == 7);
+}